@@ -33,19 +33,20 @@ fn main() {
     let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")
         .expect("Failed to create USDC address");
 
-    let simple_swap = Swap {
+    let simple_swap = Swap::new(
         // The protocol component data comes from tycho-indexer
-        component: ProtocolComponent {
+        ProtocolComponent {
             id: "0xB4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc".to_string(),
             protocol_system: "uniswap_v2".to_string(),
             ..Default::default()
         },
-        token_in: weth.clone(),
-        token_out: usdc.clone(),
+        weth.clone(),
+        usdc.clone(),
         // Split defines the fraction of the amount to be swapped. A value of 0 indicates 100% of
-        // the amount or the total remaining balance.
-        split: 0f64,
-    };
+        // the amount or the total remaining balance. An absolute amount can be set instead with
+        // `with_amount_in`.
+        0f64,
+    );
 
     // Then we create a solution object with the previous swap
     let solution = Solution {
@@ -88,48 +89,48 @@ fn main() {
     let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f")
         .expect("Failed to create DAI address");
 
-    let swap_weth_dai = Swap {
-        component: ProtocolComponent {
+    let swap_weth_dai = Swap::new(
+        ProtocolComponent {
             id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
             protocol_system: "uniswap_v2".to_string(),
             ..Default::default()
         },
-        token_in: weth.clone(),
-        token_out: dai.clone(),
-        split: 0.5f64,
-    };
-    let swap_weth_wbtc = Swap {
-        component: ProtocolComponent {
+        weth.clone(),
+        dai.clone(),
+        0.5f64,
+    );
+    let swap_weth_wbtc = Swap::new(
+        ProtocolComponent {
             id: "0xBb2b8038a1640196FbE3e38816F3e67Cba72D940".to_string(),
             protocol_system: "uniswap_v2".to_string(),
             ..Default::default()
         },
-        token_in: weth.clone(),
-        token_out: wbtc.clone(),
+        weth.clone(),
+        wbtc.clone(),
         // This represents the remaining 50%, but to avoid any rounding errors we set this to
         // 0 to signify "the remainder of the WETH value". It should still be very close to 50%
-        split: 0f64,
-    };
-    let swap_dai_usdc = Swap {
-        component: ProtocolComponent {
+        0f64,
+    );
+    let swap_dai_usdc = Swap::new(
+        ProtocolComponent {
             id: "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5".to_string(),
             protocol_system: "uniswap_v2".to_string(),
             ..Default::default()
         },
-        token_in: dai.clone(),
-        token_out: usdc.clone(),
-        split: 0f64,
-    };
-    let swap_wbtc_usdc = Swap {
-        component: ProtocolComponent {
+        dai.clone(),
+        usdc.clone(),
+        0f64,
+    );
+    let swap_wbtc_usdc = Swap::new(
+        ProtocolComponent {
             id: "0x004375Dff511095CC5A197A54140a24eFEF3A416".to_string(),
             protocol_system: "uniswap_v2".to_string(),
             ..Default::default()
         },
-        token_in: wbtc.clone(),
-        token_out: usdc.clone(),
-        split: 0f64,
-    };
+        wbtc.clone(),
+        usdc.clone(),
+        0f64,
+    );
     let mut complex_solution = solution.clone();
     complex_solution.swaps = vec![swap_weth_dai, swap_weth_wbtc, swap_dai_usdc, swap_wbtc_usdc];
 
@@ -41,6 +41,11 @@ module.exports = {
             accounts: [process.env.PRIVATE_KEY],
             chainId: 130
         },
+        anvil: {
+            url: process.env.RPC_URL,
+            accounts: [process.env.PRIVATE_KEY],
+            chainId: 31337
+        },
         worldchain: {
             url: process.env.RPC_URL,
             accounts: [process.env.PRIVATE_KEY],
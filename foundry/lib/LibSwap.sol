@@ -40,4 +40,28 @@ library LibSwap {
         executor = address(uint160(bytes20(swap[5:25])));
         protocolData = swap[25:];
     }
+
+    /**
+     * @dev Returns arguments required to perform a split swap with an absolute input amount.
+     * An `amountIn` of 0 means the `split` percentage should be used instead.
+     */
+    function decodeSplitSwapWithAmount(bytes calldata swap)
+        internal
+        pure
+        returns (
+            uint8 tokenInIndex,
+            uint8 tokenOutIndex,
+            uint24 split,
+            uint256 amountIn,
+            address executor,
+            bytes calldata protocolData
+        )
+    {
+        tokenInIndex = uint8(swap[0]);
+        tokenOutIndex = uint8(swap[1]);
+        split = uint24(bytes3(swap[2:5]));
+        amountIn = uint256(bytes32(swap[5:37]));
+        executor = address(uint160(bytes20(swap[37:57])));
+        protocolData = swap[57:];
+    }
 }
@@ -57,6 +57,13 @@ const executors_to_deploy = {
             exchange: "CurveExecutor", args: [
                 "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE"
             ]
+        },
+        // Args: WETH, Permit2
+        {
+            exchange: "NativeWrapperExecutor", args: [
+                "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                "0x000000000022D473030F116dDEE9F6B43aC78BA3"
+            ]
         }
     ],
     "base": [
@@ -101,6 +108,13 @@ const executors_to_deploy = {
         // Args: Pool manager
         {exchange: "UniswapV4Executor", args: ["0x498581ff718922c3f8e6a244956af099b2652b2b"]},
         {exchange: "BalancerV2Executor", args: []},
+        // Args: WETH, Permit2
+        {
+            exchange: "NativeWrapperExecutor", args: [
+                "0x4200000000000000000000000000000000000006",
+                "0x000000000022D473030F116dDEE9F6B43aC78BA3"
+            ]
+        },
     ],
     "unichain": [
         // Args: Factory, Pool Init Code Hash, Fee BPS
@@ -120,6 +134,13 @@ const executors_to_deploy = {
         },
         // Args: Pool manager
         {exchange: "UniswapV4Executor", args: ["0x1f98400000000000000000000000000000000004"]},
+        // Args: WETH, Permit2
+        {
+            exchange: "NativeWrapperExecutor", args: [
+                "0x4200000000000000000000000000000000000006",
+                "0x000000000022D473030F116dDEE9F6B43aC78BA3"
+            ]
+        },
     ],
 }
 
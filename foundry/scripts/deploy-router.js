@@ -13,6 +13,10 @@ async function main() {
         // permit2 address is the same as on ethereum
         permit2 = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
         weth = "0x4200000000000000000000000000000000000006";
+    } else if (network === "anvil") {
+        // A plain local node: the addresses are only checked to be non-zero
+        permit2 = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+        weth = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
     } else if (network === "unichain") {
         // permit2 address is the same as on ethereum
         permit2 = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
@@ -35,6 +39,11 @@ async function main() {
     await router.deployed();
     console.log(`TychoRouter deployed to: ${router.address}`);
 
+    if (network === "anvil") {
+        // There is nothing to verify the contract on
+        return;
+    }
+
     // Verify on Tenderly
     try {
         console.log("Verifying contract on Tenderly...");
@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: BUSL-1.1
+pragma solidity ^0.8.26;
+
+import "../lib/IWETH.sol";
+import "../lib/bytes/LibPrefixLengthEncodedByteArray.sol";
+import "@openzeppelin/contracts/interfaces/IERC4626.sol";
+import "@openzeppelin/contracts/token/ERC20/IERC20.sol";
+import "@openzeppelin/contracts/token/ERC20/utils/SafeERC20.sol";
+import "@openzeppelin/contracts/utils/Address.sol";
+
+error ActionDispatcher__UnapprovedTarget(address target);
+error ActionDispatcher__NonContractTarget();
+error ActionDispatcher__InvalidDataLength();
+error ActionDispatcher__InvalidActionType(uint8 actionType);
+error ActionDispatcher__InvalidToken(address token);
+error ActionDispatcher__InvalidShares(uint256 totalBps);
+error ActionDispatcher__ActionAfterTerminalAction();
+error ActionDispatcher__UnconsumedAmount(uint256 amount, uint256 consumed);
+
+/**
+ * @title ActionDispatcher - Dispatch actions on the funds held by the router
+ * @author PropellerHeads Devs
+ * @dev Executes an ordered list of actions on a token amount held by this
+ *  contract, before or after the swaps. Each action consumes the output of
+ *  the previous one.
+ *
+ *  Actions are encoded as a PLE encoded byte array. Each action starts with
+ *  its type (1 byte), followed by its parameters:
+ *  - WRAP / UNWRAP: no parameters.
+ *  - VAULT_DEPOSIT: vault address (20 bytes). Deposits into an ERC-4626
+ *    vault and outputs the vault shares.
+ *  - DISTRIBUTE: one or more (recipient (20 bytes), share in bps (2 bytes))
+ *    pairs whose shares add up to 10000. Sends out the whole amount.
+ *  - CALL: target address (20 bytes) followed by the calldata. Approves the
+ *    amount to a whitelisted target and calls it. The target must consume
+ *    the whole amount, otherwise the call reverts.
+ *
+ *  DISTRIBUTE and CALL are terminal actions: they leave no funds in this
+ *  contract, so they must be the last action of the list.
+ */
+contract ActionDispatcher {
+    using SafeERC20 for IERC20;
+    using LibPrefixLengthEncodedByteArray for bytes;
+
+    enum ActionType {
+        WRAP,
+        UNWRAP,
+        VAULT_DEPOSIT,
+        DISTRIBUTE,
+        CALL
+    }
+
+    uint256 private constant ACTION_BPS_DENOMINATOR = 10_000;
+
+    IWETH private immutable _actionsWeth;
+
+    mapping(address => bool) public actionTargets;
+
+    event ActionTargetSet(address indexed target);
+    event ActionTargetRemoved(address indexed target);
+
+    constructor(address weth) {
+        _actionsWeth = IWETH(weth);
+    }
+
+    /**
+     * @dev Adds an approved target of CALL actions if it is a contract.
+     * @param target address of the contract to be called
+     */
+    function _setActionTarget(address target) internal {
+        if (target.code.length == 0) {
+            revert ActionDispatcher__NonContractTarget();
+        }
+        actionTargets[target] = true;
+        emit ActionTargetSet(target);
+    }
+
+    /**
+     * @dev Removes an approved target of CALL actions.
+     * @param target address of the contract to be called
+     */
+    function _removeActionTarget(address target) internal {
+        delete actionTargets[target];
+        emit ActionTargetRemoved(target);
+    }
+
+    /**
+     * @dev Executes the given actions, in order, on `amount` of `token`.
+     *  Use `address(0)` for native ETH.
+     *
+     * @return tokenOut The token held after the last action.
+     * @return amountOut The amount of `tokenOut` held after the last action,
+     *  or sent out by the last action if it is a terminal action.
+     * @return terminated Whether the last action is a terminal action.
+     */
+    function _executeActions(
+        bytes calldata actions,
+        address token,
+        uint256 amount
+    )
+        internal
+        returns (address tokenOut, uint256 amountOut, bool terminated)
+    {
+        bytes calldata action;
+        tokenOut = token;
+        amountOut = amount;
+        while (actions.length > 0) {
+            if (terminated) {
+                revert ActionDispatcher__ActionAfterTerminalAction();
+            }
+            (action, actions) = actions.next();
+            if (action.length == 0) {
+                revert ActionDispatcher__InvalidDataLength();
+            }
+            uint8 actionType = uint8(action[0]);
+            bytes calldata params = action[1:];
+
+            if (actionType == uint8(ActionType.WRAP)) {
+                tokenOut = _wrapAction(tokenOut, amountOut, params);
+            } else if (actionType == uint8(ActionType.UNWRAP)) {
+                tokenOut = _unwrapAction(tokenOut, amountOut, params);
+            } else if (actionType == uint8(ActionType.VAULT_DEPOSIT)) {
+                (tokenOut, amountOut) =
+                    _vaultDepositAction(tokenOut, amountOut, params);
+            } else if (actionType == uint8(ActionType.DISTRIBUTE)) {
+                _distributeAction(tokenOut, amountOut, params);
+                terminated = true;
+            } else if (actionType == uint8(ActionType.CALL)) {
+                amountOut = _callAction(tokenOut, amountOut, params);
+                terminated = true;
+            } else {
+                revert ActionDispatcher__InvalidActionType(actionType);
+            }
+        }
+    }
+
+    function _wrapAction(address token, uint256 amount, bytes calldata params)
+        internal
+        returns (address)
+    {
+        if (params.length != 0) revert ActionDispatcher__InvalidDataLength();
+        if (token != address(0)) revert ActionDispatcher__InvalidToken(token);
+        _actionsWeth.deposit{value: amount}();
+        return address(_actionsWeth);
+    }
+
+    function _unwrapAction(
+        address token,
+        uint256 amount,
+        bytes calldata params
+    ) internal returns (address) {
+        if (params.length != 0) revert ActionDispatcher__InvalidDataLength();
+        if (token != address(_actionsWeth)) {
+            revert ActionDispatcher__InvalidToken(token);
+        }
+        _actionsWeth.withdraw(amount);
+        return address(0);
+    }
+
+    function _vaultDepositAction(
+        address token,
+        uint256 amount,
+        bytes calldata params
+    ) internal returns (address vault, uint256 shares) {
+        if (params.length != 20) revert ActionDispatcher__InvalidDataLength();
+        vault = address(bytes20(params[0:20]));
+        if (token != IERC4626(vault).asset()) {
+            revert ActionDispatcher__InvalidToken(token);
+        }
+        IERC20(token).forceApprove(vault, amount);
+        // slither-disable-next-line calls-loop
+        shares = IERC4626(vault).deposit(amount, address(this));
+    }
+
+    function _distributeAction(
+        address token,
+        uint256 amount,
+        bytes calldata params
+    ) internal {
+        if (params.length == 0 || params.length % 22 != 0) {
+            revert ActionDispatcher__InvalidDataLength();
+        }
+        uint256 nRecipients = params.length / 22;
+        // The shares are validated before sending anything, so that the
+        // rounding remainder of the last recipient can't underflow
+        uint256 totalBps;
+        for (uint256 i = 0; i < nRecipients; i++) {
+            totalBps += uint16(bytes2(params[i * 22 + 20:(i + 1) * 22]));
+        }
+        if (totalBps != ACTION_BPS_DENOMINATOR) {
+            revert ActionDispatcher__InvalidShares(totalBps);
+        }
+        uint256 distributed;
+        for (uint256 i = 0; i < nRecipients; i++) {
+            bytes calldata entry = params[i * 22:(i + 1) * 22];
+            address recipient = address(bytes20(entry[0:20]));
+            uint256 shareBps = uint16(bytes2(entry[20:22]));
+            // The last recipient receives the rounding remainder
+            uint256 share = i == nRecipients - 1
+                ? amount - distributed
+                : (amount * shareBps) / ACTION_BPS_DENOMINATOR;
+            distributed += share;
+            if (token == address(0)) {
+                Address.sendValue(payable(recipient), share);
+            } else {
+                IERC20(token).safeTransfer(recipient, share);
+            }
+        }
+    }
+
+    /**
+     * @dev Calls a whitelisted target with `amount` of `token`. The amount
+     *  consumed is measured from the balance of this contract, and must be at
+     *  least `amount`: a target leaving part of it in this contract reverts.
+     *
+     * @return consumed The amount of `token` consumed by the call.
+     */
+    function _callAction(address token, uint256 amount, bytes calldata params)
+        internal
+        returns (uint256 consumed)
+    {
+        if (params.length < 20) revert ActionDispatcher__InvalidDataLength();
+        address target = address(bytes20(params[0:20]));
+        if (!actionTargets[target]) {
+            revert ActionDispatcher__UnapprovedTarget(target);
+        }
+        uint256 balanceBefore = _actionTokenBalance(token);
+        if (token == address(0)) {
+            Address.functionCallWithValue(target, params[20:], amount);
+        } else {
+            IERC20(token).forceApprove(target, amount);
+            Address.functionCall(target, params[20:]);
+            IERC20(token).forceApprove(target, 0);
+        }
+        uint256 balanceAfter = _actionTokenBalance(token);
+        consumed =
+            balanceBefore > balanceAfter ? balanceBefore - balanceAfter : 0;
+        if (consumed < amount) {
+            revert ActionDispatcher__UnconsumedAmount(amount, consumed);
+        }
+    }
+
+    function _actionTokenBalance(address token)
+        internal
+        view
+        returns (uint256)
+    {
+        return token == address(0)
+            ? address(this).balance
+            : IERC20(token).balanceOf(address(this));
+    }
+}
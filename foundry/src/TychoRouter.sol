@@ -7,10 +7,10 @@ import "../lib/bytes/LibPrefixLengthEncodedByteArray.sol";
 import "@openzeppelin/contracts/access/AccessControl.sol";
 import "@openzeppelin/contracts/token/ERC20/IERC20.sol";
 import "@openzeppelin/contracts/token/ERC20/utils/SafeERC20.sol";
-import "@openzeppelin/contracts/utils/ReentrancyGuard.sol";
 import "@openzeppelin/contracts/utils/Pausable.sol";
 import "@openzeppelin/contracts/utils/Address.sol";
 import "@permit2/src/interfaces/IAllowanceTransfer.sol";
+import "./ActionDispatcher.sol";
 import "./Dispatcher.sol";
 import {LibSwap} from "../lib/LibSwap.sol";
 import {IPoolManager} from "@uniswap/v4-core/src/interfaces/IPoolManager.sol";
@@ -64,8 +64,21 @@ error TychoRouter__AmountOutNotFullyReceived(
 error TychoRouter__MessageValueMismatch(uint256 value, uint256 amount);
 error TychoRouter__InvalidDataLength();
 error TychoRouter__UndefinedMinAmountOut();
+error TychoRouter__InvalidFee(uint256 feeBps);
+error TychoRouter__InvalidSurplusShare(uint256 surplusShareBps);
+error TychoRouter__InvalidOutputs(uint256 nOutputs, uint256 nTokens);
+error TychoRouter__InvalidInputs(uint256 nInputs, uint256 nTokens);
+error TychoRouter__DeadlineExpired(uint256 deadline, uint256 timestamp);
+error TychoRouter__ReentrantCall();
+error TychoRouter__MessageValueAlreadySpent();
+error TychoRouter__InvalidSwapCall(bytes4 selector);
 
-contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
+contract TychoRouter is
+    AccessControl,
+    Dispatcher,
+    ActionDispatcher,
+    Pausable
+{
     IAllowanceTransfer public immutable permit2;
     IWETH private immutable _weth;
 
@@ -83,11 +96,85 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
     bytes32 public constant FUND_RESCUER_ROLE =
         0x912e45d663a6f4cc1d0491d8f046e06c616f40352565ea1cdb86a0e1aaefa41b;
 
+    uint256 private constant BPS_DENOMINATOR = 10_000;
+
+    // Transient storage slots, derived from their names so that they can't collide with the slots of the
+    // Dispatcher or of the delegatecalled executors.
+    // keccak256("tycho.router.reentrancy") - 1
+    uint256 private constant _REENTRANCY_STATUS_SLOT =
+        0x9afe6bd7a12c5a11f127e505c342f980b306fc3047d171ba4714fba6ca3c78bd;
+    // keccak256("tycho.router.delegatedSelector") - 1
+    uint256 private constant _DELEGATED_SELECTOR_SLOT =
+        0x86259ab08d9916965a60666aece9520aaba542acac9b709d9d4dc51dfd28628f;
+    // keccak256("tycho.router.msgValueSpent") - 1
+    uint256 private constant _MSG_VALUE_SPENT_SLOT =
+        0x37394213464df2bd9a09fc473aeefc1a5d1f3a533a8307f950cc10edde6527e4;
+
+    /**
+     * @dev Describes how the output of a swap is distributed by the router.
+     * @param feeBps Integrator fee in basis points of the output amount (after surplus capture).
+     * @param feeRecipient Address receiving the integrator fee.
+     * @param expectedAmountOut Output amount above which the output is considered surplus.
+     * @param surplusRecipient Address receiving the captured surplus.
+     * @param surplusShareBps Share of the surplus sent to `surplusRecipient`, in basis points.
+     * 10000 caps the receiver's amount at `expectedAmountOut`.
+     */
+    struct OutputSettlement {
+        uint16 feeBps;
+        address feeRecipient;
+        uint256 expectedAmountOut;
+        address surplusRecipient;
+        uint16 surplusShareBps;
+    }
+
+    /**
+     * @dev Describes one of the outputs of a multi-output swap.
+     * @param token The address of the output token.
+     * @param minAmountOut The minimum acceptable amount of the output token.
+     * @param receiver The address to receive the output token.
+     */
+    struct SwapOutput {
+        address token;
+        uint256 minAmountOut;
+        address receiver;
+    }
+
+    /**
+     * @dev Describes one of the inputs of a multi-input swap.
+     * @param token The address of the input token.
+     * @param amount The amount of the input token to be swapped.
+     */
+    struct SwapInput {
+        address token;
+        uint256 amount;
+    }
+
     event Withdrawal(
         address indexed token, uint256 amount, address indexed receiver
     );
+    event FeeCharged(
+        address indexed token, uint256 amount, address indexed recipient
+    );
+    event SurplusCaptured(
+        address indexed token, uint256 amount, address indexed recipient
+    );
 
-    constructor(address _permit2, address weth) {
+    /**
+     * @dev Prevents reentrant calls to the router.
+     *
+     * A guarded function may only be entered while another one runs if it is the call the router is
+     * currently delegating to itself (see `_delegateToSelf`), e.g. a swap of a multicall. This bypass is
+     * limited to the selector being delegated, which can only be a swap entry point, `swapWithActions` or
+     * `executeWithDeadline`, and to a single entry per delegation. Reentrant calls from executors,
+     * callbacks or action targets revert.
+     */
+    modifier nonReentrant() {
+        bool delegated = _enterGuard();
+        _;
+        _exitGuard(delegated);
+    }
+
+    constructor(address _permit2, address weth) ActionDispatcher(weth) {
         if (_permit2 == address(0) || weth == address(0)) {
             revert TychoRouter__AddressZero();
         }
@@ -139,7 +226,8 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
             unwrapEth,
             nTokens,
             receiver,
-            swaps
+            swaps,
+            false
         );
     }
 
@@ -196,7 +284,270 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
             unwrapEth,
             nTokens,
             receiver,
-            swaps
+            swaps,
+            false
+        );
+    }
+
+    /**
+     * @notice Executes a split swap where each swap may specify an absolute input amount instead of a split percentage.
+     *         This function enables multi-step swaps, optional ETH wrapping/unwrapping, and validates the output amount
+     *         against a user-specified minimum.
+     *
+     * @dev
+     * - Behaves like `splitSwap`, but every encoded swap carries an extra 32-byte `amountIn` after the split.
+     * - A non-zero `amountIn` is used as the exact input amount of that swap. An `amountIn` of 0 falls back to the
+     *   `split` percentage (or to the remainder if the split is also 0).
+     * - Reverts if the absolute amounts of a token exceed its available balance.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token. Reverts if this condition is not met. This should always be set to avoid losing funds due to slippage.
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param unwrapEth If true, unwraps the resulting WETH into native ETH and sends it to the receiver.
+     * @param nTokens The total number of tokens involved in the swap graph (used to initialize arrays for internal calculations).
+     * @param receiver The address to receive the output tokens.
+     * @param swaps Encoded swap graph data containing details of each swap, including absolute input amounts.
+     *
+     * @return amountOut The total amount of the output token received by the receiver.
+     */
+    function splitSwapWithAmounts(
+        uint256 amountIn,
+        address tokenIn,
+        address tokenOut,
+        uint256 minAmountOut,
+        bool wrapEth,
+        bool unwrapEth,
+        uint256 nTokens,
+        address receiver,
+        bytes calldata swaps
+    ) public payable whenNotPaused nonReentrant returns (uint256 amountOut) {
+        return _splitSwapChecked(
+            amountIn,
+            tokenIn,
+            tokenOut,
+            minAmountOut,
+            wrapEth,
+            unwrapEth,
+            nTokens,
+            receiver,
+            swaps,
+            true
+        );
+    }
+
+    /**
+     * @notice Executes a split swap where each swap may specify an absolute input amount instead of a split percentage.
+     *         This function enables multi-step swaps, optional ETH wrapping/unwrapping, and validates the output amount
+     *         against a user-specified minimum.
+     *
+     * @dev
+     * - Behaves like `splitSwapPermit2`, but every encoded swap carries an extra 32-byte `amountIn` after the split.
+     * - A non-zero `amountIn` is used as the exact input amount of that swap. An `amountIn` of 0 falls back to the
+     *   `split` percentage (or to the remainder if the split is also 0).
+     * - For ERC20 tokens, Permit2 is used to approve and transfer tokens from the caller to the router.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token. Reverts if this condition is not met. This should always be set to avoid losing funds due to slippage.
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param unwrapEth If true, unwraps the resulting WETH into native ETH and sends it to the receiver.
+     * @param nTokens The total number of tokens involved in the swap graph (used to initialize arrays for internal calculations).
+     * @param receiver The address to receive the output tokens.
+     * @param permitSingle A Permit2 structure containing token approval details for the input token. Ignored if `wrapEth` is true.
+     * @param signature A valid signature authorizing the Permit2 approval. Ignored if `wrapEth` is true.
+     * @param swaps Encoded swap graph data containing details of each swap, including absolute input amounts.
+     *
+     * @return amountOut The total amount of the output token received by the receiver.
+     */
+    function splitSwapWithAmountsPermit2(
+        uint256 amountIn,
+        address tokenIn,
+        address tokenOut,
+        uint256 minAmountOut,
+        bool wrapEth,
+        bool unwrapEth,
+        uint256 nTokens,
+        address receiver,
+        IAllowanceTransfer.PermitSingle calldata permitSingle,
+        bytes calldata signature,
+        bytes calldata swaps
+    ) external payable whenNotPaused nonReentrant returns (uint256 amountOut) {
+        // For native ETH, assume funds already in our router. Else, handle approval.
+        if (tokenIn != address(0)) {
+            permit2.permit(msg.sender, permitSingle, signature);
+        }
+
+        return _splitSwapChecked(
+            amountIn,
+            tokenIn,
+            tokenOut,
+            minAmountOut,
+            wrapEth,
+            unwrapEth,
+            nTokens,
+            receiver,
+            swaps,
+            true
+        );
+    }
+
+    /**
+     * @notice Executes a split swap that ends in several output tokens, each one sent to its own receiver.
+     *         This function enables multi-step swaps, optional ETH wrapping, and validates every output amount
+     *         against its user-specified minimum.
+     *
+     * @dev
+     * - The swap graph is encoded like in `splitSwapWithAmounts`: every swap carries a 32-byte `amountIn`.
+     * - The output tokens must occupy the last `outputs.length` token indices of the swap graph, in the same
+     *   order as `outputs`.
+     * - The swaps into an output token must send the tokens directly to the receiver of that output.
+     * - Reverts if any output is below its minimum or if a receiver didn't receive the full amount.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param nTokens The total number of tokens involved in the swap graph (used to initialize arrays for internal calculations).
+     * @param outputs The output tokens, with their minimum amounts and receivers.
+     * @param swaps Encoded swap graph data containing details of each swap, including absolute input amounts.
+     *
+     * @return amountsOut The amount received of each output token, in the same order as `outputs`.
+     */
+    function splitSwapMultiOutput(
+        uint256 amountIn,
+        address tokenIn,
+        bool wrapEth,
+        uint256 nTokens,
+        SwapOutput[] calldata outputs,
+        bytes calldata swaps
+    )
+        public
+        payable
+        whenNotPaused
+        nonReentrant
+        returns (uint256[] memory amountsOut)
+    {
+        return _splitSwapMultiOutputChecked(
+            amountIn, tokenIn, wrapEth, nTokens, outputs, swaps
+        );
+    }
+
+    /**
+     * @notice Executes a split swap that ends in several output tokens, each one sent to its own receiver.
+     *         This function enables multi-step swaps, optional ETH wrapping, and validates every output amount
+     *         against its user-specified minimum.
+     *
+     * @dev
+     * - Behaves like `splitSwapMultiOutput`, but for ERC20 tokens Permit2 is used to approve and transfer tokens
+     *   from the caller to the router.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param nTokens The total number of tokens involved in the swap graph (used to initialize arrays for internal calculations).
+     * @param outputs The output tokens, with their minimum amounts and receivers.
+     * @param permitSingle A Permit2 structure containing token approval details for the input token. Ignored if `wrapEth` is true.
+     * @param signature A valid signature authorizing the Permit2 approval. Ignored if `wrapEth` is true.
+     * @param swaps Encoded swap graph data containing details of each swap, including absolute input amounts.
+     *
+     * @return amountsOut The amount received of each output token, in the same order as `outputs`.
+     */
+    function splitSwapMultiOutputPermit2(
+        uint256 amountIn,
+        address tokenIn,
+        bool wrapEth,
+        uint256 nTokens,
+        SwapOutput[] calldata outputs,
+        IAllowanceTransfer.PermitSingle calldata permitSingle,
+        bytes calldata signature,
+        bytes calldata swaps
+    )
+        external
+        payable
+        whenNotPaused
+        nonReentrant
+        returns (uint256[] memory amountsOut)
+    {
+        // For native ETH, assume funds already in our router. Else, handle approval.
+        if (tokenIn != address(0)) {
+            permit2.permit(msg.sender, permitSingle, signature);
+        }
+
+        return _splitSwapMultiOutputChecked(
+            amountIn, tokenIn, wrapEth, nTokens, outputs, swaps
+        );
+    }
+
+    /**
+     * @notice Executes a split swap that starts from several input tokens and ends in a single output token.
+     *         This function enables multi-step swaps and validates the output amount against a user-specified
+     *         minimum.
+     *
+     * @dev
+     * - The swap graph is encoded like in `splitSwapWithAmounts`: every swap carries a 32-byte `amountIn`.
+     * - The input tokens must occupy the first `inputs.length` token indices of the swap graph, in the same
+     *   order as `inputs`. The output token must be the last token of the swap graph.
+     * - Native ETH is not supported as an input. The function is not payable, so sending ETH reverts.
+     * - Reverts with `TychoRouter__NegativeSlippage` if the output amount is less than `minAmountOut`.
+     *
+     * @param inputs The input tokens and the amount of each one to be swapped.
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token. Reverts if this condition is not met. This should always be set to avoid losing funds due to slippage.
+     * @param nTokens The total number of tokens involved in the swap graph (used to initialize arrays for internal calculations).
+     * @param receiver The address to receive the output tokens.
+     * @param swaps Encoded swap graph data containing details of each swap, including absolute input amounts.
+     *
+     * @return amountOut The total amount of the output token received by the receiver.
+     */
+    function splitSwapMultiInput(
+        SwapInput[] calldata inputs,
+        address tokenOut,
+        uint256 minAmountOut,
+        uint256 nTokens,
+        address receiver,
+        bytes calldata swaps
+    ) public whenNotPaused nonReentrant returns (uint256 amountOut) {
+        return _splitSwapMultiInputChecked(
+            inputs, tokenOut, minAmountOut, nTokens, receiver, swaps
+        );
+    }
+
+    /**
+     * @notice Executes a split swap that starts from several input tokens and ends in a single output token.
+     *         This function enables multi-step swaps and validates the output amount against a user-specified
+     *         minimum.
+     *
+     * @dev
+     * - Behaves like `splitSwapMultiInput`, but Permit2 is used to approve and transfer all the input tokens
+     *   from the caller to the router, with a single batch signature.
+     *
+     * @param inputs The input tokens and the amount of each one to be swapped.
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token. Reverts if this condition is not met. This should always be set to avoid losing funds due to slippage.
+     * @param nTokens The total number of tokens involved in the swap graph (used to initialize arrays for internal calculations).
+     * @param receiver The address to receive the output tokens.
+     * @param permitBatch A Permit2 structure containing token approval details for all the input tokens.
+     * @param signature A valid signature authorizing the Permit2 approvals.
+     * @param swaps Encoded swap graph data containing details of each swap, including absolute input amounts.
+     *
+     * @return amountOut The total amount of the output token received by the receiver.
+     */
+    function splitSwapMultiInputPermit2(
+        SwapInput[] calldata inputs,
+        address tokenOut,
+        uint256 minAmountOut,
+        uint256 nTokens,
+        address receiver,
+        IAllowanceTransfer.PermitBatch calldata permitBatch,
+        bytes calldata signature,
+        bytes calldata swaps
+    ) external whenNotPaused nonReentrant returns (uint256 amountOut) {
+        permit2.permit(msg.sender, permitBatch, signature);
+
+        return _splitSwapMultiInputChecked(
+            inputs, tokenOut, minAmountOut, nTokens, receiver, swaps
         );
     }
 
@@ -393,14 +744,29 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
     }
 
     /**
-     * @notice Internal implementation of the core swap logic shared between splitSwap() and splitSwapPermit2().
+     * @notice Executes a split swap and distributes the output according to `settlement`.
      *
-     * @notice This function centralizes the swap execution logic.
-     * @notice For detailed documentation on parameters and behavior, see the documentation for
-     * splitSwap() and splitSwapPermit2() functions.
+     * @dev
+     * - Behaves like `splitSwapWithAmounts`, but the final swaps must send their output to the router instead of the receiver.
+     * - `settlement.surplusShareBps` of the output exceeding `settlement.expectedAmountOut` is sent to `settlement.surplusRecipient`.
+     * - The integrator fee (`settlement.feeBps` of the remaining output) is deducted and sent to `settlement.feeRecipient`.
+     * - Reverts with `TychoRouter__NegativeSlippage` if the output amount after surplus and fees is less than `minAmountOut`.
+     * - If `unwrapEth` is true, the surplus, the fee and the receiver amount are paid out in native ETH.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token after surplus capture and fees. Reverts if this condition is not met.
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param unwrapEth If true, unwraps the resulting WETH into native ETH before distributing it.
+     * @param nTokens The total number of tokens involved in the swap graph (used to initialize arrays for internal calculations).
+     * @param receiver The address to receive the output tokens.
+     * @param settlement Describes how the output is distributed between the receiver and third parties.
+     * @param swaps Encoded swap graph data containing details of each swap, including absolute input amounts.
      *
+     * @return amountOut The amount of the output token received by the receiver.
      */
-    function _splitSwapChecked(
+    function splitSwapWithSettlement(
         uint256 amountIn,
         address tokenIn,
         address tokenOut,
@@ -409,52 +775,698 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
         bool unwrapEth,
         uint256 nTokens,
         address receiver,
+        OutputSettlement calldata settlement,
         bytes calldata swaps
-    ) internal returns (uint256 amountOut) {
-        if (receiver == address(0)) {
-            revert TychoRouter__AddressZero();
-        }
-        if (minAmountOut == 0) {
-            revert TychoRouter__UndefinedMinAmountOut();
-        }
-
-        // Assume funds are already in the router.
+    ) public payable whenNotPaused nonReentrant returns (uint256 amountOut) {
+        _validateSwapParams(receiver, minAmountOut, settlement);
         if (wrapEth) {
             _wrapETH(amountIn);
-            tokenIn = address(_weth);
-        }
-
-        uint256 initialBalanceTokenOut = _balanceOf(tokenOut, receiver);
-        amountOut = _splitSwap(amountIn, nTokens, swaps);
-
-        if (amountOut < minAmountOut) {
-            revert TychoRouter__NegativeSlippage(amountOut, minAmountOut);
-        }
-
-        if (unwrapEth) {
-            _unwrapETH(amountOut);
-            Address.sendValue(payable(receiver), amountOut);
-        }
-
-        if (tokenIn != tokenOut) {
-            uint256 currentBalanceTokenOut = _balanceOf(tokenOut, receiver);
-            uint256 userAmount = currentBalanceTokenOut - initialBalanceTokenOut;
-            if (userAmount != amountOut) {
-                revert TychoRouter__AmountOutNotFullyReceived(
-                    userAmount, amountOut
-                );
-            }
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
         }
+        uint256 initialBalance = _heldOutputBalance(tokenOut, unwrapEth);
+        amountOut = _splitSwap(amountIn, nTokens, swaps, true);
+        return _settle(
+            tokenIn,
+            tokenOut,
+            amountOut,
+            initialBalance,
+            minAmountOut,
+            unwrapEth,
+            receiver,
+            settlement
+        );
     }
 
     /**
-     * @notice Internal implementation of the core swap logic shared between singleSwap() and singleSwapPermit2().
+     * @notice Executes a split swap and distributes the output according to `settlement`.
      *
-     * @notice This function centralizes the swap execution logic.
-     * @notice For detailed documentation on parameters and behavior, see the documentation for
-     * singleSwap() and singleSwapPermit2() functions.
+     * @dev
+     * - Behaves like `splitSwapWithSettlement`, but uses Permit2 to approve and transfer ERC20 tokens from the caller.
      *
-     */
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token after surplus capture and fees. Reverts if this condition is not met.
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param unwrapEth If true, unwraps the resulting WETH into native ETH before distributing it.
+     * @param nTokens The total number of tokens involved in the swap graph (used to initialize arrays for internal calculations).
+     * @param receiver The address to receive the output tokens.
+     * @param settlement Describes how the output is distributed between the receiver and third parties.
+     * @param permitSingle A Permit2 structure containing token approval details for the input token. Ignored if `wrapEth` is true.
+     * @param signature A valid signature authorizing the Permit2 approval. Ignored if `wrapEth` is true.
+     * @param swaps Encoded swap graph data containing details of each swap, including absolute input amounts.
+     *
+     * @return amountOut The amount of the output token received by the receiver.
+     */
+    function splitSwapWithSettlementPermit2(
+        uint256 amountIn,
+        address tokenIn,
+        address tokenOut,
+        uint256 minAmountOut,
+        bool wrapEth,
+        bool unwrapEth,
+        uint256 nTokens,
+        address receiver,
+        OutputSettlement calldata settlement,
+        IAllowanceTransfer.PermitSingle calldata permitSingle,
+        bytes calldata signature,
+        bytes calldata swaps
+    ) external payable whenNotPaused nonReentrant returns (uint256 amountOut) {
+        _validateSwapParams(receiver, minAmountOut, settlement);
+        // For native ETH, assume funds already in our router. Else, handle approval.
+        if (tokenIn != address(0)) {
+            permit2.permit(msg.sender, permitSingle, signature);
+        }
+        if (wrapEth) {
+            _wrapETH(amountIn);
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
+        }
+        uint256 initialBalance = _heldOutputBalance(tokenOut, unwrapEth);
+        amountOut = _splitSwap(amountIn, nTokens, swaps, true);
+        return _settle(
+            tokenIn,
+            tokenOut,
+            amountOut,
+            initialBalance,
+            minAmountOut,
+            unwrapEth,
+            receiver,
+            settlement
+        );
+    }
+
+    /**
+     * @notice Executes a sequential swap and distributes the output according to `settlement`.
+     *
+     * @dev
+     * - Behaves like `sequentialSwap`, but the last swap must send its output to the router instead of the receiver.
+     * - `settlement.surplusShareBps` of the output exceeding `settlement.expectedAmountOut` is sent to `settlement.surplusRecipient`.
+     * - The integrator fee (`settlement.feeBps` of the remaining output) is deducted and sent to `settlement.feeRecipient`.
+     * - Reverts with `TychoRouter__NegativeSlippage` if the output amount after surplus and fees is less than `minAmountOut`.
+     * - If `unwrapEth` is true, the surplus, the fee and the receiver amount are paid out in native ETH.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token after surplus capture and fees. Reverts if this condition is not met.
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param unwrapEth If true, unwraps the resulting WETH into native ETH before distributing it.
+     * @param receiver The address to receive the output tokens.
+     * @param settlement Describes how the output is distributed between the receiver and third parties.
+     * @param swaps Encoded swaps containing details of each swap.
+     *
+     * @return amountOut The amount of the output token received by the receiver.
+     */
+    function sequentialSwapWithSettlement(
+        uint256 amountIn,
+        address tokenIn,
+        address tokenOut,
+        uint256 minAmountOut,
+        bool wrapEth,
+        bool unwrapEth,
+        address receiver,
+        OutputSettlement calldata settlement,
+        bytes calldata swaps
+    ) public payable whenNotPaused nonReentrant returns (uint256 amountOut) {
+        _validateSwapParams(receiver, minAmountOut, settlement);
+        if (wrapEth) {
+            _wrapETH(amountIn);
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
+        }
+        uint256 initialBalance = _heldOutputBalance(tokenOut, unwrapEth);
+        amountOut = _sequentialSwap(amountIn, swaps);
+        return _settle(
+            tokenIn,
+            tokenOut,
+            amountOut,
+            initialBalance,
+            minAmountOut,
+            unwrapEth,
+            receiver,
+            settlement
+        );
+    }
+
+    /**
+     * @notice Executes a sequential swap and distributes the output according to `settlement`.
+     *
+     * @dev
+     * - Behaves like `sequentialSwapWithSettlement`, but uses Permit2 to approve and transfer ERC20 tokens from the caller.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token after surplus capture and fees. Reverts if this condition is not met.
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param unwrapEth If true, unwraps the resulting WETH into native ETH before distributing it.
+     * @param receiver The address to receive the output tokens.
+     * @param settlement Describes how the output is distributed between the receiver and third parties.
+     * @param permitSingle A Permit2 structure containing token approval details for the input token. Ignored if `wrapEth` is true.
+     * @param signature A valid signature authorizing the Permit2 approval. Ignored if `wrapEth` is true.
+     * @param swaps Encoded swaps containing details of each swap.
+     *
+     * @return amountOut The amount of the output token received by the receiver.
+     */
+    function sequentialSwapWithSettlementPermit2(
+        uint256 amountIn,
+        address tokenIn,
+        address tokenOut,
+        uint256 minAmountOut,
+        bool wrapEth,
+        bool unwrapEth,
+        address receiver,
+        OutputSettlement calldata settlement,
+        IAllowanceTransfer.PermitSingle calldata permitSingle,
+        bytes calldata signature,
+        bytes calldata swaps
+    ) external payable whenNotPaused nonReentrant returns (uint256 amountOut) {
+        _validateSwapParams(receiver, minAmountOut, settlement);
+        // For native ETH, assume funds already in our router. Else, handle approval.
+        if (tokenIn != address(0)) {
+            permit2.permit(msg.sender, permitSingle, signature);
+        }
+        if (wrapEth) {
+            _wrapETH(amountIn);
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
+        }
+        uint256 initialBalance = _heldOutputBalance(tokenOut, unwrapEth);
+        amountOut = _sequentialSwap(amountIn, swaps);
+        return _settle(
+            tokenIn,
+            tokenOut,
+            amountOut,
+            initialBalance,
+            minAmountOut,
+            unwrapEth,
+            receiver,
+            settlement
+        );
+    }
+
+    /**
+     * @notice Executes a single swap and distributes the output according to `settlement`.
+     *
+     * @dev
+     * - Behaves like `singleSwap`, but the swap must send its output to the router instead of the receiver.
+     * - `settlement.surplusShareBps` of the output exceeding `settlement.expectedAmountOut` is sent to `settlement.surplusRecipient`.
+     * - The integrator fee (`settlement.feeBps` of the remaining output) is deducted and sent to `settlement.feeRecipient`.
+     * - Reverts with `TychoRouter__NegativeSlippage` if the output amount after surplus and fees is less than `minAmountOut`.
+     * - If `unwrapEth` is true, the surplus, the fee and the receiver amount are paid out in native ETH.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token after surplus capture and fees. Reverts if this condition is not met.
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param unwrapEth If true, unwraps the resulting WETH into native ETH before distributing it.
+     * @param receiver The address to receive the output tokens.
+     * @param settlement Describes how the output is distributed between the receiver and third parties.
+     * @param swapData Encoded swap details.
+     *
+     * @return amountOut The amount of the output token received by the receiver.
+     */
+    function singleSwapWithSettlement(
+        uint256 amountIn,
+        address tokenIn,
+        address tokenOut,
+        uint256 minAmountOut,
+        bool wrapEth,
+        bool unwrapEth,
+        address receiver,
+        OutputSettlement calldata settlement,
+        bytes calldata swapData
+    ) public payable whenNotPaused nonReentrant returns (uint256 amountOut) {
+        _validateSwapParams(receiver, minAmountOut, settlement);
+        if (wrapEth) {
+            _wrapETH(amountIn);
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
+        }
+        (address executor, bytes calldata protocolData) =
+            swapData.decodeSingleSwap();
+        uint256 initialBalance = _heldOutputBalance(tokenOut, unwrapEth);
+        amountOut = _callExecutor(executor, amountIn, protocolData);
+        return _settle(
+            tokenIn,
+            tokenOut,
+            amountOut,
+            initialBalance,
+            minAmountOut,
+            unwrapEth,
+            receiver,
+            settlement
+        );
+    }
+
+    /**
+     * @notice Executes a single swap and distributes the output according to `settlement`.
+     *
+     * @dev
+     * - Behaves like `singleSwapWithSettlement`, but uses Permit2 to approve and transfer ERC20 tokens from the caller.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param tokenOut The address of the output token. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount of the output token after surplus capture and fees. Reverts if this condition is not met.
+     * @param wrapEth If true, wraps the input token (native ETH) into WETH.
+     * @param unwrapEth If true, unwraps the resulting WETH into native ETH before distributing it.
+     * @param receiver The address to receive the output tokens.
+     * @param settlement Describes how the output is distributed between the receiver and third parties.
+     * @param permitSingle A Permit2 structure containing token approval details for the input token. Ignored if `wrapEth` is true.
+     * @param signature A valid signature authorizing the Permit2 approval. Ignored if `wrapEth` is true.
+     * @param swapData Encoded swap details.
+     *
+     * @return amountOut The amount of the output token received by the receiver.
+     */
+    function singleSwapWithSettlementPermit2(
+        uint256 amountIn,
+        address tokenIn,
+        address tokenOut,
+        uint256 minAmountOut,
+        bool wrapEth,
+        bool unwrapEth,
+        address receiver,
+        OutputSettlement calldata settlement,
+        IAllowanceTransfer.PermitSingle calldata permitSingle,
+        bytes calldata signature,
+        bytes calldata swapData
+    ) external payable whenNotPaused nonReentrant returns (uint256 amountOut) {
+        _validateSwapParams(receiver, minAmountOut, settlement);
+        // For native ETH, assume funds already in our router. Else, handle approval.
+        if (tokenIn != address(0)) {
+            permit2.permit(msg.sender, permitSingle, signature);
+        }
+        if (wrapEth) {
+            _wrapETH(amountIn);
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
+        }
+        (address executor, bytes calldata protocolData) =
+            swapData.decodeSingleSwap();
+        uint256 initialBalance = _heldOutputBalance(tokenOut, unwrapEth);
+        amountOut = _callExecutor(executor, amountIn, protocolData);
+        return _settle(
+            tokenIn,
+            tokenOut,
+            amountOut,
+            initialBalance,
+            minAmountOut,
+            unwrapEth,
+            receiver,
+            settlement
+        );
+    }
+
+    /**
+     * @notice Executes a call to this router, reverting if the given deadline has passed.
+     *
+     * @dev
+     * - `data` is the calldata of a swap entry point of this router (e.g. `singleSwap`) or of
+     *   `swapWithActions`, executed with a delegatecall so that `msg.sender` and `msg.value` are preserved.
+     *   Any other call reverts with `TychoRouter__InvalidSwapCall`.
+     * - This protects transactions that stay pending for a long time from being executed at a bad price.
+     *
+     * @param deadline The timestamp after which the call reverts.
+     * @param data The calldata of the call to execute.
+     *
+     * @return result The return data of the call.
+     */
+    function executeWithDeadline(uint256 deadline, bytes calldata data)
+        external
+        payable
+        whenNotPaused
+        nonReentrant
+        returns (bytes memory result)
+    {
+        if (block.timestamp > deadline) {
+            revert TychoRouter__DeadlineExpired(deadline, block.timestamp);
+        }
+        return _delegateToSelf(data);
+    }
+
+    /**
+     * @notice Executes a swap surrounded by actions on the funds held by the router, e.g. unwrapping the input
+     *         before swapping or depositing the output into an ERC-4626 vault.
+     *
+     * @dev
+     * - If `preActions` is not empty, the router first takes `amountIn` of `tokenIn` from the caller (with a
+     *   regular ERC20 transfer or with `msg.value` for native ETH) and executes the pre-swap actions on it.
+     *   `swapCall` is then expected to swap funds already held by the router.
+     * - `swapCall` is the calldata of one of the router's swap entry points (e.g. `sequentialSwap`), executed
+     *   with a delegatecall so that `msg.sender` is preserved. Its receiver must be this router.
+     * - The post-swap actions are executed on the router's balance increase of `swapTokenOut` during the swap.
+     *   Whatever the last action outputs is sent to `receiver`.
+     * - The minimum amount out is checked both by the swap call, on the swap's output, and after the post-swap
+     *   actions, on the amount output by the last action. For DISTRIBUTE actions this is the amount sent out,
+     *   and for CALL actions the amount the target actually consumed from the router's balance.
+     *
+     * @param amountIn The input token amount to be swapped.
+     * @param tokenIn The address of the input token. Use `address(0)` for native ETH
+     * @param swapTokenOut The address of the output token of the swap. Use `address(0)` for native ETH
+     * @param minAmountOut The minimum acceptable amount output by the post-swap actions. Reverts if this
+     * condition is not met.
+     * @param receiver The address to receive the output of the last action.
+     * @param preActions PLE encoded actions to execute before the swap.
+     * @param swapCall The calldata of the swap call to execute.
+     * @param postActions PLE encoded actions to execute after the swap.
+     *
+     * @return tokenOut The token sent to the receiver.
+     * @return amountOut The amount of `tokenOut` sent to the receiver.
+     */
+    function swapWithActions(
+        uint256 amountIn,
+        address tokenIn,
+        address swapTokenOut,
+        uint256 minAmountOut,
+        address receiver,
+        bytes calldata preActions,
+        bytes calldata swapCall,
+        bytes calldata postActions
+    )
+        external
+        payable
+        whenNotPaused
+        nonReentrant
+        returns (address tokenOut, uint256 amountOut)
+    {
+        if (receiver == address(0)) {
+            revert TychoRouter__AddressZero();
+        }
+        if (minAmountOut == 0) {
+            revert TychoRouter__UndefinedMinAmountOut();
+        }
+        if (swapCall.length < 4 || !_isSwapSelector(bytes4(swapCall[:4]))) {
+            revert TychoRouter__InvalidSwapCall(
+                swapCall.length < 4 ? bytes4(0) : bytes4(swapCall[:4])
+            );
+        }
+        if (preActions.length > 0) {
+            if (tokenIn == address(0)) {
+                if (msg.value != amountIn) {
+                    revert TychoRouter__MessageValueMismatch(
+                        msg.value, amountIn
+                    );
+                }
+                _spendMsgValue();
+            } else {
+                IERC20(tokenIn).safeTransferFrom(
+                    msg.sender, address(this), amountIn
+                );
+            }
+            _executeActions(preActions, tokenIn, amountIn);
+        }
+
+        // The swap's reported amount out is not trusted: the post-swap actions only use what the router
+        // actually received.
+        uint256 initialBalance = _balanceOf(swapTokenOut, address(this));
+        _delegateToSelf(swapCall);
+        uint256 swapAmountOut =
+            _balanceOf(swapTokenOut, address(this)) - initialBalance;
+
+        bool terminated;
+        (tokenOut, amountOut, terminated) =
+            _executeActions(postActions, swapTokenOut, swapAmountOut);
+        if (amountOut < minAmountOut) {
+            revert TychoRouter__NegativeSlippage(amountOut, minAmountOut);
+        }
+        if (terminated) {
+            amountOut = 0;
+        } else {
+            _transferOut(tokenOut, receiver, amountOut);
+        }
+    }
+
+    /**
+     * @dev Returns true if `selector` is one of the swap entry points of the router.
+     */
+    function _isSwapSelector(bytes4 selector) internal view returns (bool) {
+        return selector == this.singleSwap.selector
+            || selector == this.singleSwapPermit2.selector
+            || selector == this.sequentialSwap.selector
+            || selector == this.sequentialSwapPermit2.selector
+            || selector == this.splitSwap.selector
+            || selector == this.splitSwapPermit2.selector
+            || selector == this.splitSwapWithAmounts.selector
+            || selector == this.splitSwapWithAmountsPermit2.selector
+            || selector == this.splitSwapMultiOutput.selector
+            || selector == this.splitSwapMultiOutputPermit2.selector
+            || selector == this.splitSwapMultiInput.selector
+            || selector == this.splitSwapMultiInputPermit2.selector
+            || selector == this.singleSwapWithSettlement.selector
+            || selector == this.singleSwapWithSettlementPermit2.selector
+            || selector == this.sequentialSwapWithSettlement.selector
+            || selector == this.sequentialSwapWithSettlementPermit2.selector
+            || selector == this.splitSwapWithSettlement.selector
+            || selector == this.splitSwapWithSettlementPermit2.selector;
+    }
+
+    /**
+     * @notice Executes several calls to this router atomically, in a single transaction.
+     *
+     * @dev
+     * - Each entry of `data` is the calldata of a swap entry point of this router (e.g. `singleSwap`), or of
+     *   `swapWithActions` or `executeWithDeadline`, executed with a delegatecall so that `msg.sender` is
+     *   preserved. Any other call reverts with `TychoRouter__InvalidSwapCall`. If any call reverts, the whole
+     *   batch reverts.
+     * - Every call is subject to its own checks (pausing, slippage...).
+     * - `msg.value` is shared by all the calls, so at most one of them may spend native ETH. A second call
+     *   spending it reverts with `TychoRouter__MessageValueAlreadySpent`.
+     *
+     * @param data The calldata of each call to execute, in order.
+     *
+     * @return results The return data of each call.
+     */
+    function multicall(bytes[] calldata data)
+        external
+        payable
+        whenNotPaused
+        nonReentrant
+        returns (bytes[] memory results)
+    {
+        return _multicall(data);
+    }
+
+    /**
+     * @notice Executes several calls to this router atomically, after approving all their input tokens with
+     *         a single Permit2 signature.
+     *
+     * @dev
+     * - Behaves like `multicall`, but first grants the router the Permit2 allowances of `permitBatch`. The
+     *   calls are expected to use the non-Permit2 entry points, with swaps that transfer the input tokens
+     *   using Permit2.
+     *
+     * @param permitBatch A Permit2 structure containing token approval details for all the input tokens.
+     * @param signature A valid signature authorizing the Permit2 approvals.
+     * @param data The calldata of each call to execute, in order.
+     *
+     * @return results The return data of each call.
+     */
+    function multicallPermit2(
+        IAllowanceTransfer.PermitBatch calldata permitBatch,
+        bytes calldata signature,
+        bytes[] calldata data
+    )
+        external
+        payable
+        whenNotPaused
+        nonReentrant
+        returns (bytes[] memory results)
+    {
+        permit2.permit(msg.sender, permitBatch, signature);
+        return _multicall(data);
+    }
+
+    /**
+     * @notice Internal implementation of the core swap logic shared between splitSwap() and splitSwapPermit2().
+     *
+     * @notice This function centralizes the swap execution logic.
+     * @notice For detailed documentation on parameters and behavior, see the documentation for
+     * splitSwap() and splitSwapPermit2() functions.
+     *
+     * @param withAmounts If true, each encoded swap carries an absolute input amount
+     * (see splitSwapWithAmounts()).
+     */
+    function _splitSwapChecked(
+        uint256 amountIn,
+        address tokenIn,
+        address tokenOut,
+        uint256 minAmountOut,
+        bool wrapEth,
+        bool unwrapEth,
+        uint256 nTokens,
+        address receiver,
+        bytes calldata swaps,
+        bool withAmounts
+    ) internal returns (uint256 amountOut) {
+        if (receiver == address(0)) {
+            revert TychoRouter__AddressZero();
+        }
+        if (minAmountOut == 0) {
+            revert TychoRouter__UndefinedMinAmountOut();
+        }
+
+        // Assume funds are already in the router.
+        if (wrapEth) {
+            _wrapETH(amountIn);
+            tokenIn = address(_weth);
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
+        }
+
+        uint256 initialBalanceTokenOut = _balanceOf(tokenOut, receiver);
+        amountOut = _splitSwap(amountIn, nTokens, swaps, withAmounts);
+
+        if (amountOut < minAmountOut) {
+            revert TychoRouter__NegativeSlippage(amountOut, minAmountOut);
+        }
+
+        if (unwrapEth) {
+            _unwrapETH(amountOut);
+            Address.sendValue(payable(receiver), amountOut);
+        }
+
+        if (tokenIn != tokenOut) {
+            uint256 currentBalanceTokenOut = _balanceOf(tokenOut, receiver);
+            uint256 userAmount = currentBalanceTokenOut - initialBalanceTokenOut;
+            if (userAmount != amountOut) {
+                revert TychoRouter__AmountOutNotFullyReceived(
+                    userAmount, amountOut
+                );
+            }
+        }
+    }
+
+    /**
+     * @notice Internal implementation of the core swap logic shared between splitSwapMultiOutput() and
+     * splitSwapMultiOutputPermit2().
+     *
+     * @notice For detailed documentation on parameters and behavior, see the documentation for
+     * splitSwapMultiOutput() and splitSwapMultiOutputPermit2() functions.
+     */
+    function _splitSwapMultiOutputChecked(
+        uint256 amountIn,
+        address tokenIn,
+        bool wrapEth,
+        uint256 nTokens,
+        SwapOutput[] calldata outputs,
+        bytes calldata swaps
+    ) internal returns (uint256[] memory amountsOut) {
+        if (outputs.length == 0 || outputs.length >= nTokens) {
+            revert TychoRouter__InvalidOutputs(outputs.length, nTokens);
+        }
+
+        // Assume funds are already in the router.
+        if (wrapEth) {
+            _wrapETH(amountIn);
+            tokenIn = address(_weth);
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
+        }
+
+        uint256[] memory initialBalances = new uint256[](outputs.length);
+        for (uint256 i = 0; i < outputs.length; i++) {
+            if (outputs[i].receiver == address(0)) {
+                revert TychoRouter__AddressZero();
+            }
+            if (outputs[i].minAmountOut == 0) {
+                revert TychoRouter__UndefinedMinAmountOut();
+            }
+            initialBalances[i] =
+                _balanceOf(outputs[i].token, outputs[i].receiver);
+        }
+
+        uint256[] memory amounts = new uint256[](nTokens);
+        amounts[0] = amountIn;
+        (amounts,,) = _splitSwapAmounts(amounts, swaps, true);
+
+        // The outputs occupy the last token indices of the swap graph.
+        uint256 firstOutputIndex = nTokens - outputs.length;
+        amountsOut = new uint256[](outputs.length);
+        for (uint256 i = 0; i < outputs.length; i++) {
+            uint256 amountOut = amounts[firstOutputIndex + i];
+            // The receiver's balance increase is checked against the minimum, not the amount
+            // reported by the executors
+            if (outputs[i].token != tokenIn) {
+                uint256 userAmount = _balanceOf(
+                    outputs[i].token, outputs[i].receiver
+                ) - initialBalances[i];
+                if (userAmount != amountOut) {
+                    revert TychoRouter__AmountOutNotFullyReceived(
+                        userAmount, amountOut
+                    );
+                }
+                amountOut = userAmount;
+            }
+            if (amountOut < outputs[i].minAmountOut) {
+                revert TychoRouter__NegativeSlippage(
+                    amountOut, outputs[i].minAmountOut
+                );
+            }
+            amountsOut[i] = amountOut;
+        }
+    }
+
+    /**
+     * @notice Internal implementation of the core swap logic shared between splitSwapMultiInput() and
+     * splitSwapMultiInputPermit2().
+     *
+     * @notice For detailed documentation on parameters and behavior, see the documentation for
+     * splitSwapMultiInput() and splitSwapMultiInputPermit2() functions.
+     */
+    function _splitSwapMultiInputChecked(
+        SwapInput[] calldata inputs,
+        address tokenOut,
+        uint256 minAmountOut,
+        uint256 nTokens,
+        address receiver,
+        bytes calldata swaps
+    ) internal returns (uint256 amountOut) {
+        if (receiver == address(0)) {
+            revert TychoRouter__AddressZero();
+        }
+        if (minAmountOut == 0) {
+            revert TychoRouter__UndefinedMinAmountOut();
+        }
+        if (inputs.length == 0 || inputs.length >= nTokens) {
+            revert TychoRouter__InvalidInputs(inputs.length, nTokens);
+        }
+
+        // The inputs occupy the first token indices of the swap graph.
+        uint256[] memory amounts = new uint256[](nTokens);
+        for (uint256 i = 0; i < inputs.length; i++) {
+            if (inputs[i].token == address(0)) {
+                revert TychoRouter__AddressZero();
+            }
+            amounts[i] = inputs[i].amount;
+        }
+
+        uint256 initialBalanceTokenOut = _balanceOf(tokenOut, receiver);
+        (amounts,,) = _splitSwapAmounts(amounts, swaps, true);
+        amountOut = amounts[nTokens - 1];
+
+        if (amountOut < minAmountOut) {
+            revert TychoRouter__NegativeSlippage(amountOut, minAmountOut);
+        }
+
+        uint256 currentBalanceTokenOut = _balanceOf(tokenOut, receiver);
+        uint256 userAmount = currentBalanceTokenOut - initialBalanceTokenOut;
+        if (userAmount != amountOut) {
+            revert TychoRouter__AmountOutNotFullyReceived(userAmount, amountOut);
+        }
+    }
+
+    /**
+     * @notice Internal implementation of the core swap logic shared between singleSwap() and singleSwapPermit2().
+     *
+     * @notice This function centralizes the swap execution logic.
+     * @notice For detailed documentation on parameters and behavior, see the documentation for
+     * singleSwap() and singleSwapPermit2() functions.
+     *
+     */
     function _singleSwap(
         uint256 amountIn,
         address tokenIn,
@@ -476,6 +1488,8 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
         if (wrapEth) {
             _wrapETH(amountIn);
             tokenIn = address(_weth);
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
         }
 
         (address executor, bytes calldata protocolData) =
@@ -533,6 +1547,8 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
         if (wrapEth) {
             _wrapETH(amountIn);
             tokenIn = address(_weth);
+        } else if (tokenIn == address(0)) {
+            _spendMsgValue();
         }
 
         uint256 initialBalanceTokenOut = _balanceOf(tokenOut, receiver);
@@ -558,6 +1574,129 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
         }
     }
 
+    /**
+     * @dev Reverts if the common swap parameters or the output settlement are invalid.
+     */
+    function _validateSwapParams(
+        address receiver,
+        uint256 minAmountOut,
+        OutputSettlement calldata settlement
+    ) internal pure {
+        if (receiver == address(0)) {
+            revert TychoRouter__AddressZero();
+        }
+        if (minAmountOut == 0) {
+            revert TychoRouter__UndefinedMinAmountOut();
+        }
+        if (settlement.feeBps > BPS_DENOMINATOR) {
+            revert TychoRouter__InvalidFee(settlement.feeBps);
+        }
+        if (settlement.feeBps > 0 && settlement.feeRecipient == address(0)) {
+            revert TychoRouter__AddressZero();
+        }
+        if (settlement.surplusShareBps > BPS_DENOMINATOR) {
+            revert TychoRouter__InvalidSurplusShare(settlement.surplusShareBps);
+        }
+        if (
+            settlement.surplusShareBps > 0
+                && settlement.surplusRecipient == address(0)
+        ) {
+            revert TychoRouter__AddressZero();
+        }
+    }
+
+    /**
+     * @dev Distributes the output of a swap held by the router between the surplus recipient, the fee
+     * recipient and the receiver.
+     *
+     * The output is the router's balance increase of the output token during the swap, rather than the
+     * amount reported by the executors. Cyclic swaps are the exception: their input may go through the
+     * router during the swap, so the reported amount is used, like in `_splitSwapChecked`.
+     *
+     * First, `surplusShareBps` of the output exceeding `expectedAmountOut` is captured as surplus. Then the
+     * fee is charged on the remaining output. Both are deducted before checking the receiver's amount
+     * against `minAmountOut`.
+     *
+     * @param initialBalance The router's balance of the output token before the swap (see `_heldOutputBalance`).
+     *
+     * @return userAmount The amount of the output token sent to the receiver.
+     */
+    function _settle(
+        address tokenIn,
+        address tokenOut,
+        uint256 amountOut,
+        uint256 initialBalance,
+        uint256 minAmountOut,
+        bool unwrapEth,
+        address receiver,
+        OutputSettlement calldata settlement
+    ) internal returns (uint256 userAmount) {
+        if (tokenIn != tokenOut) {
+            amountOut =
+                _heldOutputBalance(tokenOut, unwrapEth) - initialBalance;
+        }
+
+        uint256 surplusAmount;
+        if (amountOut > settlement.expectedAmountOut) {
+            surplusAmount = (
+                (amountOut - settlement.expectedAmountOut)
+                    * settlement.surplusShareBps
+            ) / BPS_DENOMINATOR;
+        }
+        uint256 remainingAmount = amountOut - surplusAmount;
+        uint256 feeAmount =
+            (remainingAmount * settlement.feeBps) / BPS_DENOMINATOR;
+        userAmount = remainingAmount - feeAmount;
+
+        if (userAmount < minAmountOut) {
+            revert TychoRouter__NegativeSlippage(userAmount, minAmountOut);
+        }
+
+        if (unwrapEth) {
+            _unwrapETH(amountOut);
+            tokenOut = address(0);
+        }
+
+        if (surplusAmount > 0) {
+            emit SurplusCaptured(
+                tokenOut, surplusAmount, settlement.surplusRecipient
+            );
+            _transferOut(tokenOut, settlement.surplusRecipient, surplusAmount);
+        }
+        if (feeAmount > 0) {
+            emit FeeCharged(tokenOut, feeAmount, settlement.feeRecipient);
+            _transferOut(tokenOut, settlement.feeRecipient, feeAmount);
+        }
+        _transferOut(tokenOut, receiver, userAmount);
+    }
+
+    /**
+     * @dev Returns the router's balance of the token output by the swaps: WETH if it is unwrapped before
+     * being settled, `tokenOut` otherwise.
+     */
+    function _heldOutputBalance(address tokenOut, bool unwrapEth)
+        internal
+        view
+        returns (uint256)
+    {
+        return _balanceOf(
+            unwrapEth ? address(_weth) : tokenOut, address(this)
+        );
+    }
+
+    /**
+     * @dev Transfers tokens held by the router. Use `address(0)` for native ETH.
+     */
+    function _transferOut(address token, address to, uint256 amount)
+        internal
+    {
+        if (token == address(0)) {
+            Address.sendValue(payable(to), amount);
+        } else {
+            IERC20(token).safeTransfer(to, amount);
+        }
+    }
+
     /**
      * @dev Executes sequential swaps as defined by the provided swap graph.
      *
@@ -575,17 +1714,56 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
      *  `cyclicSwapAmountOut` is used to track the amount of the output token, and is updated when
      *  the `tokenOutIndex` is 0.
      *
+     * If `withAmounts` is true, each swap also carries an absolute input amount. A non-zero amount takes
+     * precedence over the `split` value.
+     *
      * @param amountIn The initial amount of the sell token to be swapped.
      * @param nTokens The total number of tokens involved in the swap path, used to initialize arrays for internal tracking.
      * @param swaps_ Encoded swap graph data containing the details of each swap operation.
+     * @param withAmounts Whether the swaps are encoded with an absolute input amount.
      *
      * @return The total amount of the buy token obtained after all swaps have been executed.
      */
     function _splitSwap(
         uint256 amountIn,
         uint256 nTokens,
-        bytes calldata swaps_
+        bytes calldata swaps_,
+        bool withAmounts
     ) internal returns (uint256) {
+        uint256[] memory amounts = new uint256[](nTokens);
+        amounts[0] = amountIn;
+        uint256 cyclicSwapAmountOut;
+        uint8 tokenOutIndex;
+        (amounts, cyclicSwapAmountOut, tokenOutIndex) =
+            _splitSwapAmounts(amounts, swaps_, withAmounts);
+        return tokenOutIndex == 0 ? cyclicSwapAmountOut : amounts[tokenOutIndex];
+    }
+
+    /**
+     * @dev Executes the swaps of a swap graph and keeps track of the amounts of every token.
+     *
+     * See `_splitSwap()` for details on how the swap graph is processed.
+     *
+     * @param initialAmounts The amount available of each token before any swap, indexed like the swap
+     * graph. Only the sell tokens have a non-zero amount.
+     *
+     * @return amounts The total amount of each token, indexed like the swap graph. The entries of the
+     * sell tokens include their initial amounts.
+     * @return cyclicSwapAmountOut The amount of the sell token received back in case of cyclic swaps.
+     * @return tokenOutIndex The index of the output token of the last swap.
+     */
+    function _splitSwapAmounts(
+        uint256[] memory initialAmounts,
+        bytes calldata swaps_,
+        bool withAmounts
+    )
+        internal
+        returns (
+            uint256[] memory amounts,
+            uint256 cyclicSwapAmountOut,
+            uint8 tokenOutIndex
+        )
+    {
         if (swaps_.length == 0) {
             revert TychoRouter__EmptySwaps();
         }
@@ -593,27 +1771,42 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
         uint256 currentAmountIn;
         uint256 currentAmountOut;
         uint8 tokenInIndex = 0;
-        uint8 tokenOutIndex = 0;
         uint24 split;
+        uint256 swapAmountIn;
         address executor;
         bytes calldata protocolData;
         bytes calldata swapData;
 
-        uint256[] memory remainingAmounts = new uint256[](nTokens);
-        uint256[] memory amounts = new uint256[](nTokens);
-        uint256 cyclicSwapAmountOut = 0;
-        amounts[0] = amountIn;
-        remainingAmounts[0] = amountIn;
+        amounts = initialAmounts;
+        uint256[] memory remainingAmounts = new uint256[](amounts.length);
+        for (uint256 i = 0; i < amounts.length; i++) {
+            remainingAmounts[i] = amounts[i];
+        }
 
         while (swaps_.length > 0) {
             (swapData, swaps_) = swaps_.next();
 
-            (tokenInIndex, tokenOutIndex, split, executor, protocolData) =
-                swapData.decodeSplitSwap();
+            if (withAmounts) {
+                (
+                    tokenInIndex,
+                    tokenOutIndex,
+                    split,
+                    swapAmountIn,
+                    executor,
+                    protocolData
+                ) = swapData.decodeSplitSwapWithAmount();
+            } else {
+                (tokenInIndex, tokenOutIndex, split, executor, protocolData) =
+                    swapData.decodeSplitSwap();
+            }
 
-            currentAmountIn = split > 0
-                ? (amounts[tokenInIndex] * split) / 0xffffff
-                : remainingAmounts[tokenInIndex];
+            if (swapAmountIn > 0) {
+                currentAmountIn = swapAmountIn;
+            } else {
+                currentAmountIn = split > 0
+                    ? (amounts[tokenInIndex] * split) / 0xffffff
+                    : remainingAmounts[tokenInIndex];
+            }
 
             currentAmountOut =
                 _callExecutor(executor, currentAmountIn, protocolData);
@@ -626,7 +1819,6 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
             remainingAmounts[tokenOutIndex] += currentAmountOut;
             remainingAmounts[tokenInIndex] -= currentAmountIn;
         }
-        return tokenOutIndex == 0 ? cyclicSwapAmountOut : amounts[tokenOutIndex];
     }
 
     /**
@@ -666,6 +1858,114 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
         }
     }
 
+    /**
+     * @dev Executes each call of a multicall with a delegatecall to this contract.
+     */
+    function _multicall(bytes[] calldata data)
+        internal
+        returns (bytes[] memory results)
+    {
+        results = new bytes[](data.length);
+        for (uint256 i = 0; i < data.length; i++) {
+            results[i] = _delegateToSelf(data[i]);
+        }
+    }
+
+    /**
+     * @dev Executes a call to this router with a delegatecall, so that `msg.sender` and `msg.value` are
+     * preserved. The called function may pass the reentrancy guard once.
+     *
+     * Only a swap entry point, or a swap wrapped by `swapWithActions` or `executeWithDeadline`, can be
+     * delegated, so that a multicall can't reach the admin functions nor `multicall` itself.
+     */
+    // slither-disable-next-line assembly
+    function _delegateToSelf(bytes calldata data)
+        internal
+        returns (bytes memory result)
+    {
+        if (data.length < 4) {
+            revert TychoRouter__InvalidDataLength();
+        }
+        bytes4 selector = bytes4(data[:4]);
+        if (
+            !_isSwapSelector(selector)
+                && selector != this.swapWithActions.selector
+                && selector != this.executeWithDeadline.selector
+        ) {
+            revert TychoRouter__InvalidSwapCall(selector);
+        }
+        assembly {
+            tstore(_DELEGATED_SELECTOR_SLOT, selector)
+        }
+        result = Address.functionDelegateCall(address(this), data);
+        assembly {
+            tstore(_DELEGATED_SELECTOR_SLOT, 0)
+        }
+    }
+
+    /**
+     * @dev Enters the reentrancy guard.
+     * @return delegated Whether the router is already entered and delegated this call to itself.
+     */
+    // slither-disable-next-line assembly
+    function _enterGuard() private returns (bool delegated) {
+        uint256 status;
+        bytes4 delegatedSelector;
+        assembly {
+            status := tload(_REENTRANCY_STATUS_SLOT)
+            delegatedSelector := tload(_DELEGATED_SELECTOR_SLOT)
+        }
+        if (status == 0) {
+            assembly {
+                tstore(_REENTRANCY_STATUS_SLOT, 1)
+            }
+            return false;
+        }
+        if (delegatedSelector == bytes4(0) || delegatedSelector != msg.sig) {
+            revert TychoRouter__ReentrantCall();
+        }
+        // The delegated call can only enter once.
+        assembly {
+            tstore(_DELEGATED_SELECTOR_SLOT, 0)
+        }
+        return true;
+    }
+
+    /**
+     * @dev Exits the reentrancy guard. The outermost call also resets the `msg.value` spending, so that
+     * several calls of a contract in one transaction can each spend their own `msg.value`.
+     */
+    // slither-disable-next-line assembly
+    function _exitGuard(bool delegated) private {
+        if (!delegated) {
+            assembly {
+                tstore(_REENTRANCY_STATUS_SLOT, 0)
+                tstore(_MSG_VALUE_SPENT_SLOT, 0)
+            }
+        }
+    }
+
+    /**
+     * @dev Records that the current call spends `msg.value`. Calls delegated by a multicall share the same
+     * `msg.value`, so only one of them may spend it.
+     */
+    // slither-disable-next-line assembly
+    function _spendMsgValue() internal {
+        if (msg.value == 0) {
+            return;
+        }
+        uint256 spent;
+        assembly {
+            spent := tload(_MSG_VALUE_SPENT_SLOT)
+        }
+        if (spent != 0) {
+            revert TychoRouter__MessageValueAlreadySpent();
+        }
+        assembly {
+            tstore(_MSG_VALUE_SPENT_SLOT, 1)
+        }
+    }
+
     /**
      * @dev Pauses the contract
      */
@@ -716,6 +2016,30 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
         _removeExecutor(target);
     }
 
+    /**
+     * @dev Entrypoint to add approved targets of call actions
+     * @param targets addresses of the contracts to be called
+     */
+    function setActionTargets(address[] memory targets)
+        external
+        onlyRole(EXECUTOR_SETTER_ROLE)
+    {
+        for (uint256 i = 0; i < targets.length; i++) {
+            _setActionTarget(targets[i]);
+        }
+    }
+
+    /**
+     * @dev Entrypoint to remove an approved target of call actions
+     * @param target address of the contract to be called
+     */
+    function removeActionTarget(address target)
+        external
+        onlyRole(EXECUTOR_SETTER_ROLE)
+    {
+        _removeActionTarget(target);
+    }
+
     /**
      * @dev Allows withdrawing any ERC20 funds if funds get stuck in case of a bug.
      */
@@ -760,6 +2084,7 @@ contract TychoRouter is AccessControl, Dispatcher, Pausable, ReentrancyGuard {
         if (msg.value > 0 && msg.value != amount) {
             revert TychoRouter__MessageValueMismatch(msg.value, amount);
         }
+        _spendMsgValue();
         _weth.deposit{value: amount}();
     }
 
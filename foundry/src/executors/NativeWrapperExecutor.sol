@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: BUSL-1.1
+pragma solidity ^0.8.26;
+
+import "@interfaces/IExecutor.sol";
+import "@openzeppelin/contracts/token/ERC20/utils/SafeERC20.sol";
+import "@openzeppelin/contracts/utils/Address.sol";
+import "../../lib/IWETH.sol";
+import "./TokenTransfer.sol";
+
+error NativeWrapperExecutor__InvalidDataLength();
+error NativeWrapperExecutor__InvalidToken(address token);
+error NativeWrapperExecutor__AddressZero();
+
+/**
+ * @notice Wraps or unwraps the chain's native token as a step of a route, so that
+ * swaps using the native token and swaps using the wrapped token can be chained.
+ */
+contract NativeWrapperExecutor is IExecutor, TokenTransfer {
+    using SafeERC20 for IERC20;
+
+    IWETH public immutable weth;
+
+    constructor(address _weth, address _permit2) TokenTransfer(_permit2) {
+        if (_weth == address(0)) {
+            revert NativeWrapperExecutor__AddressZero();
+        }
+        weth = IWETH(_weth);
+    }
+
+    // slither-disable-next-line locked-ether
+    function swap(uint256 givenAmount, bytes calldata data)
+        external
+        payable
+        returns (uint256 calculatedAmount)
+    {
+        address tokenIn;
+        address receiver;
+        TransferType transferType;
+
+        (tokenIn, receiver, transferType) = _decodeData(data);
+
+        // The funds are either already in this contract or transferred into it
+        if (
+            transferType != TransferType.TRANSFER_TO_PROTOCOL
+                && transferType != TransferType.NONE
+        ) {
+            _transfer(
+                tokenIn, msg.sender, address(this), givenAmount, transferType
+            );
+        }
+
+        if (tokenIn == address(0)) {
+            weth.deposit{value: givenAmount}();
+            if (receiver != address(this)) {
+                IERC20(address(weth)).safeTransfer(receiver, givenAmount);
+            }
+        } else if (tokenIn == address(weth)) {
+            weth.withdraw(givenAmount);
+            if (receiver != address(this)) {
+                Address.sendValue(payable(receiver), givenAmount);
+            }
+        } else {
+            revert NativeWrapperExecutor__InvalidToken(tokenIn);
+        }
+        calculatedAmount = givenAmount;
+    }
+
+    function _decodeData(bytes calldata data)
+        internal
+        pure
+        returns (
+            address tokenIn,
+            address receiver,
+            TransferType transferType
+        )
+    {
+        if (data.length != 41) {
+            revert NativeWrapperExecutor__InvalidDataLength();
+        }
+        tokenIn = address(bytes20(data[0:20]));
+        receiver = address(bytes20(data[20:40]));
+        transferType = TransferType(uint8(data[40]));
+    }
+}
@@ -5,6 +5,7 @@ import "@src/executors/UniswapV4Executor.sol";
 import {TychoRouter} from "@src/TychoRouter.sol";
 import "./TychoRouterTestSetup.sol";
 import "./executors/UniswapV4Utils.sol";
+import "./mock/MockActionTarget.sol";
 import {SafeCallback} from "@uniswap/v4-periphery/src/base/SafeCallback.sol";
 
 contract TychoRouterTest is TychoRouterTestSetup {
@@ -184,4 +185,612 @@ contract TychoRouterTest is TychoRouterTestSetup {
         vm.expectRevert(TychoRouter__EmptySwaps.selector);
         tychoRouter.exposedSplitSwap(amountIn, 2, swaps);
     }
+
+    function _getMulticallSwaps(TokenTransfer.TransferType transferType)
+        private
+        view
+        returns (bytes[] memory)
+    {
+        // Trade 1 WETH for DAI and 1 WETH for WBTC, in two different calls
+        bytes[] memory data = new bytes[](2);
+        data[0] = abi.encodeWithSelector(
+            tychoRouter.singleSwap.selector,
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            2000 ether, // min amount
+            false,
+            false,
+            ALICE,
+            encodeSingleSwap(
+                address(usv2Executor),
+                encodeUniswapV2Swap(
+                    WETH_ADDR, WETH_DAI_POOL, ALICE, false, transferType
+                )
+            )
+        );
+        data[1] = abi.encodeWithSelector(
+            tychoRouter.singleSwap.selector,
+            1 ether,
+            WETH_ADDR,
+            WBTC_ADDR,
+            1, // min amount
+            false,
+            false,
+            ALICE,
+            encodeSingleSwap(
+                address(usv2Executor),
+                encodeUniswapV2Swap(
+                    WETH_ADDR, WETH_WBTC_POOL, ALICE, false, transferType
+                )
+            )
+        );
+        return data;
+    }
+
+    function testMulticall() public {
+        deal(WETH_ADDR, ALICE, 2 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 2 ether);
+
+        bytes[] memory results = tychoRouter.multicall(
+            _getMulticallSwaps(
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            )
+        );
+        vm.stopPrank();
+
+        assertEq(results.length, 2);
+        assertEq(
+            abi.decode(results[0], (uint256)),
+            IERC20(DAI_ADDR).balanceOf(ALICE)
+        );
+        assertEq(
+            abi.decode(results[1], (uint256)),
+            IERC20(WBTC_ADDR).balanceOf(ALICE)
+        );
+        assertEq(IERC20(WETH_ADDR).balanceOf(ALICE), 0);
+    }
+
+    function testMulticallPermit2() public {
+        deal(WETH_ADDR, ALICE, 2 ether);
+        vm.startPrank(ALICE);
+
+        address[] memory tokens = new address[](1);
+        tokens[0] = WETH_ADDR;
+        uint256[] memory amounts = new uint256[](1);
+        amounts[0] = 2 ether;
+        (
+            IAllowanceTransfer.PermitBatch memory permitBatch,
+            bytes memory signature
+        ) = handlePermit2BatchApproval(tokens, tychoRouterAddr, amounts);
+
+        tychoRouter.multicallPermit2(
+            permitBatch,
+            signature,
+            _getMulticallSwaps(
+                TokenTransfer.TransferType.TRANSFER_PERMIT2_TO_PROTOCOL
+            )
+        );
+        vm.stopPrank();
+
+        assertGt(IERC20(DAI_ADDR).balanceOf(ALICE), 2000 ether);
+        assertGt(IERC20(WBTC_ADDR).balanceOf(ALICE), 0);
+        assertEq(IERC20(WETH_ADDR).balanceOf(ALICE), 0);
+    }
+
+    function testMulticallIsAtomic() public {
+        // Alice only approved enough WETH for the first swap, so the second swap and the whole
+        // batch must revert
+        deal(WETH_ADDR, ALICE, 2 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory data = _getMulticallSwaps(
+            TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+        );
+        vm.expectRevert();
+        tychoRouter.multicall(data);
+        vm.stopPrank();
+
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), 0);
+        assertEq(IERC20(WETH_ADDR).balanceOf(ALICE), 2 ether);
+    }
+
+    function _getWrapEthSwap() private view returns (bytes memory) {
+        // Wrap 1 ETH and trade it for DAI
+        return abi.encodeWithSelector(
+            tychoRouter.singleSwap.selector,
+            1 ether,
+            address(0),
+            DAI_ADDR,
+            2000 ether, // min amount
+            true,
+            false,
+            ALICE,
+            encodeSingleSwap(
+                address(usv2Executor),
+                encodeUniswapV2Swap(
+                    WETH_ADDR,
+                    WETH_DAI_POOL,
+                    ALICE,
+                    false,
+                    TokenTransfer.TransferType.TRANSFER_TO_PROTOCOL
+                )
+            )
+        );
+    }
+
+    function testMulticallSpendsMsgValueOnce() public {
+        // Both calls wrap the 1 ETH sent with the batch. Without tracking the spent msg.value,
+        // the second call would take the ETH held by the router.
+        vm.deal(tychoRouterAddr, 1 ether);
+        vm.deal(ALICE, 1 ether);
+        vm.startPrank(ALICE);
+
+        bytes[] memory data = new bytes[](2);
+        data[0] = _getWrapEthSwap();
+        data[1] = _getWrapEthSwap();
+        vm.expectRevert(TychoRouter__MessageValueAlreadySpent.selector);
+        tychoRouter.multicall{value: 1 ether}(data);
+        vm.stopPrank();
+
+        assertEq(tychoRouterAddr.balance, 1 ether);
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), 0);
+    }
+
+    function testMulticallSingleNativeSpend() public {
+        vm.deal(ALICE, 1 ether);
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory data = _getMulticallSwaps(
+            TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+        );
+        data[0] = _getWrapEthSwap();
+        tychoRouter.multicall{value: 1 ether}(data);
+        vm.stopPrank();
+
+        assertGt(IERC20(DAI_ADDR).balanceOf(ALICE), 2000 ether);
+        assertGt(IERC20(WBTC_ADDR).balanceOf(ALICE), 0);
+        assertEq(ALICE.balance, 0);
+    }
+
+    function testExecuteWithDeadline() public {
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory data = _getMulticallSwaps(
+            TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+        );
+        bytes memory result =
+            tychoRouter.executeWithDeadline(block.timestamp + 1 hours, data[0]);
+        vm.stopPrank();
+
+        uint256 amountOut = abi.decode(result, (uint256));
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), amountOut);
+        assertEq(IERC20(WETH_ADDR).balanceOf(ALICE), 0);
+    }
+
+    function testExecuteWithDeadlineExpired() public {
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        uint256 deadline = block.timestamp + 1 hours;
+        vm.warp(deadline + 1);
+
+        bytes[] memory data = _getMulticallSwaps(
+            TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+        );
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                TychoRouter__DeadlineExpired.selector, deadline, deadline + 1
+            )
+        );
+        tychoRouter.executeWithDeadline(deadline, data[0]);
+        vm.stopPrank();
+    }
+
+    function testMulticallRejectsNonSwapCall() public {
+        vm.deal(tychoRouterAddr, 1 ether);
+        vm.startPrank(ALICE);
+
+        bytes[] memory data = new bytes[](1);
+        data[0] = abi.encodeWithSelector(
+            tychoRouter.withdrawNative.selector, ALICE
+        );
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                TychoRouter__InvalidSwapCall.selector,
+                tychoRouter.withdrawNative.selector
+            )
+        );
+        tychoRouter.multicall(data);
+        vm.stopPrank();
+
+        assertEq(tychoRouterAddr.balance, 1 ether);
+    }
+
+    function testExecuteWithDeadlineRejectsNonSwapCall() public {
+        vm.startPrank(ALICE);
+
+        bytes memory data = abi.encodeWithSelector(
+            tychoRouter.multicall.selector, new bytes[](0)
+        );
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                TychoRouter__InvalidSwapCall.selector,
+                tychoRouter.multicall.selector
+            )
+        );
+        tychoRouter.executeWithDeadline(block.timestamp + 1 hours, data);
+        vm.stopPrank();
+    }
+
+    function _getRouterHeldOutputSwap(TokenTransfer.TransferType transferType)
+        private
+        view
+        returns (bytes memory)
+    {
+        // Trade 1 WETH for DAI, keeping the DAI in the router
+        return abi.encodeWithSelector(
+            tychoRouter.singleSwap.selector,
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            2000 ether, // min amount
+            false,
+            false,
+            tychoRouterAddr,
+            encodeSingleSwap(
+                address(usv2Executor),
+                encodeUniswapV2Swap(
+                    WETH_ADDR,
+                    WETH_DAI_POOL,
+                    tychoRouterAddr,
+                    false,
+                    transferType
+                )
+            )
+        );
+    }
+
+    function testSetActionTargets() public {
+        address[] memory targets = new address[](1);
+        targets[0] = DUMMY;
+        vm.startPrank(EXECUTOR_SETTER);
+        tychoRouter.setActionTargets(targets);
+        assert(tychoRouter.actionTargets(DUMMY) == true);
+        tychoRouter.removeActionTarget(DUMMY);
+        vm.stopPrank();
+        assert(tychoRouter.actionTargets(DUMMY) == false);
+    }
+
+    function testSetActionTargetsMissingSetterRole() public {
+        vm.expectRevert();
+        address[] memory targets = new address[](1);
+        targets[0] = DUMMY;
+        tychoRouter.setActionTargets(targets);
+    }
+
+    function testSwapWithActionsDistribute() public {
+        // WETH -> DAI, then 60% of the DAI to BOB and 40% to ALICE
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory postActions = new bytes[](1);
+        postActions[0] = abi.encodePacked(
+            uint8(ActionDispatcher.ActionType.DISTRIBUTE),
+            BOB,
+            uint16(6000),
+            ALICE,
+            uint16(4000)
+        );
+        (address tokenOut, uint256 amountOut) = tychoRouter.swapWithActions(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            2000 ether,
+            ALICE,
+            "",
+            _getRouterHeldOutputSwap(
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            ),
+            pleEncode(postActions)
+        );
+        vm.stopPrank();
+
+        assertEq(tokenOut, DAI_ADDR);
+        assertEq(amountOut, 0);
+        uint256 bobBalance = IERC20(DAI_ADDR).balanceOf(BOB);
+        uint256 aliceBalance = IERC20(DAI_ADDR).balanceOf(ALICE);
+        assertGt(bobBalance, 2000 ether * 6 / 10);
+        assertApproxEqAbs(bobBalance * 4000 / 6000, aliceBalance, 1);
+        assertEq(IERC20(DAI_ADDR).balanceOf(tychoRouterAddr), 0);
+    }
+
+    function testSwapWithActionsVaultDeposit() public {
+        // WETH -> DAI, then deposit the DAI into sDAI
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory postActions = new bytes[](1);
+        postActions[0] = abi.encodePacked(
+            uint8(ActionDispatcher.ActionType.VAULT_DEPOSIT), SDAI_ADDR
+        );
+        (address tokenOut, uint256 amountOut) = tychoRouter.swapWithActions(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            1,
+            ALICE,
+            "",
+            _getRouterHeldOutputSwap(
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            ),
+            pleEncode(postActions)
+        );
+        vm.stopPrank();
+
+        assertEq(tokenOut, SDAI_ADDR);
+        assertGt(amountOut, 0);
+        assertEq(IERC20(SDAI_ADDR).balanceOf(ALICE), amountOut);
+        assertEq(IERC20(DAI_ADDR).balanceOf(tychoRouterAddr), 0);
+    }
+
+    function testSwapWithActionsWrapBeforeSwap() public {
+        // ETH -> WETH in the router, then WETH -> DAI
+        deal(ALICE, 1 ether);
+        vm.startPrank(ALICE);
+
+        bytes[] memory preActions = new bytes[](1);
+        preActions[0] =
+            abi.encodePacked(uint8(ActionDispatcher.ActionType.WRAP));
+        bytes memory swapCall = _getRouterHeldOutputSwap(
+            TokenTransfer.TransferType.TRANSFER_TO_PROTOCOL
+        );
+        (address tokenOut, uint256 amountOut) = tychoRouter.swapWithActions{
+            value: 1 ether
+        }(
+            1 ether,
+            address(0),
+            DAI_ADDR,
+            2000 ether,
+            ALICE,
+            pleEncode(preActions),
+            swapCall,
+            ""
+        );
+        vm.stopPrank();
+
+        assertEq(tokenOut, DAI_ADDR);
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), amountOut);
+        assertEq(ALICE.balance, 0);
+    }
+
+    function testSwapWithActionsUnapprovedCallTarget() public {
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory postActions = new bytes[](1);
+        postActions[0] = abi.encodePacked(
+            uint8(ActionDispatcher.ActionType.CALL), DUMMY, hex"12345678"
+        );
+        bytes memory swapCall = _getRouterHeldOutputSwap(
+            TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+        );
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                ActionDispatcher__UnapprovedTarget.selector, DUMMY
+            )
+        );
+        tychoRouter.swapWithActions(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            1,
+            ALICE,
+            "",
+            swapCall,
+            pleEncode(postActions)
+        );
+        vm.stopPrank();
+    }
+
+    function testSwapWithActionsActionAfterTerminalAction() public {
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory postActions = new bytes[](2);
+        postActions[0] = abi.encodePacked(
+            uint8(ActionDispatcher.ActionType.DISTRIBUTE), BOB, uint16(10000)
+        );
+        postActions[1] = abi.encodePacked(
+            uint8(ActionDispatcher.ActionType.VAULT_DEPOSIT), SDAI_ADDR
+        );
+        bytes memory swapCall = _getRouterHeldOutputSwap(
+            TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+        );
+        vm.expectRevert(ActionDispatcher__ActionAfterTerminalAction.selector);
+        tychoRouter.swapWithActions(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            1,
+            ALICE,
+            "",
+            swapCall,
+            pleEncode(postActions)
+        );
+        vm.stopPrank();
+    }
+
+    function testSwapWithActionsNegativeSlippageAfterActions() public {
+        // The swap's own minimum passes, but the distributed amount is below the minimum
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory postActions = new bytes[](1);
+        postActions[0] = abi.encodePacked(
+            uint8(ActionDispatcher.ActionType.DISTRIBUTE), BOB, uint16(10000)
+        );
+        uint256 expectedAmount = 2018817438608734439722;
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                TychoRouter__NegativeSlippage.selector,
+                expectedAmount,
+                expectedAmount + 1
+            )
+        );
+        tychoRouter.swapWithActions(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            expectedAmount + 1,
+            ALICE,
+            "",
+            _getRouterHeldOutputSwap(
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            ),
+            pleEncode(postActions)
+        );
+        vm.stopPrank();
+    }
+
+    function testSwapWithActionsDistributeInvalidShares() public {
+        // The shares are checked before anything is sent out, so the last
+        // recipient's remainder doesn't underflow first
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory postActions = new bytes[](1);
+        postActions[0] = abi.encodePacked(
+            uint8(ActionDispatcher.ActionType.DISTRIBUTE),
+            BOB,
+            uint16(12000),
+            ALICE,
+            uint16(0)
+        );
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                ActionDispatcher__InvalidShares.selector, 12000
+            )
+        );
+        tychoRouter.swapWithActions(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            1,
+            ALICE,
+            "",
+            _getRouterHeldOutputSwap(
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            ),
+            pleEncode(postActions)
+        );
+        vm.stopPrank();
+    }
+
+    function _callActionTarget() internal returns (address target) {
+        target = address(new MockActionTarget());
+        address[] memory targets = new address[](1);
+        targets[0] = target;
+        vm.prank(EXECUTOR_SETTER);
+        tychoRouter.setActionTargets(targets);
+    }
+
+    function testSwapWithActionsCall() public {
+        // WETH -> DAI, then the target pulls the whole DAI output
+        address target = _callActionTarget();
+        uint256 expectedAmount = 2018817438608734439722;
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory postActions = new bytes[](1);
+        postActions[0] = abi.encodePacked(
+            uint8(ActionDispatcher.ActionType.CALL),
+            target,
+            abi.encodeCall(MockActionTarget.pull, (DAI_ADDR, expectedAmount))
+        );
+        tychoRouter.swapWithActions(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            expectedAmount,
+            ALICE,
+            "",
+            _getRouterHeldOutputSwap(
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            ),
+            pleEncode(postActions)
+        );
+        vm.stopPrank();
+
+        assertEq(IERC20(DAI_ADDR).balanceOf(target), expectedAmount);
+        assertEq(IERC20(DAI_ADDR).balanceOf(tychoRouterAddr), 0);
+    }
+
+    function testSwapWithActionsCallUnconsumedAmount() public {
+        // The target only pulls half of the DAI output
+        address target = _callActionTarget();
+        uint256 expectedAmount = 2018817438608734439722;
+        deal(WETH_ADDR, ALICE, 1 ether);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+
+        bytes[] memory postActions = new bytes[](1);
+        postActions[0] = abi.encodePacked(
+            uint8(ActionDispatcher.ActionType.CALL),
+            target,
+            abi.encodeCall(
+                MockActionTarget.pull, (DAI_ADDR, expectedAmount / 2)
+            )
+        );
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                ActionDispatcher__UnconsumedAmount.selector,
+                expectedAmount,
+                expectedAmount / 2
+            )
+        );
+        tychoRouter.swapWithActions(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            1,
+            ALICE,
+            "",
+            _getRouterHeldOutputSwap(
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            ),
+            pleEncode(postActions)
+        );
+        vm.stopPrank();
+    }
+
+    function testSwapWithActionsInvalidSwapCall() public {
+        // Only the router's swap entry points can be called
+        bytes memory swapCall = abi.encodeWithSelector(
+            tychoRouter.multicall.selector, new bytes[](0)
+        );
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                TychoRouter__InvalidSwapCall.selector,
+                tychoRouter.multicall.selector
+            )
+        );
+        tychoRouter.swapWithActions(
+            1 ether, WETH_ADDR, DAI_ADDR, 1, ALICE, "", swapCall, ""
+        );
+    }
 }
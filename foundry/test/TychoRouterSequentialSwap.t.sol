@@ -302,6 +302,100 @@ contract TychoRouterSequentialSwapTest is TychoRouterTestSetup {
         vm.stopPrank();
     }
 
+    function testSequentialSwapWrapMidRoute() public {
+        // Trade 1 ETH for DAI, wrapping it as an explicit step of the route
+        // 1 ETH   ->   WETH   ->   DAI
+        //      (wrapper)    (univ2)
+        uint256 amountIn = 1 ether;
+        deal(ALICE, amountIn);
+
+        vm.startPrank(ALICE);
+
+        bytes[] memory swaps = new bytes[](2);
+        // ETH -> WETH, sent directly into the next pool
+        swaps[0] = encodeSequentialSwap(
+            address(nativeWrapperExecutor),
+            abi.encodePacked(
+                address(0), WETH_DAI_POOL, TokenTransfer.TransferType.NONE
+            )
+        );
+
+        // WETH -> DAI
+        swaps[1] = encodeSequentialSwap(
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                WETH_ADDR,
+                WETH_DAI_POOL,
+                ALICE,
+                false,
+                TokenTransfer.TransferType.NONE
+            )
+        );
+
+        uint256 amountOut = tychoRouter.sequentialSwap{value: amountIn}(
+            amountIn,
+            address(0),
+            DAI_ADDR,
+            1000 ether, // min amount
+            false,
+            false,
+            ALICE,
+            pleEncode(swaps)
+        );
+
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), amountOut);
+        assertEq(tychoRouterAddr.balance, 0);
+        assertEq(IERC20(WETH_ADDR).balanceOf(tychoRouterAddr), 0);
+
+        vm.stopPrank();
+    }
+
+    function testSequentialSwapUnwrapMidRoute() public {
+        // Trade 3k DAI for ETH, unwrapping it as an explicit step of the route
+        // 3k DAI   ->   WETH   ->   ETH
+        //        (univ2)    (wrapper)
+        uint256 amountIn = 3_000 ether;
+        deal(DAI_ADDR, ALICE, amountIn);
+
+        vm.startPrank(ALICE);
+        IERC20(DAI_ADDR).approve(tychoRouterAddr, amountIn);
+
+        bytes[] memory swaps = new bytes[](2);
+        // DAI -> WETH
+        swaps[0] = encodeSequentialSwap(
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                DAI_ADDR,
+                WETH_DAI_POOL,
+                tychoRouterAddr,
+                true,
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            )
+        );
+
+        // WETH -> ETH
+        swaps[1] = encodeSequentialSwap(
+            address(nativeWrapperExecutor),
+            abi.encodePacked(WETH_ADDR, ALICE, TokenTransfer.TransferType.NONE)
+        );
+
+        uint256 amountOut = tychoRouter.sequentialSwap(
+            amountIn,
+            DAI_ADDR,
+            address(0),
+            1 ether, // min amount
+            false,
+            false,
+            ALICE,
+            pleEncode(swaps)
+        );
+
+        assertEq(ALICE.balance, amountOut);
+        assertEq(IERC20(WETH_ADDR).balanceOf(tychoRouterAddr), 0);
+
+        vm.stopPrank();
+    }
+
     function testCyclicSequentialSwap() public {
         // This test has start and end tokens that are the same
         // The flow is:
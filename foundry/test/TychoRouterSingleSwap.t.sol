@@ -5,6 +5,7 @@ import "@src/executors/UniswapV4Executor.sol";
 import {TychoRouter} from "@src/TychoRouter.sol";
 import "./TychoRouterTestSetup.sol";
 import "./executors/UniswapV4Utils.sol";
+import "./mock/MockOverReportingExecutor.sol";
 import {SafeCallback} from "@uniswap/v4-periphery/src/base/SafeCallback.sol";
 
 contract TychoRouterSingleSwapTest is TychoRouterTestSetup {
@@ -287,6 +288,317 @@ contract TychoRouterSingleSwapTest is TychoRouterTestSetup {
         vm.stopPrank();
     }
 
+    function _feeSettlement(uint16 feeBps, address feeRecipient)
+        private
+        pure
+        returns (TychoRouter.OutputSettlement memory)
+    {
+        return TychoRouter.OutputSettlement({
+            feeBps: feeBps,
+            feeRecipient: feeRecipient,
+            expectedAmountOut: 0,
+            surplusRecipient: address(0),
+            surplusShareBps: 0
+        });
+    }
+
+    function _surplusSettlement(
+        uint256 expectedAmountOut,
+        address surplusRecipient,
+        uint16 surplusShareBps
+    ) private pure returns (TychoRouter.OutputSettlement memory) {
+        return TychoRouter.OutputSettlement({
+            feeBps: 0,
+            feeRecipient: address(0),
+            expectedAmountOut: expectedAmountOut,
+            surplusRecipient: surplusRecipient,
+            surplusShareBps: surplusShareBps
+        });
+    }
+
+    function testSingleSwapWithFee() public {
+        // Trade 1 WETH for DAI with 1 swap on Uniswap V2, charging a 1% fee on the output
+        uint256 amountIn = 1 ether;
+        deal(WETH_ADDR, ALICE, amountIn);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, amountIn);
+
+        // The output needs to go to the router so that it can take the fee
+        bytes memory protocolData = encodeUniswapV2Swap(
+            WETH_ADDR,
+            WETH_DAI_POOL,
+            tychoRouterAddr,
+            false,
+            TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+        );
+        bytes memory swap =
+            encodeSingleSwap(address(usv2Executor), protocolData);
+
+        uint256 amountOut = tychoRouter.singleSwapWithSettlement(
+            amountIn,
+            WETH_ADDR,
+            DAI_ADDR,
+            1990 * 1e18,
+            false,
+            false,
+            ALICE,
+            _feeSettlement(100, BOB),
+            swap
+        );
+
+        uint256 expectedFee = 20188174386087344397;
+        uint256 expectedAmount = 1998629264222647095325;
+        assertEq(amountOut, expectedAmount);
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), expectedAmount);
+        assertEq(IERC20(DAI_ADDR).balanceOf(BOB), expectedFee);
+        assertEq(IERC20(DAI_ADDR).balanceOf(tychoRouterAddr), 0);
+
+        vm.stopPrank();
+    }
+
+    function testSingleSwapWithFeeNegativeSlippage() public {
+        // The min amount is checked against the output after fees
+        uint256 amountIn = 1 ether;
+        deal(WETH_ADDR, ALICE, amountIn);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, amountIn);
+
+        bytes memory protocolData = encodeUniswapV2Swap(
+            WETH_ADDR,
+            WETH_DAI_POOL,
+            tychoRouterAddr,
+            false,
+            TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+        );
+        bytes memory swap =
+            encodeSingleSwap(address(usv2Executor), protocolData);
+
+        // Without fees this min amount would be met
+        uint256 minAmountOut = 2010 * 1e18;
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                TychoRouter__NegativeSlippage.selector,
+                1998629264222647095325,
+                minAmountOut
+            )
+        );
+        tychoRouter.singleSwapWithSettlement(
+            amountIn,
+            WETH_ADDR,
+            DAI_ADDR,
+            minAmountOut,
+            false,
+            false,
+            ALICE,
+            _feeSettlement(100, BOB),
+            swap
+        );
+
+        vm.stopPrank();
+    }
+
+    function testSingleSwapWithFeeUnwrapETH() public {
+        // DAI -> WETH with unwrapping to ETH, charging a 0.5% fee in ETH
+        uint256 amountIn = 3000 ether;
+        deal(DAI_ADDR, ALICE, amountIn);
+
+        vm.startPrank(ALICE);
+        (
+            IAllowanceTransfer.PermitSingle memory permitSingle,
+            bytes memory signature
+        ) = handlePermit2Approval(DAI_ADDR, tychoRouterAddr, amountIn);
+
+        bytes memory protocolData = encodeUniswapV2Swap(
+            DAI_ADDR,
+            WETH_DAI_POOL,
+            tychoRouterAddr,
+            true,
+            TokenTransfer.TransferType.TRANSFER_PERMIT2_TO_PROTOCOL
+        );
+        bytes memory swap =
+            encodeSingleSwap(address(usv2Executor), protocolData);
+
+        uint256 amountOut = tychoRouter.singleSwapWithSettlementPermit2(
+            amountIn,
+            DAI_ADDR,
+            address(0),
+            1000_000000,
+            false,
+            true,
+            ALICE,
+            _feeSettlement(50, BOB),
+            permitSingle,
+            signature,
+            swap
+        );
+
+        uint256 expectedAmount = 1468266483689549218;
+        assertEq(amountOut, expectedAmount);
+        assertEq(ALICE.balance, expectedAmount);
+        assertEq(BOB.balance, 7378223536128388);
+
+        vm.stopPrank();
+    }
+
+    function testSingleSwapInvalidFee() public {
+        vm.expectRevert(
+            abi.encodeWithSelector(TychoRouter__InvalidFee.selector, 10001)
+        );
+        tychoRouter.singleSwapWithSettlement(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            1,
+            false,
+            false,
+            ALICE,
+            _feeSettlement(10001, BOB),
+            ""
+        );
+    }
+
+    function _overReportingSwap(uint256 actualAmount, uint256 reportedAmount)
+        private
+        returns (bytes memory)
+    {
+        MockOverReportingExecutor executor = new MockOverReportingExecutor();
+        address[] memory executors = new address[](1);
+        executors[0] = address(executor);
+        vm.prank(EXECUTOR_SETTER);
+        tychoRouter.setExecutors(executors);
+
+        return encodeSingleSwap(
+            address(executor),
+            abi.encode(address(tokens[0]), actualAmount, reportedAmount)
+        );
+    }
+
+    function testSingleSwapWithFeeOverReportingExecutor() public {
+        // The executor claims more output than it delivered. Only what the
+        // router actually received is settled, so its other funds stay put.
+        tokens[0].mint(tychoRouterAddr, 1000 * 1e18);
+        bytes memory swap = _overReportingSwap(100 * 1e18, 1100 * 1e18);
+
+        vm.prank(ALICE);
+        uint256 amountOut = tychoRouter.singleSwapWithSettlement(
+            1 ether,
+            WETH_ADDR,
+            address(tokens[0]),
+            90 * 1e18,
+            false,
+            false,
+            ALICE,
+            _feeSettlement(100, BOB),
+            swap
+        );
+
+        assertEq(amountOut, 99 * 1e18);
+        assertEq(tokens[0].balanceOf(ALICE), 99 * 1e18);
+        assertEq(tokens[0].balanceOf(BOB), 1e18);
+        assertEq(tokens[0].balanceOf(tychoRouterAddr), 1000 * 1e18);
+    }
+
+    function testSingleSwapWithFeeOverReportingExecutorNegativeSlippage()
+        public
+    {
+        // The reported amount would meet the min amount, the received one does not
+        tokens[0].mint(tychoRouterAddr, 1000 * 1e18);
+        bytes memory swap = _overReportingSwap(100 * 1e18, 1100 * 1e18);
+
+        uint256 minAmountOut = 500 * 1e18;
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                TychoRouter__NegativeSlippage.selector, 99 * 1e18, minAmountOut
+            )
+        );
+        vm.prank(ALICE);
+        tychoRouter.singleSwapWithSettlement(
+            1 ether,
+            WETH_ADDR,
+            address(tokens[0]),
+            minAmountOut,
+            false,
+            false,
+            ALICE,
+            _feeSettlement(100, BOB),
+            swap
+        );
+    }
+
+    function _singleSwapWithSurplus(uint16 surplusShareBps)
+        private
+        returns (uint256 amountOut)
+    {
+        // Trade 1 WETH for DAI with 1 swap on Uniswap V2, expecting 2000 DAI
+        uint256 amountIn = 1 ether;
+        deal(WETH_ADDR, ALICE, amountIn);
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, amountIn);
+
+        bytes memory protocolData = encodeUniswapV2Swap(
+            WETH_ADDR,
+            WETH_DAI_POOL,
+            tychoRouterAddr,
+            false,
+            TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+        );
+        bytes memory swap =
+            encodeSingleSwap(address(usv2Executor), protocolData);
+
+        amountOut = tychoRouter.singleSwapWithSettlement(
+            amountIn,
+            WETH_ADDR,
+            DAI_ADDR,
+            1990 * 1e18,
+            false,
+            false,
+            ALICE,
+            _surplusSettlement(2000 * 1e18, BOB, surplusShareBps),
+            swap
+        );
+        vm.stopPrank();
+    }
+
+    function testSingleSwapSurplusCap() public {
+        // Everything above the expected 2000 DAI goes to the surplus recipient
+        uint256 amountOut = _singleSwapWithSurplus(10000);
+
+        assertEq(amountOut, 2000 * 1e18);
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), 2000 * 1e18);
+        assertEq(IERC20(DAI_ADDR).balanceOf(BOB), 18817438608734439722);
+        assertEq(IERC20(DAI_ADDR).balanceOf(tychoRouterAddr), 0);
+    }
+
+    function testSingleSwapSurplusShare() public {
+        // Half of everything above the expected 2000 DAI goes to the surplus recipient
+        uint256 amountOut = _singleSwapWithSurplus(5000);
+
+        uint256 expectedAmount = 2009408719304367219861;
+        assertEq(amountOut, expectedAmount);
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), expectedAmount);
+        assertEq(IERC20(DAI_ADDR).balanceOf(BOB), 9408719304367219861);
+        assertEq(IERC20(DAI_ADDR).balanceOf(tychoRouterAddr), 0);
+    }
+
+    function testSingleSwapInvalidSurplusShare() public {
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                TychoRouter__InvalidSurplusShare.selector, 10001
+            )
+        );
+        tychoRouter.singleSwapWithSettlement(
+            1 ether,
+            WETH_ADDR,
+            DAI_ADDR,
+            1,
+            false,
+            false,
+            ALICE,
+            _surplusSettlement(2000 * 1e18, BOB, 10001),
+            ""
+        );
+    }
+
     function testSingleSwapIntegration() public {
         // Tests swapping WETH -> DAI on a USV2 pool with regular approvals
         deal(WETH_ADDR, ALICE, 1 ether);
@@ -157,6 +157,376 @@ contract TychoRouterSplitSwapTest is TychoRouterTestSetup {
         assertEq(IERC20(WETH_ADDR).balanceOf(ALICE), 0);
     }
 
+    function _getSplitSwapsWithAmounts(uint256 wethToWbtcAmount)
+        private
+        view
+        returns (bytes[] memory)
+    {
+        // Same graph as _getSplitSwaps, but the WETH -> WBTC swap uses an absolute
+        // input amount instead of a split percentage.
+        bytes[] memory swaps = new bytes[](4);
+
+        // WETH -> WBTC (absolute amount)
+        swaps[0] = encodeSplitSwapWithAmount(
+            uint8(0),
+            uint8(1),
+            uint24(0),
+            wethToWbtcAmount,
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                WETH_ADDR,
+                WETH_WBTC_POOL,
+                tychoRouterAddr,
+                false,
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            )
+        );
+        // WBTC -> USDC
+        swaps[1] = encodeSplitSwapWithAmount(
+            uint8(1),
+            uint8(2),
+            uint24(0),
+            uint256(0),
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                WBTC_ADDR,
+                USDC_WBTC_POOL,
+                ALICE,
+                true,
+                TokenTransfer.TransferType.TRANSFER_TO_PROTOCOL
+            )
+        );
+        // WETH -> DAI (remainder)
+        swaps[2] = encodeSplitSwapWithAmount(
+            uint8(0),
+            uint8(3),
+            uint24(0),
+            uint256(0),
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                WETH_ADDR,
+                WETH_DAI_POOL,
+                tychoRouterAddr,
+                false,
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            )
+        );
+        // DAI -> USDC
+        swaps[3] = encodeSplitSwapWithAmount(
+            uint8(3),
+            uint8(2),
+            uint24(0),
+            uint256(0),
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                DAI_ADDR,
+                DAI_USDC_POOL,
+                ALICE,
+                true,
+                TokenTransfer.TransferType.TRANSFER_TO_PROTOCOL
+            )
+        );
+
+        return swaps;
+    }
+
+    function testSplitSwapWithAmounts() public {
+        // Trade 1 WETH for USDC through DAI and WBTC, sending exactly 0.6 WETH to WBTC
+        uint256 amountIn = 1 ether;
+        deal(WETH_ADDR, ALICE, amountIn);
+
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, amountIn);
+
+        bytes[] memory swaps = _getSplitSwapsWithAmounts(0.6 ether);
+
+        uint256 amountOut = tychoRouter.splitSwapWithAmounts(
+            amountIn,
+            WETH_ADDR,
+            USDC_ADDR,
+            1000_000000, // min amount
+            false,
+            false,
+            4,
+            ALICE,
+            pleEncode(swaps)
+        );
+
+        uint256 usdcBalance = IERC20(USDC_ADDR).balanceOf(ALICE);
+        assertEq(usdcBalance, amountOut);
+        assertGt(usdcBalance, 1980_000000);
+        assertEq(IERC20(WETH_ADDR).balanceOf(ALICE), 0);
+        assertEq(IERC20(WETH_ADDR).balanceOf(tychoRouterAddr), 0);
+    }
+
+    function testSplitSwapWithAmountsExceedingBalance() public {
+        // The absolute amount is larger than the amount in, so the swap must revert
+        uint256 amountIn = 1 ether;
+        deal(WETH_ADDR, ALICE, 2 ether);
+
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 2 ether);
+
+        bytes[] memory swaps = _getSplitSwapsWithAmounts(1.5 ether);
+
+        vm.expectRevert();
+        tychoRouter.splitSwapWithAmounts(
+            amountIn,
+            WETH_ADDR,
+            USDC_ADDR,
+            1000_000000, // min amount
+            false,
+            false,
+            4,
+            ALICE,
+            pleEncode(swaps)
+        );
+        vm.stopPrank();
+    }
+
+    function _getMultiOutputSwaps() private view returns (bytes[] memory) {
+        // Trade 0.5 WETH for DAI (sent to ALICE) and the remaining WETH for WBTC (sent to BOB)
+        bytes[] memory swaps = new bytes[](2);
+
+        // WETH -> DAI (absolute amount)
+        swaps[0] = encodeSplitSwapWithAmount(
+            uint8(0),
+            uint8(1),
+            uint24(0),
+            0.5 ether,
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                WETH_ADDR,
+                WETH_DAI_POOL,
+                ALICE,
+                false,
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            )
+        );
+        // WETH -> WBTC (remainder)
+        swaps[1] = encodeSplitSwapWithAmount(
+            uint8(0),
+            uint8(2),
+            uint24(0),
+            uint256(0),
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                WETH_ADDR,
+                WETH_WBTC_POOL,
+                BOB,
+                false,
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            )
+        );
+        return swaps;
+    }
+
+    function testSplitSwapMultiOutput() public {
+        uint256 amountIn = 1 ether;
+        deal(WETH_ADDR, ALICE, amountIn);
+
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, amountIn);
+
+        TychoRouter.SwapOutput[] memory outputs =
+            new TychoRouter.SwapOutput[](2);
+        outputs[0] = TychoRouter.SwapOutput(DAI_ADDR, 900 ether, ALICE);
+        outputs[1] = TychoRouter.SwapOutput(WBTC_ADDR, 1, BOB);
+
+        uint256[] memory amountsOut = tychoRouter.splitSwapMultiOutput(
+            amountIn,
+            WETH_ADDR,
+            false,
+            3,
+            outputs,
+            pleEncode(_getMultiOutputSwaps())
+        );
+        vm.stopPrank();
+
+        assertEq(amountsOut.length, 2);
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), amountsOut[0]);
+        assertEq(IERC20(WBTC_ADDR).balanceOf(BOB), amountsOut[1]);
+        assertGt(amountsOut[0], 900 ether);
+        assertGt(amountsOut[1], 0);
+        assertEq(IERC20(WETH_ADDR).balanceOf(ALICE), 0);
+        assertEq(IERC20(WETH_ADDR).balanceOf(tychoRouterAddr), 0);
+    }
+
+    function testSplitSwapMultiOutputNegativeSlippage() public {
+        uint256 amountIn = 1 ether;
+        deal(WETH_ADDR, ALICE, amountIn);
+
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, amountIn);
+
+        TychoRouter.SwapOutput[] memory outputs =
+            new TychoRouter.SwapOutput[](2);
+        outputs[0] = TychoRouter.SwapOutput(DAI_ADDR, 900 ether, ALICE);
+        // Half an ether can't buy a whole WBTC
+        outputs[1] = TychoRouter.SwapOutput(WBTC_ADDR, 1e8, BOB);
+
+        vm.expectRevert();
+        tychoRouter.splitSwapMultiOutput(
+            amountIn,
+            WETH_ADDR,
+            false,
+            3,
+            outputs,
+            pleEncode(_getMultiOutputSwaps())
+        );
+        vm.stopPrank();
+    }
+
+    function testSplitSwapMultiOutputInvalidOutputs() public {
+        TychoRouter.SwapOutput[] memory outputs =
+            new TychoRouter.SwapOutput[](0);
+
+        vm.expectRevert(
+            abi.encodeWithSelector(TychoRouter__InvalidOutputs.selector, 0, 3)
+        );
+        tychoRouter.splitSwapMultiOutput(
+            1 ether,
+            WETH_ADDR,
+            false,
+            3,
+            outputs,
+            pleEncode(_getMultiOutputSwaps())
+        );
+    }
+
+    function _getMultiInputSwaps() private view returns (bytes[] memory) {
+        // Trade 1 WETH (through WBTC) and 1000 DAI for USDC
+        bytes[] memory swaps = new bytes[](3);
+
+        // WETH -> WBTC
+        swaps[0] = encodeSplitSwapWithAmount(
+            uint8(0),
+            uint8(2),
+            uint24(0),
+            uint256(0),
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                WETH_ADDR,
+                WETH_WBTC_POOL,
+                tychoRouterAddr,
+                false,
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            )
+        );
+        // WBTC -> USDC
+        swaps[1] = encodeSplitSwapWithAmount(
+            uint8(2),
+            uint8(3),
+            uint24(0),
+            uint256(0),
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                WBTC_ADDR,
+                USDC_WBTC_POOL,
+                ALICE,
+                true,
+                TokenTransfer.TransferType.TRANSFER_TO_PROTOCOL
+            )
+        );
+        // DAI -> USDC
+        swaps[2] = encodeSplitSwapWithAmount(
+            uint8(1),
+            uint8(3),
+            uint24(0),
+            uint256(0),
+            address(usv2Executor),
+            encodeUniswapV2Swap(
+                DAI_ADDR,
+                DAI_USDC_POOL,
+                ALICE,
+                true,
+                TokenTransfer.TransferType.TRANSFER_FROM_TO_PROTOCOL
+            )
+        );
+        return swaps;
+    }
+
+    function testSplitSwapMultiInput() public {
+        deal(WETH_ADDR, ALICE, 1 ether);
+        deal(DAI_ADDR, ALICE, 1000 ether);
+
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+        IERC20(DAI_ADDR).approve(tychoRouterAddr, 1000 ether);
+
+        TychoRouter.SwapInput[] memory inputs = new TychoRouter.SwapInput[](2);
+        inputs[0] = TychoRouter.SwapInput(WETH_ADDR, 1 ether);
+        inputs[1] = TychoRouter.SwapInput(DAI_ADDR, 1000 ether);
+
+        uint256 amountOut = tychoRouter.splitSwapMultiInput(
+            inputs,
+            USDC_ADDR,
+            2900_000000, // min amount
+            4,
+            ALICE,
+            pleEncode(_getMultiInputSwaps())
+        );
+        vm.stopPrank();
+
+        uint256 usdcBalance = IERC20(USDC_ADDR).balanceOf(ALICE);
+        assertEq(usdcBalance, amountOut);
+        assertGt(usdcBalance, 2900_000000);
+        assertEq(IERC20(WETH_ADDR).balanceOf(ALICE), 0);
+        assertEq(IERC20(DAI_ADDR).balanceOf(ALICE), 0);
+        assertEq(IERC20(WETH_ADDR).balanceOf(tychoRouterAddr), 0);
+        assertEq(IERC20(DAI_ADDR).balanceOf(tychoRouterAddr), 0);
+    }
+
+    function testSplitSwapMultiInputInvalidInputs() public {
+        TychoRouter.SwapInput[] memory inputs = new TychoRouter.SwapInput[](0);
+
+        vm.expectRevert(
+            abi.encodeWithSelector(TychoRouter__InvalidInputs.selector, 0, 4)
+        );
+        tychoRouter.splitSwapMultiInput(
+            inputs,
+            USDC_ADDR,
+            2900_000000, // min amount
+            4,
+            ALICE,
+            pleEncode(_getMultiInputSwaps())
+        );
+    }
+
+    function testSplitSwapMultiInputRejectsEther() public {
+        // Native ETH is not a supported input, so sending it must not be accepted
+        deal(WETH_ADDR, ALICE, 1 ether);
+        deal(DAI_ADDR, ALICE, 1000 ether);
+        deal(ALICE, 1 ether);
+
+        vm.startPrank(ALICE);
+        IERC20(WETH_ADDR).approve(tychoRouterAddr, 1 ether);
+        IERC20(DAI_ADDR).approve(tychoRouterAddr, 1000 ether);
+
+        TychoRouter.SwapInput[] memory inputs = new TychoRouter.SwapInput[](2);
+        inputs[0] = TychoRouter.SwapInput(WETH_ADDR, 1 ether);
+        inputs[1] = TychoRouter.SwapInput(DAI_ADDR, 1000 ether);
+
+        (bool success,) = tychoRouterAddr.call{value: 1 ether}(
+            abi.encodeCall(
+                tychoRouter.splitSwapMultiInput,
+                (
+                    inputs,
+                    USDC_ADDR,
+                    2900_000000, // min amount
+                    4,
+                    ALICE,
+                    pleEncode(_getMultiInputSwaps())
+                )
+            )
+        );
+        vm.stopPrank();
+
+        assertFalse(success);
+        assertEq(ALICE.balance, 1 ether);
+        assertEq(tychoRouterAddr.balance, 0);
+    }
+
     function testSplitSwapUndefinedMinAmount() public {
         // Min amount should always be non-zero. If zero, swap attempt should revert.
         uint256 amountIn = 1 ether;
@@ -4,6 +4,7 @@ pragma solidity ^0.8.26;
 import "../src/executors/BalancerV2Executor.sol";
 import "../src/executors/CurveExecutor.sol";
 import "../src/executors/EkuboExecutor.sol";
+import "../src/executors/NativeWrapperExecutor.sol";
 import "../src/executors/UniswapV2Executor.sol";
 import "../src/executors/UniswapV3Executor.sol";
 import "../src/executors/UniswapV4Executor.sol";
@@ -31,7 +32,7 @@ contract TychoRouterExposed is TychoRouter {
         uint256 nTokens,
         bytes calldata swaps
     ) external returns (uint256) {
-        return _splitSwap(amountIn, nTokens, swaps);
+        return _splitSwap(amountIn, nTokens, swaps, false);
     }
 
     function exposedSequentialSwap(uint256 amountIn, bytes calldata swaps)
@@ -52,6 +53,7 @@ contract TychoRouterTestSetup is Constants, Permit2TestHelper {
     BalancerV2Executor public balancerv2Executor;
     EkuboExecutor public ekuboExecutor;
     CurveExecutor public curveExecutor;
+    NativeWrapperExecutor public nativeWrapperExecutor;
     MockERC20[] tokens;
 
     function setUp() public {
@@ -109,8 +111,10 @@ contract TychoRouterTestSetup is Constants, Permit2TestHelper {
         balancerv2Executor = new BalancerV2Executor(PERMIT2_ADDRESS);
         ekuboExecutor = new EkuboExecutor(ekuboCore, PERMIT2_ADDRESS);
         curveExecutor = new CurveExecutor(ETH_ADDR_FOR_CURVE, PERMIT2_ADDRESS);
+        nativeWrapperExecutor =
+            new NativeWrapperExecutor(WETH_ADDR, PERMIT2_ADDRESS);
 
-        address[] memory executors = new address[](7);
+        address[] memory executors = new address[](8);
         executors[0] = address(usv2Executor);
         executors[1] = address(usv3Executor);
         executors[2] = address(pancakev3Executor);
@@ -118,6 +122,7 @@ contract TychoRouterTestSetup is Constants, Permit2TestHelper {
         executors[4] = address(balancerv2Executor);
         executors[5] = address(ekuboExecutor);
         executors[6] = address(curveExecutor);
+        executors[7] = address(nativeWrapperExecutor);
         return executors;
     }
 
@@ -174,6 +179,19 @@ contract TychoRouterTestSetup is Constants, Permit2TestHelper {
         );
     }
 
+    function encodeSplitSwapWithAmount(
+        uint8 tokenInIndex,
+        uint8 tokenOutIndex,
+        uint24 split,
+        uint256 amountIn,
+        address executor,
+        bytes memory protocolData
+    ) internal pure returns (bytes memory) {
+        return abi.encodePacked(
+            tokenInIndex, tokenOutIndex, split, amountIn, executor, protocolData
+        );
+    }
+
     function encodeUniswapV2Swap(
         address tokenIn,
         address target,
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: BUSL-1.1
+pragma solidity ^0.8.26;
+
+import "@src/executors/NativeWrapperExecutor.sol";
+import "@src/executors/TokenTransfer.sol";
+import {Test} from "../../lib/forge-std/src/Test.sol";
+import {Constants} from "../Constants.sol";
+
+contract NativeWrapperExecutorExposed is NativeWrapperExecutor {
+    constructor(address _weth, address _permit2)
+        NativeWrapperExecutor(_weth, _permit2)
+    {}
+
+    function decodeParams(bytes calldata data)
+        external
+        pure
+        returns (
+            address tokenIn,
+            address receiver,
+            TransferType transferType
+        )
+    {
+        return _decodeData(data);
+    }
+
+    receive() external payable {}
+}
+
+contract NativeWrapperExecutorTest is Test, Constants {
+    NativeWrapperExecutorExposed nativeWrapperExposed;
+    IERC20 WETH = IERC20(WETH_ADDR);
+
+    function setUp() public {
+        uint256 forkBlock = 17323404;
+        vm.createSelectFork(vm.rpcUrl("mainnet"), forkBlock);
+        nativeWrapperExposed =
+            new NativeWrapperExecutorExposed(WETH_ADDR, PERMIT2_ADDRESS);
+    }
+
+    function testDecodeParams() public view {
+        bytes memory params = abi.encodePacked(
+            WETH_ADDR, BOB, TokenTransfer.TransferType.NONE
+        );
+
+        (
+            address tokenIn,
+            address receiver,
+            TokenTransfer.TransferType transferType
+        ) = nativeWrapperExposed.decodeParams(params);
+
+        assertEq(tokenIn, WETH_ADDR);
+        assertEq(receiver, BOB);
+        assertEq(
+            uint8(TokenTransfer.TransferType.NONE), uint8(transferType)
+        );
+    }
+
+    function testDecodeParamsInvalidDataLength() public {
+        bytes memory invalidParams = abi.encodePacked(WETH_ADDR, BOB);
+
+        vm.expectRevert(NativeWrapperExecutor__InvalidDataLength.selector);
+        nativeWrapperExposed.decodeParams(invalidParams);
+    }
+
+    function testWrap() public {
+        uint256 amount = 1 ether;
+        deal(address(nativeWrapperExposed), amount);
+        bytes memory protocolData = abi.encodePacked(
+            address(0), BOB, TokenTransfer.TransferType.NONE
+        );
+
+        uint256 amountOut = nativeWrapperExposed.swap(amount, protocolData);
+
+        assertEq(amountOut, amount);
+        assertEq(WETH.balanceOf(BOB), amount);
+        assertEq(address(nativeWrapperExposed).balance, 0);
+    }
+
+    function testUnwrap() public {
+        uint256 amount = 1 ether;
+        deal(WETH_ADDR, address(nativeWrapperExposed), amount);
+        uint256 bobBalanceBefore = BOB.balance;
+        bytes memory protocolData = abi.encodePacked(
+            WETH_ADDR, BOB, TokenTransfer.TransferType.NONE
+        );
+
+        uint256 amountOut = nativeWrapperExposed.swap(amount, protocolData);
+
+        assertEq(amountOut, amount);
+        assertEq(BOB.balance - bobBalanceBefore, amount);
+        assertEq(WETH.balanceOf(address(nativeWrapperExposed)), 0);
+    }
+
+    function testUnwrapKeepsFundsInCaller() public {
+        uint256 amount = 1 ether;
+        deal(WETH_ADDR, address(nativeWrapperExposed), amount);
+        bytes memory protocolData = abi.encodePacked(
+            WETH_ADDR,
+            address(nativeWrapperExposed),
+            TokenTransfer.TransferType.NONE
+        );
+
+        nativeWrapperExposed.swap(amount, protocolData);
+
+        assertEq(address(nativeWrapperExposed).balance, amount);
+    }
+
+    function testInvalidToken() public {
+        bytes memory protocolData = abi.encodePacked(
+            DAI_ADDR, BOB, TokenTransfer.TransferType.NONE
+        );
+
+        vm.expectRevert(
+            abi.encodeWithSelector(
+                NativeWrapperExecutor__InvalidToken.selector, DAI_ADDR
+            )
+        );
+        nativeWrapperExposed.swap(1 ether, protocolData);
+    }
+}
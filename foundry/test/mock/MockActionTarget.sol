@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity ^0.8.26;
+
+import "@openzeppelin/contracts/token/ERC20/IERC20.sol";
+
+/// @dev Target of CALL actions which pulls the given amount of a token from
+/// the caller, whatever amount it was approved.
+contract MockActionTarget {
+    function pull(address token, uint256 amount) external {
+        IERC20(token).transferFrom(msg.sender, address(this), amount);
+    }
+}
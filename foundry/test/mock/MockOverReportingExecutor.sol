@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity ^0.8.26;
+
+import "@interfaces/IExecutor.sol";
+import "./MockERC20.sol";
+
+/// @dev Mints `actualAmount` of the output token to the caller but reports
+/// `reportedAmount` back to it. Meant to be delegatecalled by the router.
+contract MockOverReportingExecutor is IExecutor {
+    function swap(uint256, bytes calldata data)
+        external
+        payable
+        returns (uint256)
+    {
+        (address tokenOut, uint256 actualAmount, uint256 reportedAmount) =
+            abi.decode(data, (address, uint256, uint256));
+        MockERC20(tokenOut).mint(address(this), actualAmount);
+        return reportedAmount;
+    }
+}
@@ -0,0 +1,588 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{DefaultBodyLimit, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use clap::Parser;
+use serde::Deserialize;
+use tycho_common::{hex_bytes::Bytes, models::Chain};
+use tycho_execution::encoding::{
+    errors::EncodingError,
+    evm::{
+        calldata_decoder::CalldataDecoder,
+        config_file::{ConfigFile, RouterConfig},
+        encoder_builders::default_router_address,
+        router_verifier::RouterVerifier,
+        utils::bytes_to_address,
+    },
+    models::Solution,
+    tycho_encoder::TychoEncoder,
+};
+
+#[derive(Parser)]
+/// Serve the Tycho router encoders over HTTP
+///
+/// The encoders of each chain are built once at startup. All endpoints take and return JSON:
+/// * `POST /{chain}/encode`: encodes a solution, in the same format as read by `tycho-encode`, into
+///   a `{"to", "value", "data"}` transaction
+/// * `POST /{chain}/validate`: validates a solution, as `tycho-encode validate` does
+/// * `POST /{chain}/decode`: decodes `{"calldata": "0x...", "executor": "0x..."}`, where the
+///   executor is optional, as `tycho-encode decode` does
+/// * `GET /health`: the router address and protocol count of each chain. For the chains with
+///   `verify_router`, it also checks on-chain that the router isn't paused, and returns a 503
+///   status if it is or if the RPC can't be reached
+/// * `GET /metrics`: request counters, in the Prometheus text format
+///
+/// Failed requests return `{"error": "..."}` with a 4xx or 5xx status. For example:
+/// ```bash
+/// curl -X POST http://127.0.0.1:3000/ethereum/encode \
+///     -H 'Content-Type: application/json' -d @solution.json
+/// ```
+///
+/// The options of each chain can be read from the same `--config` file as `tycho-encode`, which is
+/// also the only way to give the server a signer for Permit2 approvals. The flags override the
+/// options of the file.
+#[command(author, version, about, long_about = None)]
+pub struct Cli {
+    /// Chains to serve, separated by commas. Defaults to the chains of the config file
+    #[arg(short, long, value_delimiter = ',')]
+    chains: Vec<Chain>,
+    /// TOML or JSON configuration file, read as TOML if its extension is `.toml`
+    #[arg(long)]
+    config: Option<String>,
+    #[arg(short, long)]
+    executors_file_path: Option<String>,
+    /// Router address, if a single chain is served. Set the `router_address` of each chain in the
+    /// config file otherwise
+    #[arg(short, long)]
+    router_address: Option<Bytes>,
+    /// Build the encoders even if the router address or an executor address is malformed or the
+    /// zero address
+    #[arg(long)]
+    allow_unchecked_addresses: bool,
+    /// Check on-chain that the router isn't paused and approves the configured executors when
+    /// starting, and that it isn't paused on `/health`
+    #[arg(long)]
+    verify_router: bool,
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:3000")]
+    address: SocketAddr,
+    /// Maximum size of a request body, in bytes
+    #[arg(long, default_value_t = 1024 * 1024)]
+    max_body_size: usize,
+}
+
+/// The endpoints processing a request body, as labelled in the metrics.
+#[derive(Clone, Copy)]
+enum Endpoint {
+    Encode,
+    Validate,
+    Decode,
+}
+
+impl Endpoint {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Endpoint::Encode => "encode",
+            Endpoint::Validate => "validate",
+            Endpoint::Decode => "decode",
+        }
+    }
+}
+
+/// Body of a decode request.
+#[derive(Deserialize)]
+struct DecodeRequest {
+    calldata: String,
+    executor: Option<Bytes>,
+}
+
+/// An error returned to the client, with the HTTP status it's returned with.
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: String) -> Self {
+        ApiError { status, message }
+    }
+}
+
+impl From<EncodingError> for ApiError {
+    fn from(err: EncodingError) -> Self {
+        let status = match err {
+            EncodingError::InvalidInput(_) | EncodingError::FatalError(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            EncodingError::RecoverableError(_) => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        ApiError::new(status, err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(err: serde_json::Error) -> Self {
+        ApiError::new(StatusCode::BAD_REQUEST, format!("Invalid request body: {}", err))
+    }
+}
+
+/// The encoder and decoder of a chain.
+///
+/// # Fields
+/// * `router_address`: Address of the router the encoder encodes for
+/// * `verify_router`: Whether `/health` checks that the router isn't paused
+struct ChainEncoders {
+    encoder: Box<dyn TychoEncoder + Send + Sync>,
+    decoder: CalldataDecoder,
+    router_address: Bytes,
+    verify_router: bool,
+}
+
+/// Number of requests per endpoint and response status.
+#[derive(Default)]
+struct Metrics {
+    requests: Mutex<BTreeMap<(&'static str, u16), u64>>,
+}
+
+impl Metrics {
+    fn record(&self, endpoint: Endpoint, status: StatusCode) {
+        let mut requests = self
+            .requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *requests
+            .entry((endpoint.as_str(), status.as_u16()))
+            .or_default() += 1;
+    }
+
+    fn render(&self) -> String {
+        let requests = self
+            .requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut lines = vec![
+            "# HELP tycho_encode_requests_total Number of processed requests.".to_string(),
+            "# TYPE tycho_encode_requests_total counter".to_string(),
+        ];
+        for ((endpoint, status), count) in requests.iter() {
+            lines.push(format!(
+                "tycho_encode_requests_total{{endpoint=\"{endpoint}\",status=\"{status}\"}} {count}"
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+struct AppState {
+    chains: HashMap<String, ChainEncoders>,
+    metrics: Metrics,
+    /// Checks the routers on `/health`, if a chain verifies its router
+    verifier: Option<RouterVerifier>,
+}
+
+impl AppState {
+    fn new(cli: &Cli, config_file: &ConfigFile) -> Result<Self, Box<dyn Error>> {
+        let served_chains = if !cli.chains.is_empty() {
+            cli.chains.clone()
+        } else if !config_file.chains.is_empty() {
+            config_file.configured_chains()
+        } else {
+            config_file.chain.into_iter().collect()
+        };
+        if served_chains.is_empty() {
+            return Err(
+                "No chain given: pass --chains or configure chains in the config file".into()
+            );
+        }
+        if cli.router_address.is_some() && served_chains.len() > 1 {
+            return Err("--router-address needs a single chain: set the router_address of each \
+                        chain in the config file instead"
+                .into());
+        }
+        // The on-chain lookups read a single `RPC_URL`, so the chains can't use different ones
+        let rpc_urls: BTreeSet<String> = served_chains
+            .iter()
+            .filter_map(|chain| config_file.chain_config(*chain).rpc_url)
+            .collect();
+        if rpc_urls.len() > 1 {
+            return Err("The served chains set different RPC URLs in the config file".into());
+        }
+
+        let flags = RouterConfig {
+            executors_file_path: cli.executors_file_path.clone(),
+            router_address: cli.router_address.clone(),
+            allow_unchecked_addresses: cli
+                .allow_unchecked_addresses
+                .then_some(true),
+            verify_router: cli.verify_router.then_some(true),
+            ..Default::default()
+        };
+        let mut chains = HashMap::new();
+        for chain in served_chains.iter() {
+            config_file.export_rpc_url(*chain);
+            let mut config = config_file.router_config(*chain, true)?;
+            config.update(flags.clone());
+            let router_address = match config.router_address.clone() {
+                Some(router_address) => router_address,
+                None => default_router_address(&(*chain).into())?,
+            };
+            let encoders = ChainEncoders {
+                encoder: config.build_encoder(*chain)?,
+                decoder: CalldataDecoder::new(config.executors_file_path.clone(), (*chain).into())?,
+                router_address,
+                verify_router: config.verify_router.unwrap_or(false),
+            };
+            chains.insert(chain.to_string(), encoders);
+        }
+        let verifier = if chains
+            .values()
+            .any(|encoders| encoders.verify_router)
+        {
+            Some(RouterVerifier::new()?)
+        } else {
+            None
+        };
+        Ok(AppState { chains, metrics: Metrics::default(), verifier })
+    }
+
+    /// Returns the status of each chain, and whether all of them are healthy.
+    fn health(&self) -> (bool, serde_json::Value) {
+        let mut healthy = true;
+        let mut chains = serde_json::Map::new();
+        for (chain, encoders) in self.chains.iter() {
+            let status = match (&self.verifier, encoders.verify_router) {
+                (Some(verifier), true) => {
+                    match bytes_to_address(&encoders.router_address)
+                        .and_then(|router| verifier.is_paused(router))
+                    {
+                        Ok(false) => "ok".to_string(),
+                        Ok(true) => "paused".to_string(),
+                        Err(e) => format!("unreachable: {}", e),
+                    }
+                }
+                _ => "ok".to_string(),
+            };
+            healthy &= status == "ok";
+            chains.insert(
+                chain.clone(),
+                serde_json::json!({
+                    "status": status,
+                    "router_address": encoders.router_address,
+                    "protocols": encoders.decoder.supported_protocols().len(),
+                }),
+            );
+        }
+        let status = if healthy { "ok" } else { "degraded" };
+        (healthy, serde_json::json!({ "status": status, "chains": chains }))
+    }
+
+    /// Processes the body of a request to an endpoint of the given chain.
+    fn process(
+        &self,
+        chain: &str,
+        endpoint: Endpoint,
+        body: &str,
+    ) -> Result<serde_json::Value, ApiError> {
+        let encoders = self.chains.get(chain).ok_or_else(|| {
+            ApiError::new(StatusCode::NOT_FOUND, format!("Chain {} is not served", chain))
+        })?;
+        match endpoint {
+            Endpoint::Encode => {
+                let solution: Solution = serde_json::from_str(body)?;
+                let transactions = encoders
+                    .encoder
+                    .encode_calldata(vec![solution])?;
+                serde_json::to_value(&transactions[0])
+                    .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+            }
+            Endpoint::Validate => {
+                let solution: Solution = serde_json::from_str(body)?;
+                let report = encoders
+                    .encoder
+                    .explain_solution(&solution)?;
+                Ok(serde_json::json!({
+                    "valid": report.is_valid(),
+                    "errors": report.errors,
+                    "strategy": report.strategy,
+                    "groups": report.groups,
+                }))
+            }
+            Endpoint::Decode => {
+                let request: DecodeRequest = serde_json::from_str(body)?;
+                let calldata = request.calldata.trim();
+                let calldata = hex::decode(
+                    calldata
+                        .strip_prefix("0x")
+                        .unwrap_or(calldata),
+                )
+                .map_err(|e| {
+                    ApiError::new(StatusCode::BAD_REQUEST, format!("Invalid hex calldata: {}", e))
+                })?;
+                let executor = request
+                    .executor
+                    .as_ref()
+                    .map(bytes_to_address)
+                    .transpose()?;
+                Ok(encoders
+                    .decoder
+                    .decode(&calldata, executor)?)
+            }
+        }
+    }
+}
+
+/// Processes a request on a blocking thread, since encoding may block on RPC calls, and records
+/// it in the metrics.
+async fn handle(state: Arc<AppState>, chain: String, endpoint: Endpoint, body: String) -> Response {
+    let processing_state = state.clone();
+    let result =
+        tokio::task::spawn_blocking(move || processing_state.process(&chain, endpoint, &body))
+            .await
+            .unwrap_or_else(|e| {
+                Err(ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+            });
+    let (status, output) = match result {
+        Ok(output) => (StatusCode::OK, output),
+        Err(error) => (error.status, serde_json::json!({ "error": error.message })),
+    };
+    state.metrics.record(endpoint, status);
+    (status, Json(output)).into_response()
+}
+
+async fn encode(
+    State(state): State<Arc<AppState>>,
+    Path(chain): Path<String>,
+    body: String,
+) -> Response {
+    handle(state, chain, Endpoint::Encode, body).await
+}
+
+async fn validate(
+    State(state): State<Arc<AppState>>,
+    Path(chain): Path<String>,
+    body: String,
+) -> Response {
+    handle(state, chain, Endpoint::Validate, body).await
+}
+
+async fn decode(
+    State(state): State<Arc<AppState>>,
+    Path(chain): Path<String>,
+    body: String,
+) -> Response {
+    handle(state, chain, Endpoint::Decode, body).await
+}
+
+/// Checks the chains on a blocking thread, since checking a router blocks on RPC calls.
+async fn health(State(state): State<Arc<AppState>>) -> Response {
+    match tokio::task::spawn_blocking(move || state.health()).await {
+        Ok((true, output)) => (StatusCode::OK, Json(output)).into_response(),
+        Ok((false, output)) => (StatusCode::SERVICE_UNAVAILABLE, Json(output)).into_response(),
+        Err(e) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() })))
+                .into_response()
+        }
+    }
+}
+
+async fn metrics(State(state): State<Arc<AppState>>) -> Response {
+    state.metrics.render().into_response()
+}
+
+/// Routes the endpoints to the given state.
+fn app(state: Arc<AppState>, max_body_size: usize) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/metrics", get(metrics))
+        .route("/:chain/encode", post(encode))
+        .route("/:chain/validate", post(validate))
+        .route("/:chain/decode", post(decode))
+        .layer(DefaultBodyLimit::max(max_body_size))
+        .with_state(state)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let config_file = match &cli.config {
+        Some(path) => ConfigFile::load(path)?,
+        None => ConfigFile::default(),
+    };
+    // The encoders are built before starting the server's runtime, since building them may block
+    // on RPC calls
+    let state = Arc::new(AppState::new(&cli, &config_file)?);
+    let app = app(state, cli.max_body_size);
+
+    tokio::runtime::Runtime::new()?.block_on(async {
+        let listener = tokio::net::TcpListener::bind(cli.address).await?;
+        println!("Listening on {}", cli.address);
+        axum::serve(listener, app).await?;
+        Ok::<(), Box<dyn Error>>(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use axum::{body::Body, http::Request};
+    use num_bigint::BigUint;
+    use tower::ServiceExt;
+    use tycho_common::models::protocol::ProtocolComponent;
+    use tycho_execution::encoding::models::Swap;
+
+    use super::*;
+
+    const ROUTER_ADDRESS: &str = "0x3ede3eca2a72b3aecc820e955b36f38437d01395";
+
+    fn test_state() -> Arc<AppState> {
+        let cli = Cli::parse_from([
+            "tycho-encode-server",
+            "--chains",
+            "ethereum",
+            "--executors-file-path",
+            "config/test_executor_addresses.json",
+            "--router-address",
+            ROUTER_ADDRESS,
+        ]);
+        Arc::new(AppState::new(&cli, &ConfigFile::default()).unwrap())
+    }
+
+    fn solution_body() -> String {
+        // Sells 1 WETH for DAI on a Uniswap V2 pool
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let receiver = Bytes::from_str("0xcd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2").unwrap();
+        let solution = Solution {
+            sender: receiver.clone(),
+            receiver,
+            given_token: weth.clone(),
+            given_amount: BigUint::from(1_000_000_000_000_000_000u64),
+            checked_token: dai.clone(),
+            checked_amount: Some(BigUint::from(1u32)),
+            swaps: vec![Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth,
+                dai,
+                0f64,
+            )],
+            ..Default::default()
+        };
+        serde_json::to_string(&solution).unwrap()
+    }
+
+    async fn send(app: Router, request: Request<Body>) -> (StatusCode, String) {
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn post(uri: &str, body: String) -> Request<Body> {
+        Request::post(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_encode() {
+        let (status, body) =
+            send(app(test_state(), 1024 * 1024), post("/ethereum/encode", solution_body())).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let transaction: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(transaction["to"], ROUTER_ADDRESS);
+        assert_eq!(transaction["value"], "0x00");
+        assert!(transaction["data"]
+            .as_str()
+            .unwrap()
+            .starts_with("0x"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_encode_invalid_solution() {
+        let (status, body) =
+            send(app(test_state(), 1024 * 1024), post("/ethereum/encode", "{}".to_string())).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("Invalid request body"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_body_limit() {
+        let (status, _) =
+            send(app(test_state(), 16), post("/ethereum/encode", solution_body())).await;
+
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_metrics() {
+        let state = test_state();
+        send(app(state.clone(), 1024 * 1024), post("/ethereum/encode", solution_body())).await;
+        send(app(state.clone(), 1024 * 1024), post("/base/encode", solution_body())).await;
+
+        let (status, body) = send(
+            app(state, 1024 * 1024),
+            Request::get("/metrics")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("tycho_encode_requests_total{endpoint=\"encode\",status=\"200\"} 1"));
+        assert!(body.contains("tycho_encode_requests_total{endpoint=\"encode\",status=\"404\"} 1"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_health() {
+        let (status, body) = send(
+            app(test_state(), 1024 * 1024),
+            Request::get("/health")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let health: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(health["status"], "ok");
+        assert_eq!(health["chains"]["ethereum"]["router_address"], ROUTER_ADDRESS);
+    }
+
+    #[test]
+    fn test_router_address_needs_a_single_chain() {
+        let cli = Cli::parse_from([
+            "tycho-encode-server",
+            "--chains",
+            "ethereum,base",
+            "--router-address",
+            ROUTER_ADDRESS,
+        ]);
+
+        let result = AppState::new(&cli, &ConfigFile::default());
+
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("--router-address needs a single chain"));
+    }
+}
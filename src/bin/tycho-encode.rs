@@ -1,9 +1,23 @@
-use std::io::{self, Read};
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::{self, BufRead, Read, Write},
+};
 
 use clap::{Parser, Subcommand};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
 use tycho_common::{hex_bytes::Bytes, models::Chain};
 use tycho_execution::encoding::{
-    evm::encoder_builders::{TychoExecutorEncoderBuilder, TychoRouterEncoderBuilder},
+    errors::EncodingError,
+    evm::{
+        calldata_decoder::CalldataDecoder,
+        config_check,
+        config_file::{ConfigFile, RouterConfig, SignerSource},
+        encoder_builders::TychoExecutorEncoderBuilder,
+        route_visualizer::{GraphFormat, RouteVisualizer},
+        utils::bytes_to_address,
+    },
     models::Solution,
     tycho_encoder::TychoEncoder,
 };
@@ -39,78 +53,1031 @@ use tycho_execution::encoding::{
 ///     }],
 /// }
 /// ```
+///
+/// With `--jsonl`, reads one such JSON object per line instead and writes one result per line, in
+/// the same order: the encoded transaction, or `{"error": "..."}` if the solution couldn't be
+/// processed.
+///
+/// The options can also be read from a TOML or JSON file given with `--config`, which sets them
+/// per chain. The flags override the options of the file. For example:
+/// ```toml
+/// chain = "ethereum"
+///
+/// [chains.ethereum]
+/// router_address = "0x..."
+/// executors_file_path = "config/executor_addresses.json"
+/// rpc_url = "https://..."
+/// signer = { env = "SWAPPER_PK" }
+/// # or: signer = { keystore = "keystore.json", password_env = "KEYSTORE_PASSWORD" }
+/// token_in_already_in_router = false
+/// optimize_routes = true
+/// ```
+///
+/// The RPC URL is exported as `RPC_URL` for the on-chain lookups, unless `RPC_URL` is already set.
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    /// Defaults to the chain of the config file
     #[arg(short, long)]
-    chain: Chain,
+    chain: Option<Chain>,
+    /// TOML or JSON configuration file, read as TOML if its extension is `.toml`
+    #[arg(long)]
+    config: Option<String>,
     #[arg(short, long)]
     executors_file_path: Option<String>,
     #[arg(short, long)]
     router_address: Option<Bytes>,
+    /// Private key of the swapper, used to sign Permit2 approvals. Prefer a signer in the config
+    /// file, since the command line is visible in `ps` and the shell history: refused without
+    /// `--allow-raw-key`
     #[arg(short, long)]
     swapper_pk: Option<String>,
+    /// Accept a raw private key in `--swapper-pk`
+    #[arg(long)]
+    allow_raw_key: bool,
     #[arg(short, long)]
     token_in_already_in_router: Option<bool>,
+    /// Build the encoders even if the router address or an executor address is malformed or the
+    /// zero address
+    #[arg(long)]
+    allow_unchecked_addresses: bool,
+    /// Check on-chain that the router isn't paused and approves the configured executors before
+    /// encoding with it. Needs the `RPC_URL` env var or the `rpc_url` of the config file
+    #[arg(long)]
+    verify_router: bool,
+    /// Read one input per line from stdin and write one result per line to stdout
+    #[arg(long, global = true)]
+    jsonl: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Use Tycho router encoding
-    TychoRouter,
+    TychoRouter {
+        /// Reorder and clean up the swaps of the solution before encoding it, to make them
+        /// cheaper to execute
+        #[arg(long)]
+        optimize_routes: bool,
+    },
     /// Use direct execution encoding
-    TychoExecutor,
+    TychoExecutor {
+        /// Output the full `swap(givenAmount, data)` calldata of the executor instead of only the
+        /// protocol data
+        #[arg(long)]
+        full_calldata: bool,
+    },
+    /// Render the route of the solution as a graph instead of encoding it, labelling each swap
+    /// with the transfer type the Tycho router would use
+    Visualize {
+        /// Output format of the graph: dot or mermaid
+        #[arg(long, default_value = "dot")]
+        format: GraphFormat,
+    },
+    /// Validate the solution instead of encoding it, reporting all its problems, the swap
+    /// strategy the Tycho router would use, and the transfer type and receiver of each swap group
+    Validate {
+        /// Reorder and clean up the swaps of the solution before validating it, as when encoding
+        /// it with `tycho-router --optimize-routes`
+        #[arg(long)]
+        optimize_routes: bool,
+    },
+    /// Decode router or executor calldata into a JSON breakdown instead of encoding a solution
+    ///
+    /// Reads the hex calldata from the argument, or from stdin if it's not given.
+    Decode {
+        /// Hex calldata to decode
+        calldata: Option<String>,
+        /// Executor called by the calldata, to decode the protocol data of a direct
+        /// `swap(uint256,bytes)` executor call
+        #[arg(long)]
+        executor: Option<Bytes>,
+    },
+    /// Run as a long-lived process speaking line-delimited JSON-RPC 2.0 over stdin and stdout
+    ///
+    /// Each line of stdin is a request and each response is written on its own line. The methods
+    /// are `encode`, `validate`, `decode`, `supported_protocols` and `set_config`, and take their
+    /// params by name. All of them accept an optional `chain` param, which defaults to the chain
+    /// of the CLI. The encoder of each chain is built on its first use and kept for the next
+    /// requests, until `set_config` changes its configuration.
+    ///
+    /// `set_config` takes the options of a chain of the config file, except `rpc_url`. The private
+    /// key of the swapper is only read from a `signer`, and `allow_unchecked_addresses` needs the
+    /// `--allow-unchecked-addresses` flag.
+    Rpc,
+    /// Inspect the executors and router configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-    // Read from stdin until EOF
-    let mut buffer = String::new();
-    io::stdin()
-        .read_to_string(&mut buffer)
-        .map_err(|e| format!("Failed to read from stdin: {}", e))?;
-
-    if buffer.trim().is_empty() {
-        return Err("No input provided. Expected JSON input on stdin.".into());
-    }
-    let solution: Solution = serde_json::from_str(&buffer)?;
-
-    let chain = cli.chain;
-    let encoder: Box<dyn TychoEncoder> = match cli.command {
-        Commands::TychoRouter => {
-            let mut builder = TychoRouterEncoderBuilder::new().chain(chain);
-            if let Some(config_path) = cli.executors_file_path {
-                builder = builder.executors_file_path(config_path);
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the protocol coverage of each chain of the executors file, and flag the protocols
+    /// with a swap encoder but no executor, or the reverse, and the malformed or zero addresses
+    ///
+    /// The executors file is the one of the flag, else the one configured for the chain in the
+    /// config file, else the default one. Fails if the encoders of a chain can't be built from its
+    /// configuration.
+    Check,
+}
+
+/// Processes the inputs read from stdin, as selected by the subcommand.
+enum InputProcessor {
+    Encoder(Box<dyn TychoEncoder>),
+    Validator(Box<dyn TychoEncoder>),
+    Visualizer(RouteVisualizer, GraphFormat),
+    Decoder(CalldataDecoder, Option<Bytes>),
+}
+
+impl InputProcessor {
+    fn new(cli: Cli, config_file: &ConfigFile, chain: Chain) -> Result<Self, Box<dyn Error>> {
+        let processor = match &cli.command {
+            Commands::TychoRouter { optimize_routes } => InputProcessor::Encoder(
+                resolve_router_config(&cli, config_file, chain, *optimize_routes)?
+                    .build_encoder(chain)?,
+            ),
+            Commands::Validate { optimize_routes } => InputProcessor::Validator(
+                resolve_router_config(&cli, config_file, chain, *optimize_routes)?
+                    .build_encoder(chain)?,
+            ),
+            Commands::TychoExecutor { full_calldata } => {
+                let mut builder = TychoExecutorEncoderBuilder::new()
+                    .chain(chain)
+                    .full_calldata(*full_calldata)
+                    .allow_unchecked_addresses(cli.allow_unchecked_addresses);
+                if let Some(config_path) = cli
+                    .executors_file_path
+                    .clone()
+                    .or(config_file
+                        .chain_config(chain)
+                        .executors_file_path)
+                {
+                    builder = builder.executors_file_path(config_path);
+                }
+                InputProcessor::Encoder(builder.build()?)
+            }
+            Commands::Visualize { format } => {
+                // The signer is only checked for, since the graph doesn't need the key itself
+                let chain_config = config_file.chain_config(chain);
+                InputProcessor::Visualizer(
+                    RouteVisualizer::new(
+                        chain.into(),
+                        cli.swapper_pk.is_some() || chain_config.signer.is_some(),
+                        cli.token_in_already_in_router
+                            .or(chain_config.token_in_already_in_router)
+                            .unwrap_or(false),
+                    )?,
+                    *format,
+                )
             }
-            if let Some(router_address) = cli.router_address {
-                builder = builder.router_address(router_address);
+            Commands::Decode { executor, .. } => InputProcessor::Decoder(
+                CalldataDecoder::new(
+                    cli.executors_file_path
+                        .clone()
+                        .or(config_file
+                            .chain_config(chain)
+                            .executors_file_path),
+                    chain.into(),
+                )?,
+                executor.clone(),
+            ),
+            Commands::Rpc => return Err("The rpc mode doesn't process single inputs".into()),
+            Commands::Config { .. } => {
+                return Err("The config commands don't process inputs".into())
+            }
+        };
+        Ok(processor)
+    }
+
+    /// Processes an input into its output: the encoded transaction of a solution, the rendered
+    /// graph of a solution or the breakdown of some calldata.
+    fn process(&self, input: &str) -> Result<Value, Box<dyn Error>> {
+        match self {
+            InputProcessor::Encoder(encoder) => {
+                Ok(encode_solution(encoder.as_ref(), serde_json::from_str(input)?)?)
             }
-            if let Some(swapper_pk) = cli.swapper_pk {
-                builder = builder.swapper_pk(swapper_pk);
+            InputProcessor::Validator(encoder) => {
+                Ok(validate_solution(encoder.as_ref(), &serde_json::from_str(input)?)?)
             }
-            if let Some(token_in_already_in_router) = cli.token_in_already_in_router {
-                builder = builder.token_in_already_in_router(token_in_already_in_router);
+            InputProcessor::Visualizer(visualizer, format) => {
+                let solution: Solution = serde_json::from_str(input)?;
+                Ok(Value::String(visualizer.render(&solution, *format)?))
+            }
+            InputProcessor::Decoder(decoder, executor) => {
+                Ok(decode_calldata(decoder, input, executor.as_ref())?)
             }
-            builder.build()?
         }
-        Commands::TychoExecutor => TychoExecutorEncoderBuilder::new()
-            .chain(chain)
-            .build()?,
-    };
+    }
+}
 
+/// Encodes a solution into its transaction.
+fn encode_solution(encoder: &dyn TychoEncoder, solution: Solution) -> Result<Value, EncodingError> {
     let transactions = encoder.encode_calldata(vec![solution])?;
-    let encoded = serde_json::json!({
-        "to": format!("0x{}", hex::encode(&transactions[0].to)),
-        "value": format!("0x{}", hex::encode(transactions[0].value.to_bytes_be())),
-        "data": format!("0x{}", hex::encode(&transactions[0].data)),
-    });
-    // Output the encoded result as JSON to stdout
-    println!(
-        "{}",
-        serde_json::to_string(&encoded)
-            .map_err(|e| format!("Failed to serialize output: {}", e))?
-    );
+    serde_json::to_value(&transactions[0])
+        .map_err(|e| EncodingError::FatalError(format!("Failed to serialize transaction: {}", e)))
+}
 
+/// Validates a solution into its report.
+fn validate_solution(
+    encoder: &dyn TychoEncoder,
+    solution: &Solution,
+) -> Result<Value, EncodingError> {
+    let report = encoder.explain_solution(solution)?;
+    Ok(json!({
+        "valid": report.is_valid(),
+        "errors": report.errors,
+        "strategy": report.strategy,
+        "groups": report.groups,
+    }))
+}
+
+/// Decodes hex calldata into its breakdown.
+fn decode_calldata(
+    decoder: &CalldataDecoder,
+    calldata: &str,
+    executor: Option<&Bytes>,
+) -> Result<Value, EncodingError> {
+    let calldata = calldata.trim();
+    let calldata = hex::decode(
+        calldata
+            .strip_prefix("0x")
+            .unwrap_or(calldata),
+    )
+    .map_err(|e| EncodingError::InvalidInput(format!("Invalid hex calldata: {}", e)))?;
+    let executor = executor
+        .map(bytes_to_address)
+        .transpose()?;
+    decoder.decode(&calldata, executor)
+}
+
+/// Returns the options set by the flags. The boolean flags are only set if they're given, so that
+/// they don't override the config file otherwise.
+fn cli_router_config(cli: &Cli, optimize_routes: bool) -> RouterConfig {
+    RouterConfig {
+        executors_file_path: cli.executors_file_path.clone(),
+        router_address: cli.router_address.clone(),
+        swapper_pk: cli.swapper_pk.clone(),
+        token_in_already_in_router: cli.token_in_already_in_router,
+        optimize_routes: optimize_routes.then_some(true),
+        allow_unchecked_addresses: cli
+            .allow_unchecked_addresses
+            .then_some(true),
+        verify_router: cli.verify_router.then_some(true),
+    }
+}
+
+/// Returns the options of the config file for the given chain, overridden by the flags.
+fn resolve_router_config(
+    cli: &Cli,
+    config_file: &ConfigFile,
+    chain: Chain,
+    optimize_routes: bool,
+) -> Result<RouterConfig, EncodingError> {
+    let mut config = config_file.router_config(chain, cli.swapper_pk.is_none())?;
+    config.update(cli_router_config(cli, optimize_routes));
+    Ok(config)
+}
+
+/// A JSON-RPC 2.0 error object.
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    const PARSE_ERROR: i64 = -32700;
+    const INVALID_REQUEST: i64 = -32600;
+    const METHOD_NOT_FOUND: i64 = -32601;
+    const INVALID_PARAMS: i64 = -32602;
+    /// Code of the errors raised by the encoders, in the range reserved for server errors
+    const ENCODING_ERROR: i64 = -32000;
+
+    fn new(code: i64, message: String) -> Self {
+        RpcError { code, message, data: None }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut error = json!({ "code": self.code, "message": self.message });
+        if let Some(data) = &self.data {
+            error["data"] = data.clone();
+        }
+        error
+    }
+}
+
+impl From<EncodingError> for RpcError {
+    fn from(err: EncodingError) -> Self {
+        let kind = match err {
+            EncodingError::InvalidInput(_) => "invalid_input",
+            EncodingError::FatalError(_) => "fatal",
+            EncodingError::RecoverableError(_) => "recoverable",
+        };
+        RpcError {
+            code: RpcError::ENCODING_ERROR,
+            message: err.to_string(),
+            data: Some(json!({ "kind": kind })),
+        }
+    }
+}
+
+/// Params of the `encode` and `validate` methods.
+#[derive(Deserialize)]
+struct SolutionParams {
+    chain: Option<Chain>,
+    solution: Solution,
+}
+
+/// Params of the `decode` method.
+#[derive(Deserialize)]
+struct DecodeParams {
+    chain: Option<Chain>,
+    calldata: String,
+    executor: Option<Bytes>,
+}
+
+/// Params of the `supported_protocols` method.
+#[derive(Deserialize)]
+struct ChainParams {
+    chain: Option<Chain>,
+}
+
+/// Params of the `set_config` method: the chain to configure and the options to override.
+///
+/// The private key of the swapper can only be read from a signer source, as in the config file,
+/// and not given raw over the RPC channel.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigParams {
+    chain: Option<Chain>,
+    executors_file_path: Option<String>,
+    router_address: Option<Bytes>,
+    signer: Option<SignerSource>,
+    token_in_already_in_router: Option<bool>,
+    optimize_routes: Option<bool>,
+    allow_unchecked_addresses: Option<bool>,
+    verify_router: Option<bool>,
+}
+
+/// The encoder and decoder of a chain.
+struct ChainEncoders {
+    encoder: Box<dyn TychoEncoder>,
+    decoder: CalldataDecoder,
+}
+
+/// Serves JSON-RPC 2.0 requests, keeping the encoders of each chain alive between requests.
+///
+/// # Fields
+/// * `default_chain`: Chain of the requests which don't set one
+/// * `default_config`: Configuration of the chains which weren't configured with `set_config` or
+///   the config file. The router address of the CLI only applies to the default chain.
+/// * `configs`: Configuration of each chain configured with `set_config` or the config file
+/// * `encoders`: Encoders of each chain, built on their first use
+/// * `allow_unchecked_addresses`: Whether `set_config` may disable the address checks, which needs
+///   the `--allow-unchecked-addresses` flag
+struct RpcServer {
+    default_chain: Chain,
+    default_config: RouterConfig,
+    configs: HashMap<String, RouterConfig>,
+    encoders: HashMap<String, ChainEncoders>,
+    allow_unchecked_addresses: bool,
+}
+
+impl RpcServer {
+    fn new(cli: &Cli, config_file: &ConfigFile, chain: Chain) -> Result<Self, Box<dyn Error>> {
+        let default_config = RouterConfig { router_address: None, ..cli_router_config(cli, false) };
+        let mut configs = HashMap::new();
+        for configured_chain in config_file.configured_chains() {
+            let mut config =
+                config_file.router_config(configured_chain, cli.swapper_pk.is_none())?;
+            config.update(default_config.clone());
+            configs.insert(configured_chain.to_string(), config);
+        }
+        configs.insert(chain.to_string(), resolve_router_config(cli, config_file, chain, false)?);
+        Ok(RpcServer {
+            default_chain: chain,
+            default_config,
+            configs,
+            encoders: HashMap::new(),
+            allow_unchecked_addresses: cli.allow_unchecked_addresses,
+        })
+    }
+
+    /// Answers the requests read from stdin until EOF.
+    fn run(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut stdout = io::stdout().lock();
+        for line in io::stdin().lock().lines() {
+            let line = line.map_err(|e| format!("Failed to read from stdin: {}", e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_line(&line) {
+                writeln!(stdout, "{}", response)?;
+                stdout.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Answers a line holding a request or a batch of requests. Notifications, requests without an
+    /// id, get no response.
+    fn handle_line(&mut self, line: &str) -> Option<Value> {
+        match serde_json::from_str::<Value>(line) {
+            Ok(Value::Array(requests)) => {
+                if requests.is_empty() {
+                    return Some(error_response(
+                        Value::Null,
+                        RpcError::new(RpcError::INVALID_REQUEST, "Empty batch".to_string()),
+                    ));
+                }
+                let responses: Vec<Value> = requests
+                    .into_iter()
+                    .filter_map(|request| self.handle_request(request))
+                    .collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(request) => self.handle_request(request),
+            Err(e) => Some(error_response(
+                Value::Null,
+                RpcError::new(RpcError::PARSE_ERROR, format!("Parse error: {}", e)),
+            )),
+        }
+    }
+
+    fn handle_request(&mut self, request: Value) -> Option<Value> {
+        let id = request.get("id").cloned();
+        let method = request
+            .get("method")
+            .and_then(Value::as_str);
+        let (Some(method), Some("2.0")) = (
+            method,
+            request
+                .get("jsonrpc")
+                .and_then(Value::as_str),
+        ) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                RpcError::new(RpcError::INVALID_REQUEST, "Invalid request".to_string()),
+            ));
+        };
+        let params = request
+            .get("params")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        let result = self.call(method, params);
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "encode" => {
+                let params: SolutionParams = parse_params(params)?;
+                let encoders = self.get_encoders(params.chain)?;
+                Ok(encode_solution(encoders.encoder.as_ref(), params.solution)?)
+            }
+            "validate" => {
+                let params: SolutionParams = parse_params(params)?;
+                let encoders = self.get_encoders(params.chain)?;
+                Ok(validate_solution(encoders.encoder.as_ref(), &params.solution)?)
+            }
+            "decode" => {
+                let params: DecodeParams = parse_params(params)?;
+                let encoders = self.get_encoders(params.chain)?;
+                Ok(decode_calldata(&encoders.decoder, &params.calldata, params.executor.as_ref())?)
+            }
+            "supported_protocols" => {
+                let params: ChainParams = parse_params(params)?;
+                let encoders = self.get_encoders(params.chain)?;
+                Ok(json!(encoders.decoder.supported_protocols()))
+            }
+            "set_config" => {
+                let params: ConfigParams = parse_params(params)?;
+                if params.allow_unchecked_addresses == Some(true) && !self.allow_unchecked_addresses
+                {
+                    return Err(RpcError::new(
+                        RpcError::INVALID_PARAMS,
+                        "allow_unchecked_addresses needs the --allow-unchecked-addresses flag"
+                            .to_string(),
+                    ));
+                }
+                let chain = params
+                    .chain
+                    .unwrap_or(self.default_chain)
+                    .to_string();
+                let swapper_pk = params
+                    .signer
+                    .map(|signer| signer.private_key())
+                    .transpose()?;
+                let mut config = self.get_config(&chain);
+                config.update(RouterConfig {
+                    executors_file_path: params.executors_file_path,
+                    router_address: params.router_address,
+                    swapper_pk,
+                    token_in_already_in_router: params.token_in_already_in_router,
+                    optimize_routes: params.optimize_routes,
+                    allow_unchecked_addresses: params.allow_unchecked_addresses,
+                    verify_router: params.verify_router,
+                });
+                self.configs
+                    .insert(chain.clone(), config);
+                // The encoders are rebuilt with the new configuration on their next use
+                self.encoders.remove(&chain);
+                Ok(Value::Bool(true))
+            }
+            _ => Err(RpcError::new(
+                RpcError::METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        }
+    }
+
+    fn get_config(&self, chain: &str) -> RouterConfig {
+        self.configs
+            .get(chain)
+            .cloned()
+            .unwrap_or_else(|| self.default_config.clone())
+    }
+
+    /// Returns the encoders of the given chain, building them if they're not built yet.
+    fn get_encoders(&mut self, chain: Option<Chain>) -> Result<&ChainEncoders, RpcError> {
+        let chain = chain.unwrap_or(self.default_chain);
+        let key = chain.to_string();
+        if !self.encoders.contains_key(&key) {
+            let config = self.get_config(&key);
+            let encoders = ChainEncoders {
+                encoder: config.build_encoder(chain)?,
+                decoder: CalldataDecoder::new(config.executors_file_path.clone(), chain.into())?,
+            };
+            self.encoders
+                .insert(key.clone(), encoders);
+        }
+        Ok(&self.encoders[&key])
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(RpcError::INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() })
+}
+
+/// Prints the report of the configuration of each chain, and fails if a chain has an invalid
+/// configuration.
+fn check_config(executors_file_path: Option<String>) -> Result<(), Box<dyn Error>> {
+    let reports = config_check::check_config(executors_file_path)?;
+    println!("{}", serde_json::to_string_pretty(&reports)?);
+    let invalid_chains: Vec<&str> = reports
+        .iter()
+        .filter(|report| !report.is_valid())
+        .map(|report| report.chain.as_str())
+        .collect();
+    if !invalid_chains.is_empty() {
+        return Err(format!("Invalid configuration of: {}", invalid_chains.join(", ")).into());
+    }
+    Ok(())
+}
+
+/// Refuses the private key of `--swapper-pk` unless `--allow-raw-key` is given.
+fn check_raw_key(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    if cli.swapper_pk.is_some() && !cli.allow_raw_key {
+        return Err("Refusing the private key of --swapper-pk, which is visible in `ps` and the \
+                    shell history. Set a signer in the config file instead, or pass \
+                    --allow-raw-key to use it anyway."
+            .into());
+    }
+    Ok(())
+}
+
+/// Processes one input per line and writes one output per line, in the same order.
+fn process_lines(
+    processor: &InputProcessor,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), Box<dyn Error>> {
+    for line in input.lines() {
+        let line = line.map_err(|e| format!("Failed to read from stdin: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // A failing input is reported on its line and doesn't stop the batch
+        let result = processor
+            .process(&line)
+            .unwrap_or_else(|e| json!({ "error": e.to_string() }));
+        writeln!(output, "{}", result)?;
+    }
     Ok(())
 }
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    check_raw_key(&cli)?;
+    let config_file = match &cli.config {
+        Some(path) => ConfigFile::load(path)?,
+        None => ConfigFile::default(),
+    };
+    let chain = cli.chain.or(config_file.chain);
+    if let Commands::Config { command: ConfigCommands::Check } = &cli.command {
+        // The executors file of the configured chain is checked unless the flag overrides it
+        let chain_config = chain
+            .map(|chain| config_file.chain_config(chain))
+            .unwrap_or_default();
+        return check_config(
+            cli.executors_file_path
+                .clone()
+                .or(chain_config.executors_file_path),
+        );
+    }
+    let chain = chain.ok_or("No chain given: pass --chain or set the chain of the config file")?;
+    config_file.export_rpc_url(chain);
+
+    if matches!(cli.command, Commands::Rpc) {
+        return RpcServer::new(&cli, &config_file, chain)?.run();
+    }
+    let jsonl = cli.jsonl;
+    let calldata = match &cli.command {
+        Commands::Decode { calldata, .. } => calldata.clone(),
+        _ => None,
+    };
+    // The processor is built once, to be reused for every input
+    let processor = InputProcessor::new(cli, &config_file, chain)?;
+
+    if jsonl {
+        return process_lines(&processor, io::stdin().lock(), io::stdout().lock());
+    }
+
+    let input = if let Some(calldata) = calldata {
+        calldata
+    } else {
+        // Read from stdin until EOF
+        let mut buffer = String::new();
+        io::stdin()
+            .read_to_string(&mut buffer)
+            .map_err(|e| format!("Failed to read from stdin: {}", e))?;
+        buffer
+    };
+
+    if input.trim().is_empty() {
+        return Err("No input provided. Expected input on stdin.".into());
+    }
+
+    match processor.process(&input)? {
+        // Graphs are output as is
+        Value::String(graph) => print!("{}", graph),
+        // Decoded calldata and validation reports are meant to be read
+        decoded
+            if matches!(processor, InputProcessor::Decoder(..) | InputProcessor::Validator(..)) =>
+        {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&decoded)
+                    .map_err(|e| format!("Failed to serialize output: {}", e))?
+            )
+        }
+        // Output the encoded result as JSON to stdout
+        encoded => println!(
+            "{}",
+            serde_json::to_string(&encoded)
+                .map_err(|e| format!("Failed to serialize output: {}", e))?
+        ),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::BigUint;
+    use tycho_common::models::protocol::ProtocolComponent;
+    use tycho_execution::encoding::models::Swap;
+
+    use super::*;
+
+    const ROUTER_ADDRESS: &str = "0x3ede3eca2a72b3aecc820e955b36f38437d01395";
+    const OTHER_ROUTER_ADDRESS: &str = "0x1111111111111111111111111111111111111111";
+
+    /// Parses the given arguments after the chain, executors file and router address flags.
+    fn cli(args: &[&str]) -> Cli {
+        let flags = [
+            "tycho-encode",
+            "--chain",
+            "ethereum",
+            "--executors-file-path",
+            "config/test_executor_addresses.json",
+            "--router-address",
+            ROUTER_ADDRESS,
+        ];
+        Cli::parse_from(flags.iter().chain(args))
+    }
+
+    fn solution() -> Solution {
+        // Sells 1 WETH for DAI on a Uniswap V2 pool
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let receiver = Bytes::from_str("0xcd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2").unwrap();
+        Solution {
+            sender: receiver.clone(),
+            receiver,
+            given_token: weth.clone(),
+            given_amount: BigUint::from(1_000_000_000_000_000_000u64),
+            checked_token: dai.clone(),
+            checked_amount: Some(BigUint::from(1u32)),
+            swaps: vec![Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth,
+                dai,
+                0f64,
+            )],
+            ..Default::default()
+        }
+    }
+
+    fn rpc_server(args: &[&str]) -> RpcServer {
+        RpcServer::new(&cli(args), &ConfigFile::default(), Chain::Ethereum).unwrap()
+    }
+
+    fn rpc_request(id: Option<u64>, method: &str, params: Value) -> Value {
+        let mut request = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        if let Some(id) = id {
+            request["id"] = json!(id);
+        }
+        request
+    }
+
+    #[test]
+    fn test_check_raw_key() {
+        let pk = "0x123456789abcdef123456789abcdef123456789abcdef123456789abcdef1234";
+
+        assert!(check_raw_key(&cli(&["tycho-router"])).is_ok());
+        assert!(check_raw_key(&cli(&["--swapper-pk", pk, "tycho-router"])).is_err());
+        assert!(
+            check_raw_key(&cli(&["--swapper-pk", pk, "--allow-raw-key", "tycho-router"])).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_resolve_router_config() {
+        let config_file: ConfigFile = toml::from_str(&format!(
+            r#"
+[chains.ethereum]
+router_address = "{}"
+executors_file_path = "config/executor_addresses.json"
+token_in_already_in_router = true
+optimize_routes = true
+verify_router = true
+"#,
+            OTHER_ROUTER_ADDRESS
+        ))
+        .unwrap();
+        let cli = Cli::parse_from([
+            "tycho-encode",
+            "--chain",
+            "ethereum",
+            "--router-address",
+            ROUTER_ADDRESS,
+            "--allow-unchecked-addresses",
+            "tycho-router",
+        ]);
+
+        let config = resolve_router_config(&cli, &config_file, Chain::Ethereum, false).unwrap();
+
+        // The flags given override the config file, which sets the other options
+        assert_eq!(config.router_address, Some(Bytes::from_str(ROUTER_ADDRESS).unwrap()));
+        assert_eq!(config.executors_file_path, Some("config/executor_addresses.json".to_string()));
+        assert_eq!(config.token_in_already_in_router, Some(true));
+        assert_eq!(config.optimize_routes, Some(true));
+        assert_eq!(config.allow_unchecked_addresses, Some(true));
+        assert_eq!(config.verify_router, Some(true));
+
+        let cli = Cli::parse_from([
+            "tycho-encode",
+            "--chain",
+            "ethereum",
+            "--token-in-already-in-router",
+            "false",
+            "tycho-router",
+        ]);
+        let config = resolve_router_config(&cli, &config_file, Chain::Ethereum, false).unwrap();
+
+        assert_eq!(config.router_address, Some(Bytes::from_str(OTHER_ROUTER_ADDRESS).unwrap()));
+        assert_eq!(config.token_in_already_in_router, Some(false));
+        assert_eq!(config.allow_unchecked_addresses, None);
+    }
+
+    #[test]
+    fn test_process_lines() {
+        let processor = InputProcessor::new(
+            cli(&["--jsonl", "tycho-router"]),
+            &ConfigFile::default(),
+            Chain::Ethereum,
+        )
+        .unwrap();
+        let solution = serde_json::to_string(&solution()).unwrap();
+        let input = format!("{}\n\nnot a solution\n{}\n", solution, solution);
+        let mut output = Vec::new();
+
+        process_lines(&processor, input.as_bytes(), &mut output).unwrap();
+
+        // Empty lines are skipped and a failing line doesn't stop the batch
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["to"], ROUTER_ADDRESS);
+        assert!(lines[1]["error"]
+            .as_str()
+            .unwrap()
+            .contains("expected"));
+        assert_eq!(lines[2], lines[0]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let processor =
+            InputProcessor::new(cli(&["validate"]), &ConfigFile::default(), Chain::Ethereum)
+                .unwrap();
+        let invalid_solution = Solution { exact_out: true, ..solution() };
+
+        let report = processor
+            .process(&serde_json::to_string(&solution()).unwrap())
+            .unwrap();
+        let invalid_report = processor
+            .process(&serde_json::to_string(&invalid_solution).unwrap())
+            .unwrap();
+
+        assert_eq!(report["valid"], true);
+        assert_eq!(report["errors"], json!([]));
+        assert_eq!(report["strategy"], "single");
+        assert_eq!(
+            report["groups"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(report["groups"][0]["protocol_system"], "uniswap_v2");
+        assert_eq!(invalid_report["valid"], false);
+        assert_eq!(
+            invalid_report["errors"],
+            json!(["Fatal error: Currently only exact input solutions are supported"])
+        );
+    }
+
+    #[test]
+    fn test_rpc_encode() {
+        let mut server = rpc_server(&["rpc"]);
+
+        let response = server
+            .handle_line(
+                &rpc_request(Some(1), "encode", json!({ "solution": solution() })).to_string(),
+            )
+            .unwrap();
+
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["to"], ROUTER_ADDRESS);
+        assert_eq!(response["result"]["value"], "0x00");
+    }
+
+    #[test]
+    fn test_rpc_errors() {
+        let mut server = rpc_server(&["rpc"]);
+        let exact_out_solution = Solution { exact_out: true, ..solution() };
+        let mut error = |request: String| {
+            let response = server.handle_line(&request).unwrap();
+            response["error"].clone()
+        };
+
+        assert_eq!(error("{".to_string())["code"], RpcError::PARSE_ERROR);
+        assert_eq!(error("[]".to_string())["code"], RpcError::INVALID_REQUEST);
+        assert_eq!(
+            error(json!({ "id": 1, "method": "encode" }).to_string())["code"],
+            RpcError::INVALID_REQUEST
+        );
+        assert_eq!(
+            error(rpc_request(Some(1), "swap", json!({})).to_string())["code"],
+            RpcError::METHOD_NOT_FOUND
+        );
+        assert_eq!(
+            error(rpc_request(Some(1), "encode", json!({})).to_string())["code"],
+            RpcError::INVALID_PARAMS
+        );
+        let encoding_error = error(
+            rpc_request(Some(1), "encode", json!({ "solution": exact_out_solution })).to_string(),
+        );
+        assert_eq!(encoding_error["code"], RpcError::ENCODING_ERROR);
+        assert_eq!(
+            encoding_error["message"],
+            "Fatal error: Currently only exact input solutions are supported"
+        );
+        assert_eq!(encoding_error["data"]["kind"], "fatal");
+    }
+
+    #[test]
+    fn test_rpc_batch() {
+        let mut server = rpc_server(&["rpc"]);
+        let batch = json!([
+            rpc_request(Some(1), "encode", json!({ "solution": solution() })),
+            rpc_request(Some(2), "swap", json!({})),
+            rpc_request(None, "supported_protocols", json!({})),
+        ]);
+
+        let response = server
+            .handle_line(&batch.to_string())
+            .unwrap();
+        let notification = server.handle_line(
+            &rpc_request(None, "supported_protocols", json!({ "chain": "ethereum" })).to_string(),
+        );
+
+        // The notification gets no response
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["to"], ROUTER_ADDRESS);
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(responses[1]["error"]["code"], RpcError::METHOD_NOT_FOUND);
+        assert_eq!(notification, None);
+    }
+
+    #[test]
+    fn test_rpc_set_config() {
+        let mut server = rpc_server(&["rpc"]);
+        let encode = rpc_request(Some(2), "encode", json!({ "solution": solution() })).to_string();
+        server
+            .handle_line(&rpc_request(Some(1), "supported_protocols", json!({})).to_string())
+            .unwrap();
+        assert!(server.encoders.contains_key("ethereum"));
+
+        let response = server
+            .handle_line(
+                &rpc_request(
+                    Some(1),
+                    "set_config",
+                    json!({ "router_address": OTHER_ROUTER_ADDRESS }),
+                )
+                .to_string(),
+            )
+            .unwrap();
+
+        // The encoders are rebuilt with the new router address, and keep the other options
+        assert_eq!(response["result"], true);
+        assert!(!server.encoders.contains_key("ethereum"));
+        assert_eq!(
+            server
+                .get_config("ethereum")
+                .executors_file_path,
+            Some("config/test_executor_addresses.json".to_string())
+        );
+        let response = server.handle_line(&encode).unwrap();
+        assert_eq!(response["result"]["to"], OTHER_ROUTER_ADDRESS);
+    }
+
+    #[test]
+    fn test_rpc_set_config_restrictions() {
+        let mut server = rpc_server(&["rpc"]);
+        let mut unchecked_server = rpc_server(&["--allow-unchecked-addresses", "rpc"]);
+        let raw_key = rpc_request(
+            Some(1),
+            "set_config",
+            json!({ "swapper_pk": "0x123456789abcdef123456789abcdef123456789abcdef123456789abcdef1234" }),
+        )
+        .to_string();
+        let unchecked_addresses =
+            rpc_request(Some(1), "set_config", json!({ "allow_unchecked_addresses": true }))
+                .to_string();
+
+        // A raw private key is refused, and the address checks are only disabled with the flag
+        assert_eq!(
+            server.handle_line(&raw_key).unwrap()["error"]["code"],
+            RpcError::INVALID_PARAMS
+        );
+        assert_eq!(
+            server
+                .handle_line(&unchecked_addresses)
+                .unwrap()["error"]["code"],
+            RpcError::INVALID_PARAMS
+        );
+        assert_eq!(
+            unchecked_server
+                .handle_line(&unchecked_addresses)
+                .unwrap()["result"],
+            true
+        );
+    }
+}
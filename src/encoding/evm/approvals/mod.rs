@@ -1,2 +1,3 @@
 pub mod permit2;
+#[cfg(feature = "rpc")]
 pub mod protocol_approvals_manager;
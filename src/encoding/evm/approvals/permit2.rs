@@ -1,41 +1,59 @@
+#[cfg(feature = "rpc")]
 use std::{str::FromStr, sync::Arc};
 
 use alloy::{
-    primitives::{aliases::U48, Address, Bytes as AlloyBytes, TxKind, U160, U256},
+    primitives::{aliases::U48, Address, U160, U256},
+    signers::{local::PrivateKeySigner, SignerSync},
+};
+#[cfg(feature = "rpc")]
+use alloy::{
+    primitives::{Bytes as AlloyBytes, TxKind},
     providers::{Provider, RootProvider},
     rpc::types::{TransactionInput, TransactionRequest},
-    signers::{local::PrivateKeySigner, SignerSync},
     transports::BoxTransport,
 };
-use alloy_primitives::{PrimitiveSignature as Signature, B256};
-use alloy_sol_types::{eip712_domain, sol, SolStruct, SolValue};
+use alloy_primitives::PrimitiveSignature as Signature;
+#[cfg(feature = "rpc")]
+use alloy_primitives::B256;
+#[cfg(feature = "rpc")]
+use alloy_sol_types::SolValue;
+use alloy_sol_types::{eip712_domain, sol, SolStruct};
 use chrono::Utc;
 use num_bigint::BigUint;
+#[cfg(feature = "rpc")]
 use tokio::{
     runtime::{Handle, Runtime},
     task::block_in_place,
 };
 use tycho_common::Bytes;
 
+#[cfg(feature = "rpc")]
+use crate::encoding::evm::utils::{encode_input, get_client, get_runtime};
 use crate::encoding::{
     errors::EncodingError,
-    evm::utils::{biguint_to_u256, bytes_to_address, encode_input, get_client, get_runtime},
+    evm::utils::{biguint_to_u256, bytes_to_address},
     models::Chain,
 };
 
 /// Struct for managing Permit2 operations, including encoding approvals and fetching allowance
 /// data.
+///
+/// Fetching the allowance nonces needs an RPC connection, so Permit2 approvals are only available
+/// with the `rpc` feature.
 #[derive(Clone)]
 pub struct Permit2 {
     address: Address,
+    #[cfg(feature = "rpc")]
     client: Arc<RootProvider<BoxTransport>>,
     signer: PrivateKeySigner,
     chain_id: u64,
+    #[cfg(feature = "rpc")]
     runtime_handle: Handle,
     // Store the runtime to prevent it from being dropped before use.
     // This is required since tycho-execution does not have a pre-existing runtime.
     // However, if the library is used in a context where a runtime already exists, it is not
     // necessary to store it.
+    #[cfg(feature = "rpc")]
     #[allow(dead_code)]
     runtime: Option<Arc<Runtime>>,
 }
@@ -56,6 +74,13 @@ sol! {
         uint256 sigDeadline;
     }
 
+    #[derive(Debug)]
+    struct PermitBatch {
+        PermitDetails[] details;
+        address spender;
+        uint256 sigDeadline;
+    }
+
     #[derive(Debug)]
     struct PermitDetails {
         address token;
@@ -66,6 +91,7 @@ sol! {
 }
 
 impl Permit2 {
+    #[cfg(feature = "rpc")]
     pub fn new(swapper_pk: String, chain: Chain) -> Result<Self, EncodingError> {
         let (handle, runtime) = get_runtime()?;
         let client = block_in_place(|| handle.block_on(get_client()))?;
@@ -86,7 +112,15 @@ impl Permit2 {
         })
     }
 
+    #[cfg(not(feature = "rpc"))]
+    pub fn new(_swapper_pk: String, _chain: Chain) -> Result<Self, EncodingError> {
+        Err(EncodingError::FatalError(
+            "Permit2 approvals need the `rpc` feature to fetch the allowance nonces".to_string(),
+        ))
+    }
+
     /// Fetches allowance data for a specific owner, spender, and token.
+    #[cfg(feature = "rpc")]
     fn get_existing_allowance(
         &self,
         owner: &Bytes,
@@ -121,6 +155,19 @@ impl Permit2 {
             ))),
         }
     }
+
+    #[cfg(not(feature = "rpc"))]
+    fn get_existing_allowance(
+        &self,
+        _owner: &Bytes,
+        _spender: &Bytes,
+        _token: &Bytes,
+    ) -> Result<Allowance, EncodingError> {
+        Err(EncodingError::FatalError(
+            "Fetching Permit2 allowances needs the `rpc` feature".to_string(),
+        ))
+    }
+
     /// Creates permit single and signature
     pub fn get_permit(
         &self,
@@ -164,6 +211,53 @@ impl Permit2 {
             })?;
         Ok((permit_single, signature))
     }
+
+    /// Creates permit batch and signature, approving several tokens with a single signature.
+    pub fn get_permit_batch(
+        &self,
+        spender: &Bytes,
+        owner: &Bytes,
+        tokens: &[(Bytes, BigUint)],
+    ) -> Result<(PermitBatch, Signature), EncodingError> {
+        let current_time = Utc::now()
+            .naive_utc()
+            .and_utc()
+            .timestamp() as u64;
+
+        let expiration = U48::from(current_time + PERMIT_EXPIRATION);
+        let sig_deadline = U256::from(current_time + PERMIT_SIG_EXPIRATION);
+
+        let mut details = Vec::with_capacity(tokens.len());
+        for (token, amount) in tokens {
+            let (_, _, nonce) = self.get_existing_allowance(owner, spender, token)?;
+            details.push(PermitDetails {
+                token: bytes_to_address(token)?,
+                amount: U160::from(biguint_to_u256(amount)),
+                expiration,
+                nonce,
+            });
+        }
+
+        let permit_batch =
+            PermitBatch { details, spender: bytes_to_address(spender)?, sigDeadline: sig_deadline };
+
+        let domain = eip712_domain! {
+            name: "Permit2",
+            chain_id: self.chain_id,
+            verifying_contract: self.address,
+        };
+        let hash = permit_batch.eip712_signing_hash(&domain);
+        let signature = self
+            .signer
+            .sign_hash_sync(&hash)
+            .map_err(|e| {
+                EncodingError::FatalError(format!(
+                    "Failed to sign permit2 batch approval with error: {}",
+                    e
+                ))
+            })?;
+        Ok((permit_batch, signature))
+    }
 }
 
 #[cfg(test)]
@@ -207,6 +301,12 @@ mod tests {
         }
     }
 
+    impl PartialEq for PermitBatch {
+        fn eq(&self, other: &Self) -> bool {
+            self.details == other.details && self.spender == other.spender
+        }
+    }
+
     fn eth_chain() -> Chain {
         TychoCommonChain::Ethereum.into()
     }
@@ -264,6 +364,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_permit_batch() {
+        // Set up a mock private key for signing
+        let private_key =
+            "4c0883a69102937d6231471b5dbb6204fe512961708279feb1be6ae5538da033".to_string();
+        let permit2 = Permit2::new(private_key, eth_chain()).expect("Failed to create Permit2");
+
+        let owner = Bytes::from_str("0x2c6a3cd97c6283b95ac8c5a4459ebb0d5fd404f4").unwrap();
+        let spender = Bytes::from_str("0xba12222222228d8ba445958a75a0704d566bf2c8").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let tokens =
+            vec![(usdc.clone(), BigUint::from(1000u64)), (dai.clone(), BigUint::from(2000u64))];
+
+        let (permit, _) = permit2
+            .get_permit_batch(&spender, &owner, &tokens)
+            .unwrap();
+
+        let expiration = U48::from(Utc::now().timestamp() as u64 + PERMIT_EXPIRATION);
+        let expected_permit_batch = PermitBatch {
+            details: vec![
+                PermitDetails {
+                    token: bytes_to_address(&usdc).unwrap(),
+                    amount: U160::from(1000u64),
+                    expiration,
+                    nonce: U48::from(0),
+                },
+                PermitDetails {
+                    token: bytes_to_address(&dai).unwrap(),
+                    amount: U160::from(2000u64),
+                    expiration,
+                    nonce: U48::from(0),
+                },
+            ],
+            spender: Address::from_str("0xba12222222228d8ba445958a75a0704d566bf2c8").unwrap(),
+            sigDeadline: U256::from(Utc::now().timestamp() as u64 + PERMIT_SIG_EXPIRATION),
+        };
+
+        assert_eq!(
+            permit, expected_permit_batch,
+            "Decoded PermitBatch does not match expected values"
+        );
+    }
+
     /// This test actually calls the permit method on the Permit2 contract to verify the encoded
     /// data works. It requires an Anvil fork, so please run with the following command: anvil
     /// --fork-url <RPC-URL> And set up the following env var as RPC_URL=127.0.0.1:8545
@@ -0,0 +1,964 @@
+use std::{collections::HashMap, str::FromStr};
+
+use alloy_primitives::{hex, Address, U256};
+use alloy_sol_types::{sol, SolCall, SolInterface};
+use serde_json::{json, Value};
+
+use crate::encoding::{
+    errors::EncodingError,
+    evm::{
+        constants::NATIVE_WRAPPER_PROTOCOL, strategy_encoder::actions::decode_actions,
+        swap_encoder::swap_encoder_registry::get_executor_addresses, utils::ple_decode,
+    },
+    models::{Chain, TransferType},
+};
+
+sol! {
+    /// Entry points of the router, as called by the calldata of the `TychoRouterEncoder`. Mirrors
+    /// the `TychoRouter` contract.
+    interface ITychoRouter {
+        struct OutputSettlement {
+            uint16 feeBps;
+            address feeRecipient;
+            uint256 expectedAmountOut;
+            address surplusRecipient;
+            uint16 surplusShareBps;
+        }
+
+        struct SwapOutput {
+            address token;
+            uint256 minAmountOut;
+            address receiver;
+        }
+
+        struct SwapInput {
+            address token;
+            uint256 amount;
+        }
+
+        struct PermitDetails {
+            address token;
+            uint160 amount;
+            uint48 expiration;
+            uint48 nonce;
+        }
+
+        struct PermitSingle {
+            PermitDetails details;
+            address spender;
+            uint256 sigDeadline;
+        }
+
+        struct PermitBatch {
+            PermitDetails[] details;
+            address spender;
+            uint256 sigDeadline;
+        }
+
+        function singleSwap(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, address receiver, bytes swapData);
+        function singleSwapPermit2(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, address receiver, PermitSingle permitSingle, bytes signature, bytes swapData);
+        function singleSwapWithSettlement(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, address receiver, OutputSettlement settlement, bytes swapData);
+        function singleSwapWithSettlementPermit2(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, address receiver, OutputSettlement settlement, PermitSingle permitSingle, bytes signature, bytes swapData);
+        function sequentialSwap(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, address receiver, bytes swaps);
+        function sequentialSwapPermit2(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, address receiver, PermitSingle permitSingle, bytes signature, bytes swaps);
+        function sequentialSwapWithSettlement(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, address receiver, OutputSettlement settlement, bytes swaps);
+        function sequentialSwapWithSettlementPermit2(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, address receiver, OutputSettlement settlement, PermitSingle permitSingle, bytes signature, bytes swaps);
+        function splitSwap(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, uint256 nTokens, address receiver, bytes swaps);
+        function splitSwapPermit2(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, uint256 nTokens, address receiver, PermitSingle permitSingle, bytes signature, bytes swaps);
+        function splitSwapWithAmounts(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, uint256 nTokens, address receiver, bytes swaps);
+        function splitSwapWithAmountsPermit2(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, uint256 nTokens, address receiver, PermitSingle permitSingle, bytes signature, bytes swaps);
+        function splitSwapWithSettlement(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, uint256 nTokens, address receiver, OutputSettlement settlement, bytes swaps);
+        function splitSwapWithSettlementPermit2(uint256 amountIn, address tokenIn, address tokenOut, uint256 minAmountOut, bool wrapEth, bool unwrapEth, uint256 nTokens, address receiver, OutputSettlement settlement, PermitSingle permitSingle, bytes signature, bytes swaps);
+        function splitSwapMultiOutput(uint256 amountIn, address tokenIn, bool wrapEth, uint256 nTokens, SwapOutput[] outputs, bytes swaps);
+        function splitSwapMultiOutputPermit2(uint256 amountIn, address tokenIn, bool wrapEth, uint256 nTokens, SwapOutput[] outputs, PermitSingle permitSingle, bytes signature, bytes swaps);
+        function splitSwapMultiInput(SwapInput[] inputs, address tokenOut, uint256 minAmountOut, uint256 nTokens, address receiver, bytes swaps);
+        function splitSwapMultiInputPermit2(SwapInput[] inputs, address tokenOut, uint256 minAmountOut, uint256 nTokens, address receiver, PermitBatch permitBatch, bytes signature, bytes swaps);
+        function executeWithDeadline(uint256 deadline, bytes data);
+        function swapWithActions(uint256 amountIn, address tokenIn, address swapTokenOut, uint256 minAmountOut, address receiver, bytes preActions, bytes swapCall, bytes postActions);
+        function multicall(bytes[] data);
+    }
+
+    /// Mirrors the `IExecutor` interface.
+    interface IExecutor {
+        function swap(uint256 givenAmount, bytes data);
+    }
+}
+
+use ITychoRouter::ITychoRouterCalls as RouterCall;
+
+/// Decodes the calldata produced by the encoders into a JSON breakdown, e.g. to investigate a
+/// transaction.
+///
+/// Swaps are decoded with the layout of the protocol of their executor. The protocols are known
+/// from the executor addresses of the chain.
+///
+/// # Fields
+/// * `executors`: Protocols of each executor address. Protocols sharing an executor share its
+///   protocol data layout.
+pub struct CalldataDecoder {
+    executors: HashMap<Address, Vec<String>>,
+}
+
+impl CalldataDecoder {
+    /// Creates a decoder for the executors of the given chain, read from the file at the given
+    /// path or from the default executors file.
+    pub fn new(executors_file_path: Option<String>, chain: Chain) -> Result<Self, EncodingError> {
+        let mut executors: HashMap<Address, Vec<String>> = HashMap::new();
+        for (protocol, executor_address) in get_executor_addresses(executors_file_path, &chain)? {
+            let address = Address::from_str(&executor_address).map_err(|_| {
+                EncodingError::FatalError(format!("Invalid executor address: {executor_address}"))
+            })?;
+            executors
+                .entry(address)
+                .or_default()
+                .push(protocol);
+        }
+        for protocols in executors.values_mut() {
+            protocols.sort();
+        }
+        Ok(CalldataDecoder { executors })
+    }
+
+    /// Returns the protocols with an executor on the chain, sorted by name.
+    pub fn supported_protocols(&self) -> Vec<String> {
+        let mut protocols: Vec<String> = self
+            .executors
+            .values()
+            .flatten()
+            .cloned()
+            .collect();
+        protocols.sort();
+        protocols
+    }
+
+    /// Decodes a call to the router or a `swap(uint256,bytes)` call to an executor.
+    ///
+    /// The protocol data of a direct executor call can only be decoded if the executor is given.
+    pub fn decode(
+        &self,
+        calldata: &[u8],
+        executor: Option<Address>,
+    ) -> Result<Value, EncodingError> {
+        if calldata.starts_with(&IExecutor::swapCall::SELECTOR) {
+            let call = IExecutor::swapCall::abi_decode(calldata, true).map_err(decoding_error)?;
+            return Ok(json!({
+                "function": "swap",
+                "given_amount": call.givenAmount.to_string(),
+                "protocol_data": match executor {
+                    Some(executor) => self.decode_protocol_data(executor, &call.data)?,
+                    None => Value::String(hex_string(&call.data)),
+                },
+            }));
+        }
+        self.decode_router_call(calldata)
+    }
+
+    /// Decodes the protocol data of a swap executed by the given executor.
+    pub fn decode_protocol_data(
+        &self,
+        executor: Address,
+        protocol_data: &[u8],
+    ) -> Result<Value, EncodingError> {
+        let protocol = self
+            .executors
+            .get(&executor)
+            .and_then(|protocols| protocols.first())
+            .ok_or_else(|| EncodingError::InvalidInput(format!("Unknown executor: {executor}")))?;
+        decode_protocol_data(protocol, protocol_data)
+    }
+
+    fn decode_router_call(&self, calldata: &[u8]) -> Result<Value, EncodingError> {
+        let call = RouterCall::abi_decode(calldata, true).map_err(decoding_error)?;
+        let decoded = match call {
+            RouterCall::singleSwap(c) => {
+                let mut decoded = swap_args(
+                    "singleSwap",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["swaps"] = json!([self.decode_swap(&c.swapData)?]);
+                decoded
+            }
+            RouterCall::singleSwapPermit2(c) => {
+                let mut decoded = swap_args(
+                    "singleSwapPermit2",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["permit"] = permit_single(&c.permitSingle);
+                decoded["signature"] = Value::String(hex_string(&c.signature));
+                decoded["swaps"] = json!([self.decode_swap(&c.swapData)?]);
+                decoded
+            }
+            RouterCall::singleSwapWithSettlement(c) => {
+                let mut decoded = swap_args(
+                    "singleSwapWithSettlement",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["settlement"] = settlement(&c.settlement);
+                decoded["swaps"] = json!([self.decode_swap(&c.swapData)?]);
+                decoded
+            }
+            RouterCall::singleSwapWithSettlementPermit2(c) => {
+                let mut decoded = swap_args(
+                    "singleSwapWithSettlementPermit2",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["settlement"] = settlement(&c.settlement);
+                decoded["permit"] = permit_single(&c.permitSingle);
+                decoded["signature"] = Value::String(hex_string(&c.signature));
+                decoded["swaps"] = json!([self.decode_swap(&c.swapData)?]);
+                decoded
+            }
+            RouterCall::sequentialSwap(c) => {
+                let mut decoded = swap_args(
+                    "sequentialSwap",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["swaps"] = self.decode_sequential_swaps(&c.swaps)?;
+                decoded
+            }
+            RouterCall::sequentialSwapPermit2(c) => {
+                let mut decoded = swap_args(
+                    "sequentialSwapPermit2",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["permit"] = permit_single(&c.permitSingle);
+                decoded["signature"] = Value::String(hex_string(&c.signature));
+                decoded["swaps"] = self.decode_sequential_swaps(&c.swaps)?;
+                decoded
+            }
+            RouterCall::sequentialSwapWithSettlement(c) => {
+                let mut decoded = swap_args(
+                    "sequentialSwapWithSettlement",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["settlement"] = settlement(&c.settlement);
+                decoded["swaps"] = self.decode_sequential_swaps(&c.swaps)?;
+                decoded
+            }
+            RouterCall::sequentialSwapWithSettlementPermit2(c) => {
+                let mut decoded = swap_args(
+                    "sequentialSwapWithSettlementPermit2",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["settlement"] = settlement(&c.settlement);
+                decoded["permit"] = permit_single(&c.permitSingle);
+                decoded["signature"] = Value::String(hex_string(&c.signature));
+                decoded["swaps"] = self.decode_sequential_swaps(&c.swaps)?;
+                decoded
+            }
+            RouterCall::splitSwap(c) => {
+                let mut decoded = swap_args(
+                    "splitSwap",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["n_tokens"] = Value::String(c.nTokens.to_string());
+                decoded["swaps"] = self.decode_split_swaps(&c.swaps, false)?;
+                decoded
+            }
+            RouterCall::splitSwapPermit2(c) => {
+                let mut decoded = swap_args(
+                    "splitSwapPermit2",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["n_tokens"] = Value::String(c.nTokens.to_string());
+                decoded["permit"] = permit_single(&c.permitSingle);
+                decoded["signature"] = Value::String(hex_string(&c.signature));
+                decoded["swaps"] = self.decode_split_swaps(&c.swaps, false)?;
+                decoded
+            }
+            RouterCall::splitSwapWithAmounts(c) => {
+                let mut decoded = swap_args(
+                    "splitSwapWithAmounts",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["n_tokens"] = Value::String(c.nTokens.to_string());
+                decoded["swaps"] = self.decode_split_swaps(&c.swaps, true)?;
+                decoded
+            }
+            RouterCall::splitSwapWithAmountsPermit2(c) => {
+                let mut decoded = swap_args(
+                    "splitSwapWithAmountsPermit2",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["n_tokens"] = Value::String(c.nTokens.to_string());
+                decoded["permit"] = permit_single(&c.permitSingle);
+                decoded["signature"] = Value::String(hex_string(&c.signature));
+                decoded["swaps"] = self.decode_split_swaps(&c.swaps, true)?;
+                decoded
+            }
+            RouterCall::splitSwapWithSettlement(c) => {
+                let mut decoded = swap_args(
+                    "splitSwapWithSettlement",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["n_tokens"] = Value::String(c.nTokens.to_string());
+                decoded["settlement"] = settlement(&c.settlement);
+                decoded["swaps"] = self.decode_split_swaps(&c.swaps, true)?;
+                decoded
+            }
+            RouterCall::splitSwapWithSettlementPermit2(c) => {
+                let mut decoded = swap_args(
+                    "splitSwapWithSettlementPermit2",
+                    &c.amountIn,
+                    c.tokenIn,
+                    c.tokenOut,
+                    &c.minAmountOut,
+                    c.wrapEth,
+                    c.unwrapEth,
+                    c.receiver,
+                );
+                decoded["n_tokens"] = Value::String(c.nTokens.to_string());
+                decoded["settlement"] = settlement(&c.settlement);
+                decoded["permit"] = permit_single(&c.permitSingle);
+                decoded["signature"] = Value::String(hex_string(&c.signature));
+                decoded["swaps"] = self.decode_split_swaps(&c.swaps, true)?;
+                decoded
+            }
+            RouterCall::splitSwapMultiOutput(c) => json!({
+                "function": "splitSwapMultiOutput",
+                "amount_in": c.amountIn.to_string(),
+                "token_in": c.tokenIn.to_string(),
+                "wrap": c.wrapEth,
+                "n_tokens": c.nTokens.to_string(),
+                "outputs": swap_outputs(&c.outputs),
+                "swaps": self.decode_split_swaps(&c.swaps, true)?,
+            }),
+            RouterCall::splitSwapMultiOutputPermit2(c) => json!({
+                "function": "splitSwapMultiOutputPermit2",
+                "amount_in": c.amountIn.to_string(),
+                "token_in": c.tokenIn.to_string(),
+                "wrap": c.wrapEth,
+                "n_tokens": c.nTokens.to_string(),
+                "outputs": swap_outputs(&c.outputs),
+                "permit": permit_single(&c.permitSingle),
+                "signature": hex_string(&c.signature),
+                "swaps": self.decode_split_swaps(&c.swaps, true)?,
+            }),
+            RouterCall::splitSwapMultiInput(c) => json!({
+                "function": "splitSwapMultiInput",
+                "inputs": swap_inputs(&c.inputs),
+                "token_out": c.tokenOut.to_string(),
+                "min_amount_out": c.minAmountOut.to_string(),
+                "n_tokens": c.nTokens.to_string(),
+                "receiver": c.receiver.to_string(),
+                "swaps": self.decode_split_swaps(&c.swaps, true)?,
+            }),
+            RouterCall::splitSwapMultiInputPermit2(c) => json!({
+                "function": "splitSwapMultiInputPermit2",
+                "inputs": swap_inputs(&c.inputs),
+                "token_out": c.tokenOut.to_string(),
+                "min_amount_out": c.minAmountOut.to_string(),
+                "n_tokens": c.nTokens.to_string(),
+                "receiver": c.receiver.to_string(),
+                "permit": permit_batch(&c.permitBatch),
+                "signature": hex_string(&c.signature),
+                "swaps": self.decode_split_swaps(&c.swaps, true)?,
+            }),
+            RouterCall::executeWithDeadline(c) => json!({
+                "function": "executeWithDeadline",
+                "deadline": c.deadline.to_string(),
+                "call": self.decode_router_call(&c.data)?,
+            }),
+            RouterCall::swapWithActions(c) => json!({
+                "function": "swapWithActions",
+                "amount_in": c.amountIn.to_string(),
+                "token_in": c.tokenIn.to_string(),
+                "swap_token_out": c.swapTokenOut.to_string(),
+                "min_amount_out": c.minAmountOut.to_string(),
+                "receiver": c.receiver.to_string(),
+                "pre_swap_actions": serde_json::to_value(decode_actions(&c.preActions)?)?,
+                "swap_call": self.decode_router_call(&c.swapCall)?,
+                "post_swap_actions": serde_json::to_value(decode_actions(&c.postActions)?)?,
+            }),
+            RouterCall::multicall(c) => json!({
+                "function": "multicall",
+                "calls": c
+                    .data
+                    .iter()
+                    .map(|call| self.decode_router_call(call))
+                    .collect::<Result<Vec<_>, _>>()?,
+            }),
+        };
+        Ok(decoded)
+    }
+
+    /// Decodes a swap executed by the router: the executor address followed by the protocol data.
+    fn decode_swap(&self, swap_data: &[u8]) -> Result<Value, EncodingError> {
+        if swap_data.len() < 20 {
+            return Err(EncodingError::InvalidInput(
+                "Swap data shorter than an executor address".to_string(),
+            ));
+        }
+        let executor = Address::from_slice(&swap_data[..20]);
+        let protocol_data = &swap_data[20..];
+        Ok(match self.executors.get(&executor) {
+            Some(protocols) => json!({
+                "executor": executor.to_string(),
+                "protocols": protocols,
+                "protocol_data": decode_protocol_data(&protocols[0], protocol_data)?,
+            }),
+            None => json!({
+                "executor": executor.to_string(),
+                "protocols": [],
+                "protocol_data": hex_string(protocol_data),
+            }),
+        })
+    }
+
+    fn decode_sequential_swaps(&self, swaps: &[u8]) -> Result<Value, EncodingError> {
+        Ok(Value::Array(
+            ple_decode(swaps)?
+                .into_iter()
+                .map(|swap| self.decode_swap(swap))
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    }
+
+    /// Decodes the swaps of a split swap: each one starts with the indexes of its tokens, its
+    /// split and, if the router expects them, its absolute input amount.
+    fn decode_split_swaps(&self, swaps: &[u8], with_amounts: bool) -> Result<Value, EncodingError> {
+        let mut decoded_swaps = vec![];
+        for swap in ple_decode(swaps)? {
+            let mut reader = PackedReader::new(swap);
+            let token_in_index = reader.uint(1)?;
+            let token_out_index = reader.uint(1)?;
+            let split = reader.uint(3)?;
+            let amount_in = if with_amounts { Some(reader.uint(32)?.to_string()) } else { None };
+            let mut decoded = self.decode_swap(reader.remaining())?;
+            decoded["token_in_index"] = json!(token_in_index.to::<u64>());
+            decoded["token_out_index"] = json!(token_out_index.to::<u64>());
+            decoded["split"] = json!(split.to::<u64>() as f64 / 16_777_215f64);
+            if let Some(amount_in) = amount_in {
+                decoded["amount_in"] = Value::String(amount_in);
+            }
+            decoded_swaps.push(decoded);
+        }
+        Ok(Value::Array(decoded_swaps))
+    }
+}
+
+/// Decodes the protocol data of a swap with the layout of the given protocol's swap encoder.
+fn decode_protocol_data(protocol: &str, protocol_data: &[u8]) -> Result<Value, EncodingError> {
+    let mut reader = PackedReader::new(protocol_data);
+    let decoded = match protocol {
+        "uniswap_v2" | "sushiswap_v2" | "pancakeswap_v2" => json!({
+            "token_in": reader.address()?,
+            "pool": reader.address()?,
+            "receiver": reader.address()?,
+            "zero_for_one": reader.bool()?,
+            "transfer_type": reader.transfer_type()?,
+        }),
+        "uniswap_v3" | "pancakeswap_v3" => json!({
+            "token_in": reader.address()?,
+            "token_out": reader.address()?,
+            "fee": reader.uint(3)?.to::<u64>(),
+            "receiver": reader.address()?,
+            "pool": reader.address()?,
+            "zero_for_one": reader.bool()?,
+            "transfer_type": reader.transfer_type()?,
+        }),
+        "uniswap_v4" => {
+            let mut decoded = json!({
+                "token_in": reader.address()?,
+                "token_out": reader.address()?,
+                "zero_for_one": reader.bool()?,
+                "transfer_type": reader.transfer_type()?,
+                "receiver": reader.address()?,
+            });
+            let mut pools = vec![];
+            while !reader.remaining().is_empty() {
+                pools.push(json!({
+                    "token_out": reader.address()?,
+                    "fee": reader.uint(3)?.to::<u64>(),
+                    "tick_spacing": reader.uint(3)?.to::<u64>(),
+                }));
+            }
+            decoded["pools"] = Value::Array(pools);
+            decoded
+        }
+        "vm:balancer_v2" => json!({
+            "token_in": reader.address()?,
+            "token_out": reader.address()?,
+            "pool_id": hex_string(reader.take(32)?),
+            "receiver": reader.address()?,
+            "approval_needed": reader.bool()?,
+            "transfer_type": reader.transfer_type()?,
+        }),
+        "ekubo_v2" => {
+            let mut decoded = json!({
+                "transfer_type": reader.transfer_type()?,
+                "receiver": reader.address()?,
+                "token_in": reader.address()?,
+            });
+            let mut pools = vec![];
+            while !reader.remaining().is_empty() {
+                pools.push(json!({
+                    "token_out": reader.address()?,
+                    "extension": reader.address()?,
+                    "fee": reader.uint(8)?.to::<u64>(),
+                    "tick_spacing": reader.uint(4)?.to::<u64>(),
+                }));
+            }
+            decoded["pools"] = Value::Array(pools);
+            decoded
+        }
+        "vm:curve" => json!({
+            "token_in": reader.address()?,
+            "token_out": reader.address()?,
+            "pool": reader.address()?,
+            "pool_type": reader.uint(1)?.to::<u64>(),
+            "i": reader.uint(1)?.to::<u64>(),
+            "j": reader.uint(1)?.to::<u64>(),
+            "approval_needed": reader.bool()?,
+            "transfer_type": reader.transfer_type()?,
+            "receiver": reader.address()?,
+        }),
+        NATIVE_WRAPPER_PROTOCOL => json!({
+            "token_in": reader.address()?,
+            "receiver": reader.address()?,
+            "transfer_type": reader.transfer_type()?,
+        }),
+        _ => return Ok(Value::String(hex_string(protocol_data))),
+    };
+    if !reader.remaining().is_empty() {
+        return Err(EncodingError::InvalidInput(format!(
+            "Unexpected trailing bytes in {protocol} protocol data"
+        )));
+    }
+    Ok(decoded)
+}
+
+/// Reads the values of data encoded with `abi_encode_packed`, in order.
+struct PackedReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> PackedReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        PackedReader { data }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], EncodingError> {
+        if self.data.len() < length {
+            return Err(EncodingError::InvalidInput("Unexpected end of packed data".to_string()));
+        }
+        let (value, remaining) = self.data.split_at(length);
+        self.data = remaining;
+        Ok(value)
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn address(&mut self) -> Result<String, EncodingError> {
+        Ok(Address::from_slice(self.take(20)?).to_string())
+    }
+
+    fn uint(&mut self, length: usize) -> Result<U256, EncodingError> {
+        Ok(U256::from_be_slice(self.take(length)?))
+    }
+
+    fn bool(&mut self) -> Result<bool, EncodingError> {
+        Ok(self.take(1)?[0] != 0)
+    }
+
+    fn transfer_type(&mut self) -> Result<String, EncodingError> {
+        let value = self.take(1)?[0];
+        [
+            TransferType::TransferToProtocol,
+            TransferType::TransferFromToProtocol,
+            TransferType::TransferPermit2ToProtocol,
+            TransferType::TransferFromToRouter,
+            TransferType::TransferPermit2ToRouter,
+            TransferType::None,
+        ]
+        .into_iter()
+        .find(|transfer_type| transfer_type.clone() as u8 == value)
+        .map(|transfer_type| format!("{transfer_type:?}"))
+        .ok_or_else(|| EncodingError::InvalidInput(format!("Invalid transfer type: {value}")))
+    }
+}
+
+/// Returns the arguments shared by the single, sequential and split swap entry points.
+#[allow(clippy::too_many_arguments)]
+fn swap_args(
+    function: &str,
+    amount_in: &U256,
+    token_in: Address,
+    token_out: Address,
+    min_amount_out: &U256,
+    wrap: bool,
+    unwrap: bool,
+    receiver: Address,
+) -> Value {
+    json!({
+        "function": function,
+        "amount_in": amount_in.to_string(),
+        "token_in": token_in.to_string(),
+        "token_out": token_out.to_string(),
+        "min_amount_out": min_amount_out.to_string(),
+        "wrap": wrap,
+        "unwrap": unwrap,
+        "receiver": receiver.to_string(),
+    })
+}
+
+fn settlement(settlement: &ITychoRouter::OutputSettlement) -> Value {
+    json!({
+        "fee_bps": settlement.feeBps,
+        "fee_recipient": settlement.feeRecipient.to_string(),
+        "expected_amount_out": settlement.expectedAmountOut.to_string(),
+        "surplus_recipient": settlement.surplusRecipient.to_string(),
+        "surplus_share_bps": settlement.surplusShareBps,
+    })
+}
+
+fn permit_details(details: &ITychoRouter::PermitDetails) -> Value {
+    json!({
+        "token": details.token.to_string(),
+        "amount": details.amount.to_string(),
+        "expiration": details.expiration.to_string(),
+        "nonce": details.nonce.to_string(),
+    })
+}
+
+fn permit_single(permit: &ITychoRouter::PermitSingle) -> Value {
+    json!({
+        "details": permit_details(&permit.details),
+        "spender": permit.spender.to_string(),
+        "sig_deadline": permit.sigDeadline.to_string(),
+    })
+}
+
+fn permit_batch(permit: &ITychoRouter::PermitBatch) -> Value {
+    json!({
+        "details": permit.details.iter().map(permit_details).collect::<Vec<_>>(),
+        "spender": permit.spender.to_string(),
+        "sig_deadline": permit.sigDeadline.to_string(),
+    })
+}
+
+fn swap_outputs(outputs: &[ITychoRouter::SwapOutput]) -> Value {
+    outputs
+        .iter()
+        .map(|output| {
+            json!({
+                "token": output.token.to_string(),
+                "min_amount_out": output.minAmountOut.to_string(),
+                "receiver": output.receiver.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn swap_inputs(inputs: &[ITychoRouter::SwapInput]) -> Value {
+    inputs
+        .iter()
+        .map(|input| json!({"token": input.token.to_string(), "amount": input.amount.to_string()}))
+        .collect()
+}
+
+fn hex_string(data: &[u8]) -> String {
+    format!("0x{}", hex::encode(data))
+}
+
+fn decoding_error(error: alloy_sol_types::Error) -> EncodingError {
+    EncodingError::InvalidInput(format!("Failed to decode calldata: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_sol_types::SolValue;
+    use num_bigint::BigUint;
+    use tycho_common::models::Chain as TychoCommonChain;
+
+    use super::*;
+    use crate::encoding::evm::utils::{encode_executor_swap, encode_input, ple_encode};
+
+    fn get_decoder() -> CalldataDecoder {
+        CalldataDecoder::new(
+            Some("config/test_executor_addresses.json".to_string()),
+            TychoCommonChain::Ethereum.into(),
+        )
+        .unwrap()
+    }
+
+    fn usv2_executor() -> Address {
+        Address::from_str("0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f").unwrap()
+    }
+
+    fn weth() -> Address {
+        Address::from_str("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2").unwrap()
+    }
+
+    fn dai() -> Address {
+        Address::from_str("0x6B175474E89094C44Da98b954EedeAC495271d0F").unwrap()
+    }
+
+    fn receiver() -> Address {
+        Address::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap()
+    }
+
+    fn usv2_pool() -> Address {
+        Address::from_str("0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11").unwrap()
+    }
+
+    fn usv2_protocol_data(receiver: Address, transfer_type: TransferType) -> Vec<u8> {
+        (weth(), usv2_pool(), receiver, false, (transfer_type as u8).to_be_bytes())
+            .abi_encode_packed()
+    }
+
+    fn expected_usv2_swap(receiver: Address, transfer_type: &str) -> Value {
+        json!({
+            "executor": usv2_executor().to_string(),
+            "protocols": ["pancakeswap_v2", "sushiswap_v2", "uniswap_v2"],
+            "protocol_data": {
+                "token_in": weth().to_string(),
+                "pool": usv2_pool().to_string(),
+                "receiver": receiver.to_string(),
+                "zero_for_one": false,
+                "transfer_type": transfer_type,
+            },
+        })
+    }
+
+    #[test]
+    fn test_decode_single_swap() {
+        let mut swap_data = usv2_executor().to_vec();
+        swap_data.extend(usv2_protocol_data(receiver(), TransferType::TransferFromToProtocol));
+        let calldata = encode_input(
+            "singleSwap(uint256,address,address,uint256,bool,bool,address,bytes)",
+            (U256::from(1000), weth(), dai(), U256::from(990), false, false, receiver(), swap_data)
+                .abi_encode(),
+        );
+
+        let decoded = get_decoder()
+            .decode(&calldata, None)
+            .unwrap();
+
+        assert_eq!(
+            decoded,
+            json!({
+                "function": "singleSwap",
+                "amount_in": "1000",
+                "token_in": weth().to_string(),
+                "token_out": dai().to_string(),
+                "min_amount_out": "990",
+                "wrap": false,
+                "unwrap": false,
+                "receiver": receiver().to_string(),
+                "swaps": [expected_usv2_swap(receiver(), "TransferFromToProtocol")],
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_split_swap_in_deadline() {
+        let mut swap_data = vec![0u8, 1u8, 0u8, 0u8, 0u8];
+        swap_data.extend(usv2_executor().to_vec());
+        swap_data.extend(usv2_protocol_data(receiver(), TransferType::TransferFromToProtocol));
+        let split_swap = encode_input(
+            "splitSwap(uint256,address,address,uint256,bool,bool,uint256,address,bytes)",
+            (
+                U256::from(1000),
+                weth(),
+                dai(),
+                U256::from(990),
+                false,
+                false,
+                U256::from(2),
+                receiver(),
+                ple_encode(vec![swap_data]),
+            )
+                .abi_encode(),
+        );
+        let calldata = encode_input(
+            "executeWithDeadline(uint256,bytes)",
+            (U256::from(1700000000u64), split_swap).abi_encode(),
+        );
+
+        let decoded = get_decoder()
+            .decode(&calldata, None)
+            .unwrap();
+
+        let mut expected_swap = expected_usv2_swap(receiver(), "TransferFromToProtocol");
+        expected_swap["token_in_index"] = json!(0);
+        expected_swap["token_out_index"] = json!(1);
+        expected_swap["split"] = json!(0.0);
+        assert_eq!(decoded["function"], "executeWithDeadline");
+        assert_eq!(decoded["deadline"], "1700000000");
+        assert_eq!(decoded["call"]["function"], "splitSwap");
+        assert_eq!(decoded["call"]["n_tokens"], "2");
+        assert_eq!(decoded["call"]["swaps"], json!([expected_swap]));
+    }
+
+    #[test]
+    fn test_decode_executor_swap() {
+        let calldata = encode_executor_swap(
+            &BigUint::from(1000u32),
+            usv2_protocol_data(receiver(), TransferType::TransferToProtocol),
+        );
+
+        let decoded = get_decoder()
+            .decode(&calldata, Some(usv2_executor()))
+            .unwrap();
+
+        assert_eq!(
+            decoded,
+            json!({
+                "function": "swap",
+                "given_amount": "1000",
+                "protocol_data": expected_usv2_swap(receiver(), "TransferToProtocol")["protocol_data"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_unknown_executor() {
+        let mut swap_data = receiver().to_vec();
+        swap_data.extend([0xab, 0xcd]);
+
+        let decoded = get_decoder()
+            .decode_swap(&swap_data)
+            .unwrap();
+
+        assert_eq!(
+            decoded,
+            json!({
+                "executor": receiver().to_string(),
+                "protocols": [],
+                "protocol_data": "0xabcd",
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_uniswap_v4_grouped_swaps() {
+        let usdc = Address::from_str("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48").unwrap();
+        let mut protocol_data = (
+            weth(),
+            dai(),
+            true,
+            (TransferType::TransferToProtocol as u8).to_be_bytes(),
+            receiver(),
+        )
+            .abi_encode_packed();
+        protocol_data.extend((usdc, [0u8, 0x0b, 0xb8], [0u8, 0, 60]).abi_encode_packed());
+        protocol_data.extend((dai(), [0u8, 0, 100], [0u8, 0, 1]).abi_encode_packed());
+
+        let decoded = decode_protocol_data("uniswap_v4", &protocol_data).unwrap();
+
+        assert_eq!(
+            decoded["pools"],
+            json!([
+                {"token_out": usdc.to_string(), "fee": 3000, "tick_spacing": 60},
+                {"token_out": dai().to_string(), "fee": 100, "tick_spacing": 1},
+            ])
+        );
+        assert_eq!(decoded["transfer_type"], "TransferToProtocol");
+    }
+
+    #[test]
+    fn test_decode_truncated_protocol_data() {
+        let protocol_data = usv2_protocol_data(receiver(), TransferType::TransferToProtocol);
+
+        let result = decode_protocol_data("uniswap_v2", &protocol_data[..40]);
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_decode_unknown_selector() {
+        let result = get_decoder().decode(&[0xde, 0xad, 0xbe, 0xef], None);
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_supported_protocols() {
+        let protocols = get_decoder().supported_protocols();
+
+        assert_eq!(protocols.len(), 10);
+        assert_eq!(protocols[0], "ekubo_v2");
+        assert!(protocols.contains(&"sushiswap_v2".to_string()));
+    }
+}
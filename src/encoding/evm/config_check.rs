@@ -0,0 +1,153 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::encoding::{
+    errors::EncodingError,
+    evm::{
+        constants::DEFAULT_ROUTERS_JSON,
+        swap_encoder::{builder::PROTOCOL_SYSTEMS, swap_encoder_registry::read_executors_file},
+        utils::parse_configured_address,
+    },
+};
+
+/// Report of the executors and router configuration of a chain.
+///
+/// # Fields
+/// * `chain`: Name of the chain
+/// * `router_address`: Default router address of the chain, if any
+/// * `supported_protocols`: Protocols with both an executor and a swap encoder
+/// * `missing_executors`: Protocols with a swap encoder but no executor on the chain, which can't
+///   be encoded on it
+/// * `missing_encoders`: Protocols with an executor but no swap encoder, which make building the
+///   encoders of the chain fail
+/// * `errors`: Malformed or zero addresses, which make building the encoders of the chain fail
+///   unless the addresses are explicitly allowed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainConfigReport {
+    pub chain: String,
+    pub router_address: Option<String>,
+    pub supported_protocols: Vec<String>,
+    pub missing_executors: Vec<String>,
+    pub missing_encoders: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl ChainConfigReport {
+    /// Whether the encoders of the chain can be built from its configuration.
+    pub fn is_valid(&self) -> bool {
+        self.missing_encoders.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// Checks the configuration of every chain of the executors file at the given path, or of the
+/// default executors file, and of the default router addresses. The reports are sorted by chain
+/// name.
+pub fn check_config(
+    executors_file_path: Option<String>,
+) -> Result<Vec<ChainConfigReport>, EncodingError> {
+    let executors = read_executors_file(executors_file_path)?;
+    let routers: HashMap<String, String> = serde_json::from_str(DEFAULT_ROUTERS_JSON)?;
+    let chains: BTreeSet<&String> = executors
+        .keys()
+        .chain(routers.keys())
+        .collect();
+
+    let mut reports = Vec::new();
+    for chain in chains {
+        let chain_executors = executors
+            .get(chain)
+            .cloned()
+            .unwrap_or_default();
+        let router_address = routers.get(chain).cloned();
+        let mut errors = Vec::new();
+        if let Some(router_address) = &router_address {
+            if let Err(err) = parse_configured_address("router address", router_address) {
+                errors.push(err.to_string());
+            }
+        }
+
+        let protocols: BTreeSet<&String> = chain_executors.keys().collect();
+        let mut supported_protocols = Vec::new();
+        let mut missing_encoders = Vec::new();
+        for protocol in protocols {
+            if let Err(err) = parse_configured_address(
+                &format!("executor address of {}", protocol),
+                &chain_executors[protocol],
+            ) {
+                errors.push(err.to_string());
+            }
+            if PROTOCOL_SYSTEMS.contains(&protocol.as_str()) {
+                supported_protocols.push(protocol.clone());
+            } else {
+                missing_encoders.push(protocol.clone());
+            }
+        }
+        let mut missing_executors: Vec<String> = PROTOCOL_SYSTEMS
+            .iter()
+            .filter(|protocol| !chain_executors.contains_key(**protocol))
+            .map(|protocol| protocol.to_string())
+            .collect();
+        missing_executors.sort();
+
+        reports.push(ChainConfigReport {
+            chain: chain.clone(),
+            router_address,
+            supported_protocols,
+            missing_executors,
+            missing_encoders,
+            errors,
+        });
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_report(reports: &[ChainConfigReport], chain: &str) -> ChainConfigReport {
+        reports
+            .iter()
+            .find(|report| report.chain == chain)
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn test_check_default_config() {
+        let reports = check_config(None).unwrap();
+
+        let ethereum = get_report(&reports, "ethereum");
+        assert!(ethereum.is_valid());
+        assert_eq!(ethereum.supported_protocols.len(), 9);
+        assert_eq!(ethereum.missing_executors, vec!["native_wrapper".to_string()]);
+
+        let worldchain = get_report(&reports, "worldchain");
+        assert!(!worldchain.is_valid());
+        assert_eq!(worldchain.supported_protocols, vec!["uniswap_v3".to_string()]);
+        assert_eq!(
+            worldchain.errors,
+            vec![
+                "Fatal error: Invalid router address: the zero address".to_string(),
+                "Fatal error: Invalid executor address of uniswap_v3: the zero address".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_config_file() {
+        let reports =
+            check_config(Some("config/test_executor_addresses.json".to_string())).unwrap();
+
+        let ethereum = get_report(&reports, "ethereum");
+        assert!(ethereum.is_valid());
+        assert_eq!(ethereum.supported_protocols.len(), 10);
+        assert!(ethereum.missing_executors.is_empty());
+
+        // Chains with a default router but no executors in the file are reported too
+        let base = get_report(&reports, "base");
+        assert!(base.supported_protocols.is_empty());
+        assert_eq!(base.missing_executors.len(), 10);
+    }
+}
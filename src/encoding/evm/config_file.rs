@@ -0,0 +1,400 @@
+use std::{collections::HashMap, env, fs, path::Path};
+
+use alloy::signers::local::PrivateKeySigner;
+use serde::Deserialize;
+use tycho_common::{models::Chain, Bytes};
+
+use crate::encoding::{
+    errors::EncodingError, evm::encoder_builders::TychoRouterEncoderBuilder,
+    tycho_encoder::TychoEncoder,
+};
+
+/// Configuration of a Tycho router encoder. Unset options keep the defaults of
+/// `TychoRouterEncoderBuilder`. It isn't `Debug`, so that the private key of the swapper isn't
+/// logged.
+#[derive(Clone, Default, Deserialize)]
+pub struct RouterConfig {
+    pub executors_file_path: Option<String>,
+    pub router_address: Option<Bytes>,
+    pub swapper_pk: Option<String>,
+    pub token_in_already_in_router: Option<bool>,
+    pub optimize_routes: Option<bool>,
+    pub allow_unchecked_addresses: Option<bool>,
+    pub verify_router: Option<bool>,
+}
+
+impl RouterConfig {
+    /// Overrides the options set in the given configuration.
+    pub fn update(&mut self, config: RouterConfig) {
+        self.executors_file_path = config
+            .executors_file_path
+            .or(self.executors_file_path.take());
+        self.router_address = config
+            .router_address
+            .or(self.router_address.take());
+        self.swapper_pk = config
+            .swapper_pk
+            .or(self.swapper_pk.take());
+        self.token_in_already_in_router = config
+            .token_in_already_in_router
+            .or(self.token_in_already_in_router);
+        self.optimize_routes = config
+            .optimize_routes
+            .or(self.optimize_routes);
+        self.allow_unchecked_addresses = config
+            .allow_unchecked_addresses
+            .or(self.allow_unchecked_addresses);
+        self.verify_router = config
+            .verify_router
+            .or(self.verify_router);
+    }
+
+    /// Builds the Tycho router encoder of the given chain with this configuration.
+    pub fn build_encoder(
+        &self,
+        chain: Chain,
+    ) -> Result<Box<dyn TychoEncoder + Send + Sync>, EncodingError> {
+        let mut builder = TychoRouterEncoderBuilder::new().chain(chain);
+        if let Some(config_path) = self.executors_file_path.clone() {
+            builder = builder.executors_file_path(config_path);
+        }
+        if let Some(router_address) = self.router_address.clone() {
+            builder = builder.router_address(router_address);
+        }
+        if let Some(swapper_pk) = self.swapper_pk.clone() {
+            builder = builder.swapper_pk(swapper_pk);
+        }
+        if let Some(token_in_already_in_router) = self.token_in_already_in_router {
+            builder = builder.token_in_already_in_router(token_in_already_in_router);
+        }
+        if let Some(optimize_routes) = self.optimize_routes {
+            builder = builder.optimize_routes(optimize_routes);
+        }
+        if let Some(allow_unchecked_addresses) = self.allow_unchecked_addresses {
+            builder = builder.allow_unchecked_addresses(allow_unchecked_addresses);
+        }
+        if let Some(verify_router) = self.verify_router {
+            builder = builder.verify_router(verify_router);
+        }
+        builder.build()
+    }
+}
+
+/// Configuration file of `tycho-encode` and `tycho-encode-server`, given with `--config`.
+///
+/// # Fields
+/// * `chain`: Chain used when `--chain` isn't given
+/// * `chains`: Options of each chain, by chain name
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub chain: Option<Chain>,
+    #[serde(default)]
+    pub chains: HashMap<String, ChainConfig>,
+}
+
+/// Options of a chain in the config file.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainConfig {
+    pub router_address: Option<Bytes>,
+    pub executors_file_path: Option<String>,
+    /// RPC URL of the on-chain lookups, exported as `RPC_URL` unless it's already set
+    pub rpc_url: Option<String>,
+    pub signer: Option<SignerSource>,
+    pub token_in_already_in_router: Option<bool>,
+    pub optimize_routes: Option<bool>,
+    pub allow_unchecked_addresses: Option<bool>,
+    pub verify_router: Option<bool>,
+}
+
+/// Where the private key of the swapper is read from, so that it never appears on the command
+/// line.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum SignerSource {
+    /// An environment variable holding the hex private key
+    Env { env: String },
+    /// An encrypted JSON keystore, and the environment variable holding its password
+    Keystore { keystore: String, password_env: String },
+}
+
+impl SignerSource {
+    /// Reads the private key, as a hex string.
+    pub fn private_key(&self) -> Result<String, EncodingError> {
+        match self {
+            SignerSource::Env { env } => env::var(env).map_err(|_| {
+                EncodingError::FatalError(format!("Missing signer variable {} in environment", env))
+            }),
+            SignerSource::Keystore { keystore, password_env } => {
+                let password = env::var(password_env).map_err(|_| {
+                    EncodingError::FatalError(format!(
+                        "Missing keystore password variable {} in environment",
+                        password_env
+                    ))
+                })?;
+                let signer =
+                    PrivateKeySigner::decrypt_keystore(keystore, password).map_err(|e| {
+                        EncodingError::FatalError(format!(
+                            "Failed to decrypt keystore {}: {}",
+                            keystore, e
+                        ))
+                    })?;
+                Ok(format!("0x{}", hex::encode(signer.to_bytes())))
+            }
+        }
+    }
+}
+
+impl ConfigFile {
+    /// Reads the config file at the given path, parsed as TOML if its extension is `.toml` and as
+    /// JSON otherwise.
+    pub fn load(path: &str) -> Result<Self, EncodingError> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            EncodingError::FatalError(format!("Failed to read config file {}: {}", path, e))
+        })?;
+        let invalid_config =
+            |e: String| EncodingError::FatalError(format!("Invalid config file {}: {}", path, e));
+        let config: ConfigFile = if Path::new(path)
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            toml::from_str(&content).map_err(|e| invalid_config(e.to_string()))?
+        } else {
+            serde_json::from_str(&content).map_err(|e| invalid_config(e.to_string()))?
+        };
+        for name in config.chains.keys() {
+            parse_chain(name).map_err(|_| {
+                EncodingError::FatalError(format!("Unknown chain {} in config file {}", name, path))
+            })?;
+        }
+        Ok(config)
+    }
+
+    /// Returns the configured chains, sorted by name.
+    pub fn configured_chains(&self) -> Vec<Chain> {
+        let mut names: Vec<&String> = self.chains.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .filter_map(|name| parse_chain(name).ok())
+            .collect()
+    }
+
+    /// Returns the options of the given chain, which are all unset if the chain isn't configured.
+    pub fn chain_config(&self, chain: Chain) -> ChainConfig {
+        self.chains
+            .get(&chain.to_string())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the options of the Tycho router encoder of the given chain. The private key of the
+    /// swapper is only read from its signer source if `read_signer` is set, e.g. when a flag
+    /// doesn't override it.
+    pub fn router_config(
+        &self,
+        chain: Chain,
+        read_signer: bool,
+    ) -> Result<RouterConfig, EncodingError> {
+        let chain_config = self.chain_config(chain);
+        let swapper_pk = match &chain_config.signer {
+            Some(signer) if read_signer => Some(signer.private_key()?),
+            _ => None,
+        };
+        Ok(RouterConfig {
+            executors_file_path: chain_config.executors_file_path,
+            router_address: chain_config.router_address,
+            swapper_pk,
+            token_in_already_in_router: chain_config.token_in_already_in_router,
+            optimize_routes: chain_config.optimize_routes,
+            allow_unchecked_addresses: chain_config.allow_unchecked_addresses,
+            verify_router: chain_config.verify_router,
+        })
+    }
+
+    /// Exports the RPC URL of the given chain as `RPC_URL`, which the on-chain lookups read, unless
+    /// the environment already sets it.
+    pub fn export_rpc_url(&self, chain: Chain) {
+        if let Some(rpc_url) = self.chain_config(chain).rpc_url {
+            if env::var_os("RPC_URL").is_none() {
+                env::set_var("RPC_URL", rpc_url);
+            }
+        }
+    }
+}
+
+/// Parses a chain from its name, e.g. `ethereum`.
+pub fn parse_chain(name: &str) -> Result<Chain, EncodingError> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| EncodingError::InvalidInput(format!("Unknown chain {}", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    /// Writes a config file to the temporary directory and returns its path.
+    fn write_config(name: &str, content: &str) -> String {
+        let path = env::temp_dir().join(name);
+        fs::write(&path, content).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_update() {
+        let mut config = RouterConfig {
+            executors_file_path: Some("config/executor_addresses.json".to_string()),
+            router_address: Some(
+                Bytes::from_str("0x1111111111111111111111111111111111111111").unwrap(),
+            ),
+            token_in_already_in_router: Some(true),
+            optimize_routes: Some(true),
+            ..Default::default()
+        };
+
+        config.update(RouterConfig {
+            router_address: Some(
+                Bytes::from_str("0x2222222222222222222222222222222222222222").unwrap(),
+            ),
+            token_in_already_in_router: Some(false),
+            verify_router: Some(true),
+            ..Default::default()
+        });
+
+        // The options set in the update override the others, which are kept
+        assert_eq!(config.executors_file_path, Some("config/executor_addresses.json".to_string()));
+        assert_eq!(
+            config.router_address,
+            Some(Bytes::from_str("0x2222222222222222222222222222222222222222").unwrap())
+        );
+        assert_eq!(config.swapper_pk, None);
+        assert_eq!(config.token_in_already_in_router, Some(false));
+        assert_eq!(config.optimize_routes, Some(true));
+        assert_eq!(config.allow_unchecked_addresses, None);
+        assert_eq!(config.verify_router, Some(true));
+    }
+
+    #[test]
+    fn test_load_toml() {
+        let path = write_config(
+            "tycho_execution_test_load.toml",
+            r#"
+chain = "ethereum"
+
+[chains.ethereum]
+router_address = "0x3ede3eca2a72b3aecc820e955b36f38437d01395"
+executors_file_path = "config/test_executor_addresses.json"
+signer = { env = "TYCHO_EXECUTION_TEST_UNSET_SIGNER" }
+optimize_routes = true
+
+[chains.base]
+signer = { keystore = "keystore.json", password_env = "KEYSTORE_PASSWORD" }
+"#,
+        );
+
+        let config = ConfigFile::load(&path).unwrap();
+
+        assert_eq!(config.chain, Some(Chain::Ethereum));
+        assert_eq!(config.configured_chains(), vec![Chain::Base, Chain::Ethereum]);
+        let chain_config = config.chain_config(Chain::Ethereum);
+        assert_eq!(
+            chain_config.executors_file_path,
+            Some("config/test_executor_addresses.json".to_string())
+        );
+        assert!(matches!(chain_config.signer, Some(SignerSource::Env { .. })));
+        assert!(matches!(
+            config.chain_config(Chain::Base).signer,
+            Some(SignerSource::Keystore { .. })
+        ));
+        // An unconfigured chain has no options set
+        assert_eq!(
+            config
+                .chain_config(Chain::Unichain)
+                .router_address,
+            None
+        );
+    }
+
+    #[test]
+    fn test_load_json() {
+        let path = write_config(
+            "tycho_execution_test_load.json",
+            r#"{"chains": {"ethereum": {"token_in_already_in_router": true}}}"#,
+        );
+
+        let config = ConfigFile::load(&path).unwrap();
+
+        assert_eq!(config.chain, None);
+        assert_eq!(
+            config
+                .chain_config(Chain::Ethereum)
+                .token_in_already_in_router,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_load_invalid() {
+        let unknown_chain = write_config(
+            "tycho_execution_test_unknown_chain.toml",
+            "[chains.not_a_chain]\noptimize_routes = true\n",
+        );
+        let unknown_option = write_config(
+            "tycho_execution_test_unknown_option.toml",
+            "[chains.ethereum]\nswapper_pk = \"0x123\"\n",
+        );
+
+        let unknown_chain_error = ConfigFile::load(&unknown_chain).unwrap_err();
+        let unknown_option_error = ConfigFile::load(&unknown_option).unwrap_err();
+
+        assert!(unknown_chain_error
+            .to_string()
+            .contains("Unknown chain not_a_chain"));
+        assert!(unknown_option_error
+            .to_string()
+            .contains("Invalid config file"));
+        assert!(ConfigFile::load("missing_config.toml").is_err());
+    }
+
+    #[test]
+    fn test_router_config_reads_signer_only_if_asked() {
+        let config: ConfigFile = toml::from_str(
+            r#"
+[chains.ethereum]
+router_address = "0x3ede3eca2a72b3aecc820e955b36f38437d01395"
+signer = { env = "TYCHO_EXECUTION_TEST_UNSET_SIGNER" }
+"#,
+        )
+        .unwrap();
+
+        let router_config = config
+            .router_config(Chain::Ethereum, false)
+            .unwrap();
+        let error = config
+            .router_config(Chain::Ethereum, true)
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            router_config.router_address,
+            Some(Bytes::from_str("0x3ede3eca2a72b3aecc820e955b36f38437d01395").unwrap())
+        );
+        assert_eq!(router_config.swapper_pk, None);
+        assert_eq!(
+            error,
+            EncodingError::FatalError(
+                "Missing signer variable TYCHO_EXECUTION_TEST_UNSET_SIGNER in environment"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_chain() {
+        assert_eq!(parse_chain("ethereum").unwrap(), Chain::Ethereum);
+        assert!(parse_chain("not_a_chain").is_err());
+    }
+}
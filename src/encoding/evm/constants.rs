@@ -45,4 +45,8 @@ pub static CALLBACK_CONSTRAINED_PROTOCOLS: LazyLock<HashSet<&'static str>> = Laz
     set.insert("uniswap_v4");
     set.insert("ekubo_v2");
     set
-});
\ No newline at end of file
+});
+
+/// Protocol system of the steps converting between the chain's native token and its wrapped token
+/// in the middle of a route. These steps are executed on the funds held by the router.
+pub const NATIVE_WRAPPER_PROTOCOL: &str = "native_wrapper";
@@ -2,14 +2,17 @@ use std::collections::HashMap;
 
 use tycho_common::{models::Chain as TychoCommonChain, Bytes};
 
+#[cfg(feature = "rpc")]
+use crate::encoding::evm::{router_verifier::RouterVerifier, utils::bytes_to_address};
 use crate::encoding::{
     errors::EncodingError,
     evm::{
         constants::DEFAULT_ROUTERS_JSON,
-        swap_encoder::swap_encoder_registry::SwapEncoderRegistry,
+        swap_encoder::swap_encoder_registry::{get_executor_addresses, SwapEncoderRegistry},
         tycho_encoders::{TychoExecutorEncoder, TychoRouterEncoder},
+        utils::parse_configured_address,
     },
-    models::Chain,
+    models::{Chain, FundingMode},
     tycho_encoder::TychoEncoder,
 };
 
@@ -22,6 +25,9 @@ pub struct TychoRouterEncoderBuilder {
     executors_file_path: Option<String>,
     router_address: Option<Bytes>,
     token_in_already_in_router: Option<bool>,
+    optimize_routes: Option<bool>,
+    allow_unchecked_addresses: Option<bool>,
+    verify_router: Option<bool>,
 }
 
 impl Default for TychoRouterEncoderBuilder {
@@ -38,6 +44,9 @@ impl TychoRouterEncoderBuilder {
             executors_file_path: None,
             router_address: None,
             token_in_already_in_router: None,
+            optimize_routes: None,
+            allow_unchecked_addresses: None,
+            verify_router: None,
         }
     }
     pub fn chain(mut self, chain: TychoCommonChain) -> Self {
@@ -74,27 +83,59 @@ impl TychoRouterEncoderBuilder {
         self
     }
 
+    /// Sets the `optimize_routes` flag.
+    /// If set to true, the swaps of each solution are reordered and cleaned up before encoding
+    /// (see `route_optimizer::optimize_solution`). Defaults to false.
+    pub fn optimize_routes(mut self, optimize_routes: bool) -> Self {
+        self.optimize_routes = Some(optimize_routes);
+        self
+    }
+
+    /// Sets the `allow_unchecked_addresses` flag.
+    /// By default, building fails if the router address or an executor address is malformed or the
+    /// zero address. If set to true, the addresses are used as they are. Defaults to false.
+    pub fn allow_unchecked_addresses(mut self, allow_unchecked_addresses: bool) -> Self {
+        self.allow_unchecked_addresses = Some(allow_unchecked_addresses);
+        self
+    }
+
+    /// Sets the `verify_router` flag.
+    /// If set to true, building queries the router on-chain and fails if it is paused or doesn't
+    /// approve one of the configured executors, which would make the swaps revert. It needs the
+    /// `rpc` feature and the `RPC_URL` env var. Defaults to false.
+    pub fn verify_router(mut self, verify_router: bool) -> Self {
+        self.verify_router = Some(verify_router);
+        self
+    }
+
     /// Builds the `TychoRouterEncoder` instance using the configured chain.
     /// Returns an error if either the chain has not been set.
-    pub fn build(self) -> Result<Box<dyn TychoEncoder>, EncodingError> {
+    pub fn build(self) -> Result<Box<dyn TychoEncoder + Send + Sync>, EncodingError> {
         if let Some(chain) = self.chain {
-            let tycho_router_address;
-            if let Some(address) = self.router_address {
-                tycho_router_address = address;
+            let tycho_router_address = match self.router_address {
+                Some(address) => address,
+                None => default_router_address(&chain)?,
+            };
+
+            let check_addresses = !self
+                .allow_unchecked_addresses
+                .unwrap_or(false);
+            if check_addresses {
+                parse_configured_address(
+                    "router address",
+                    &format!("0x{}", hex::encode(&tycho_router_address)),
+                )?;
+            }
+            let swap_encoder_registry = if check_addresses {
+                SwapEncoderRegistry::new(self.executors_file_path.clone(), chain.clone())?
             } else {
-                let default_routers: HashMap<String, Bytes> =
-                    serde_json::from_str(DEFAULT_ROUTERS_JSON)?;
-                tycho_router_address = default_routers
-                    .get(&chain.name)
-                    .ok_or(EncodingError::FatalError(
-                        "No default router address found for chain".to_string(),
-                    ))?
-                    .to_owned();
+                SwapEncoderRegistry::new_unchecked(self.executors_file_path.clone(), chain.clone())?
+            };
+            if self.verify_router.unwrap_or(false) {
+                let executors = get_executor_addresses(self.executors_file_path.clone(), &chain)?;
+                verify_router(&tycho_router_address, &executors)?;
             }
 
-            let swap_encoder_registry =
-                SwapEncoderRegistry::new(self.executors_file_path.clone(), chain.clone())?;
-
             Ok(Box::new(TychoRouterEncoder::new(
                 chain,
                 swap_encoder_registry,
@@ -102,6 +143,7 @@ impl TychoRouterEncoderBuilder {
                 tycho_router_address,
                 self.token_in_already_in_router
                     .unwrap_or(false),
+                self.optimize_routes.unwrap_or(false),
             )?))
         } else {
             Err(EncodingError::FatalError(
@@ -111,10 +153,42 @@ impl TychoRouterEncoderBuilder {
     }
 }
 
+/// Returns the address of the router deployed by Tycho on the chain (config/router_addresses.json).
+pub fn default_router_address(chain: &Chain) -> Result<Bytes, EncodingError> {
+    let default_routers: HashMap<String, Bytes> = serde_json::from_str(DEFAULT_ROUTERS_JSON)?;
+    default_routers
+        .get(&chain.name)
+        .cloned()
+        .ok_or(EncodingError::FatalError("No default router address found for chain".to_string()))
+}
+
+/// Verifies on-chain that the router isn't paused and approves the given executors.
+#[cfg(feature = "rpc")]
+fn verify_router(
+    router_address: &Bytes,
+    executors: &HashMap<String, String>,
+) -> Result<(), EncodingError> {
+    RouterVerifier::new()?.verify(bytes_to_address(router_address)?, executors)
+}
+
+#[cfg(not(feature = "rpc"))]
+fn verify_router(
+    _router_address: &Bytes,
+    _executors: &HashMap<String, String>,
+) -> Result<(), EncodingError> {
+    Err(EncodingError::FatalError(
+        "Verifying the router on-chain needs the `rpc` feature".to_string(),
+    ))
+}
+
 /// Builder pattern for constructing a `TychoExecutorEncoder` with customizable options.
 pub struct TychoExecutorEncoderBuilder {
     chain: Option<Chain>,
     executors_file_path: Option<String>,
+    full_calldata: Option<bool>,
+    caller_address: Option<Bytes>,
+    funding_mode: Option<FundingMode>,
+    allow_unchecked_addresses: Option<bool>,
 }
 
 impl Default for TychoExecutorEncoderBuilder {
@@ -125,7 +199,14 @@ impl Default for TychoExecutorEncoderBuilder {
 
 impl TychoExecutorEncoderBuilder {
     pub fn new() -> Self {
-        TychoExecutorEncoderBuilder { chain: None, executors_file_path: None }
+        TychoExecutorEncoderBuilder {
+            chain: None,
+            executors_file_path: None,
+            full_calldata: None,
+            caller_address: None,
+            funding_mode: None,
+            allow_unchecked_addresses: None,
+        }
     }
     pub fn chain(mut self, chain: TychoCommonChain) -> Self {
         self.chain = Some(chain.into());
@@ -139,13 +220,57 @@ impl TychoExecutorEncoderBuilder {
         self
     }
 
+    /// Sets the `full_calldata` flag.
+    /// If set to true, the encoded transactions contain the full `IExecutor.swap(givenAmount,
+    /// data)` calldata, ready to be sent to the executor. Otherwise, they only contain the
+    /// protocol data.
+    pub fn full_calldata(mut self, full_calldata: bool) -> Self {
+        self.full_calldata = Some(full_calldata);
+        self
+    }
+
+    /// Sets the address of the contract calling the executors, e.g. your own router.
+    /// It is used to compute the receivers of intermediate swaps and the token approvals needed by
+    /// the executors. If it's not set, the solution's sender is assumed to be the caller.
+    pub fn caller_address(mut self, caller_address: Bytes) -> Self {
+        self.caller_address = Some(caller_address);
+        self
+    }
+
+    /// Sets how the input tokens reach the caller contract.
+    /// If it's not set, the input tokens are assumed to be already held by the caller contract.
+    pub fn funding_mode(mut self, funding_mode: FundingMode) -> Self {
+        self.funding_mode = Some(funding_mode);
+        self
+    }
+
+    /// Sets the `allow_unchecked_addresses` flag.
+    /// By default, building fails if an executor address is malformed or the zero address. If set
+    /// to true, the addresses are used as they are. Defaults to false.
+    pub fn allow_unchecked_addresses(mut self, allow_unchecked_addresses: bool) -> Self {
+        self.allow_unchecked_addresses = Some(allow_unchecked_addresses);
+        self
+    }
+
     /// Builds the `TychoExecutorEncoder` instance using the configured chain and strategy.
     /// Returns an error if either the chain or strategy has not been set.
-    pub fn build(self) -> Result<Box<dyn TychoEncoder>, EncodingError> {
+    pub fn build(self) -> Result<Box<dyn TychoEncoder + Send + Sync>, EncodingError> {
         if let Some(chain) = self.chain {
-            let swap_encoder_registry =
-                SwapEncoderRegistry::new(self.executors_file_path.clone(), chain.clone())?;
-            Ok(Box::new(TychoExecutorEncoder::new(chain, swap_encoder_registry)?))
+            let swap_encoder_registry = if self
+                .allow_unchecked_addresses
+                .unwrap_or(false)
+            {
+                SwapEncoderRegistry::new_unchecked(self.executors_file_path.clone(), chain.clone())?
+            } else {
+                SwapEncoderRegistry::new(self.executors_file_path.clone(), chain.clone())?
+            };
+            Ok(Box::new(TychoExecutorEncoder::new(
+                chain,
+                swap_encoder_registry,
+                self.full_calldata.unwrap_or(false),
+                self.caller_address,
+                self.funding_mode.unwrap_or_default(),
+            )?))
         } else {
             Err(EncodingError::FatalError(
                 "Please set the chain and strategy before building the encoder".to_string(),
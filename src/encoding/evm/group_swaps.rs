@@ -1,3 +1,4 @@
+use num_bigint::BigUint;
 use tycho_common::Bytes;
 
 use crate::encoding::{evm::constants::GROUPABLE_PROTOCOLS, models::Swap};
@@ -11,6 +12,7 @@ use crate::encoding::{evm::constants::GROUPABLE_PROTOCOLS, models::Swap};
 /// * `protocol_system`: String, the protocol system of the swaps
 /// * `swaps`: Vec<Swap>, the sequence of swaps to be executed as a group
 /// * `split`: f64, the split percentage of the first swap in the group
+/// * `amount_in`: Option<BigUint>, the absolute input amount of the first swap in the group
 #[derive(Clone, PartialEq, Debug)]
 pub struct SwapGroup {
     pub token_in: Bytes,
@@ -18,6 +20,7 @@ pub struct SwapGroup {
     pub protocol_system: String,
     pub swaps: Vec<Swap>,
     pub split: f64,
+    pub amount_in: Option<BigUint>,
 }
 
 /// Group consecutive swaps which can be encoded into one swap execution for gas optimization.
@@ -35,8 +38,10 @@ pub fn group_swaps(swaps: Vec<Swap>) -> Vec<SwapGroup> {
         groupable_protocol = GROUPABLE_PROTOCOLS.contains(&current_swap_protocol.as_str());
 
         // Split 0 can also mean that the swap is the remaining part of a branch of splits,
-        // so we need to check the last swap's out token as well
-        let no_split = swap.split == 0.0 && swap.token_in == last_swap_out_token;
+        // so we need to check the last swap's out token as well. A swap with an absolute input
+        // amount is always the start of a new branch.
+        let no_split =
+            swap.split == 0.0 && swap.amount_in.is_none() && swap.token_in == last_swap_out_token;
 
         if current_swap_protocol == last_swap_protocol && groupable_protocol && no_split {
             // Second or later groupable pool in a sequence of groupable pools. Merge to the
@@ -58,6 +63,7 @@ pub fn group_swaps(swaps: Vec<Swap>) -> Vec<SwapGroup> {
                 protocol_system: current_swap_protocol.clone(),
                 swaps: vec![swap.clone()],
                 split: swap.split,
+                amount_in: swap.amount_in.clone(),
             });
         }
         last_swap_protocol = current_swap_protocol;
@@ -105,6 +111,7 @@ mod tests {
             // This represents the remaining 50%, but to avoid any rounding errors we set this to
             // 0 to signify "the remainder of the WETH value". It should still be very close to 50%
             split: 0f64,
+            amount_in: None,
         };
         let swap_wbtc_usdc = Swap {
             component: ProtocolComponent {
@@ -114,6 +121,7 @@ mod tests {
             token_in: wbtc.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            amount_in: None,
         };
         let swap_usdc_dai = Swap {
             component: ProtocolComponent {
@@ -123,6 +131,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: dai.clone(),
             split: 0f64,
+            amount_in: None,
         };
         let grouped_swaps = group_swaps(vec![
             swap_weth_wbtc.clone(),
@@ -139,6 +148,7 @@ mod tests {
                     token_out: usdc.clone(),
                     protocol_system: "uniswap_v4".to_string(),
                     split: 0f64,
+                    amount_in: None,
                 },
                 SwapGroup {
                     swaps: vec![swap_usdc_dai],
@@ -146,6 +156,7 @@ mod tests {
                     token_out: dai,
                     protocol_system: "uniswap_v2".to_string(),
                     split: 0f64,
+                    amount_in: None,
                 }
             ]
         );
@@ -174,6 +185,7 @@ mod tests {
             token_in: wbtc.clone(),
             token_out: weth.clone(),
             split: 0f64,
+            amount_in: None,
         };
         let swap_weth_usdc = Swap {
             component: ProtocolComponent {
@@ -183,6 +195,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0.5f64,
+            amount_in: None,
         };
         let swap_weth_dai = Swap {
             component: ProtocolComponent {
@@ -194,6 +207,7 @@ mod tests {
             // This represents the remaining 50%, but to avoid any rounding errors we set this to
             // 0 to signify "the remainder of the WETH value". It should still be very close to 50%
             split: 0f64,
+            amount_in: None,
         };
         let swap_dai_usdc = Swap {
             component: ProtocolComponent {
@@ -203,6 +217,7 @@ mod tests {
             token_in: dai.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            amount_in: None,
         };
         let grouped_swaps = group_swaps(vec![
             swap_wbtc_weth.clone(),
@@ -220,6 +235,7 @@ mod tests {
                     token_out: weth.clone(),
                     protocol_system: "uniswap_v4".to_string(),
                     split: 0f64,
+                    amount_in: None,
                 },
                 SwapGroup {
                     swaps: vec![swap_weth_usdc],
@@ -227,6 +243,7 @@ mod tests {
                     token_out: usdc.clone(),
                     protocol_system: "uniswap_v4".to_string(),
                     split: 0.5f64,
+                    amount_in: None,
                 },
                 SwapGroup {
                     swaps: vec![swap_weth_dai, swap_dai_usdc],
@@ -234,6 +251,7 @@ mod tests {
                     token_out: usdc,
                     protocol_system: "uniswap_v4".to_string(),
                     split: 0f64,
+                    amount_in: None,
                 }
             ]
         );
@@ -261,6 +279,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: wbtc.clone(),
             split: 0.5f64,
+            amount_in: None,
         };
         let swap_wbtc_usdc = Swap {
             component: ProtocolComponent {
@@ -270,6 +289,7 @@ mod tests {
             token_in: wbtc.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            amount_in: None,
         };
         let swap_weth_dai = Swap {
             component: ProtocolComponent {
@@ -281,6 +301,7 @@ mod tests {
             // This represents the remaining 50%, but to avoid any rounding errors we set this to
             // 0 to signify "the remainder of the WETH value". It should still be very close to 50%
             split: 0f64,
+            amount_in: None,
         };
         let swap_dai_usdc = Swap {
             component: ProtocolComponent {
@@ -290,6 +311,7 @@ mod tests {
             token_in: dai.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            amount_in: None,
         };
 
         let grouped_swaps = group_swaps(vec![
@@ -308,6 +330,7 @@ mod tests {
                     token_out: usdc.clone(),
                     protocol_system: "vm:balancer_v3".to_string(),
                     split: 0.5f64,
+                    amount_in: None,
                 },
                 SwapGroup {
                     swaps: vec![swap_weth_dai, swap_dai_usdc],
@@ -315,6 +338,7 @@ mod tests {
                     token_out: usdc,
                     protocol_system: "uniswap_v4".to_string(),
                     split: 0f64,
+                    amount_in: None,
                 }
             ]
         );
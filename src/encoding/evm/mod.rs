@@ -1,7 +1,15 @@
 pub mod approvals;
+pub mod calldata_decoder;
+pub mod config_check;
+#[cfg(feature = "rpc")]
+pub mod config_file;
 mod constants;
 pub mod encoder_builders;
 mod group_swaps;
+pub mod route_optimizer;
+pub mod route_visualizer;
+#[cfg(feature = "rpc")]
+pub mod router_verifier;
 pub mod strategy_encoder;
 mod swap_encoder;
 pub mod tycho_encoders;
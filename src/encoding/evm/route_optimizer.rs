@@ -0,0 +1,390 @@
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::aliases::U24;
+use tycho_common::Bytes;
+
+use crate::encoding::{
+    evm::{constants::GROUPABLE_PROTOCOLS, utils::percentage_to_uint24},
+    models::{Solution, Swap},
+};
+
+/// A swap of the route, possibly merging several swaps on the same component.
+///
+/// # Fields
+/// * `swap`: Swap, the first of the merged swaps
+/// * `index`: usize, position of the first of the merged swaps in the original route
+/// * `split`: f64, sum of the splits of the merged swaps
+/// * `is_remainder`: bool, whether one of the merged swaps takes the remainder of its input token
+/// * `share`: f64, share of the total amount of the input token swapped by this edge
+struct RouteEdge {
+    swap: Swap,
+    index: usize,
+    split: f64,
+    is_remainder: bool,
+    share: f64,
+}
+
+/// Returns an equivalent solution whose swaps are cheaper to execute.
+///
+/// The optimization:
+/// * merges swaps on the same component with the same input and output tokens
+/// * drops swaps whose share of their input token is too small to be encoded, together with the
+///   swaps which depend on their output
+/// * makes each token's remainder swap the last swap of that token
+/// * reorders independent branches so that consecutive swaps of groupable protocols (e.g. Uniswap
+///   V4 or Ekubo) follow each other and can be grouped into a single swap execution
+///
+/// Solutions which can't be optimized safely (e.g. with absolute swap amounts, multiple inputs or
+/// invalid splits) are returned unchanged, for the validators to report any problem.
+pub fn optimize_solution(solution: &Solution) -> Solution {
+    match optimize_swaps(solution) {
+        Some(swaps) => Solution { swaps, ..solution.clone() },
+        None => solution.clone(),
+    }
+}
+
+fn optimize_swaps(solution: &Solution) -> Option<Vec<Swap>> {
+    if solution.swaps.len() < 2 ||
+        !solution.additional_inputs.is_empty() ||
+        solution
+            .swaps
+            .iter()
+            .any(|swap| swap.amount_in.is_some() || swap.split < 0.0)
+    {
+        return None;
+    }
+    let start_token = solution.swaps[0].token_in.clone();
+    let sinks = get_sink_tokens(solution.swaps.iter());
+
+    let mut edges = merge_duplicate_swaps(&solution.swaps);
+    compute_shares(&mut edges)?;
+
+    // Drop the swaps which are too small to be encoded, then the swaps left without input
+    edges.retain(|edge| percentage_to_uint24(edge.share) != U24::ZERO);
+    loop {
+        let reachable = get_reachable_tokens(&start_token, &edges);
+        let n_edges = edges.len();
+        edges.retain(|edge| reachable.contains(&edge.swap.token_in));
+        if edges.len() == n_edges {
+            break;
+        }
+    }
+    if get_sink_tokens(edges.iter().map(|edge| &edge.swap)) != sinks {
+        return None;
+    }
+
+    let order = sort_edges(&start_token, &edges)?;
+
+    // Each token's remainder swap is its last swap
+    let mut last_swap_of_token: HashMap<&Bytes, usize> = HashMap::new();
+    for &i in order.iter() {
+        last_swap_of_token.insert(&edges[i].swap.token_in, i);
+    }
+    Some(
+        order
+            .iter()
+            .map(|&i| {
+                let edge = &edges[i];
+                let split =
+                    if last_swap_of_token[&edge.swap.token_in] == i { 0f64 } else { edge.share };
+                Swap { split, ..edge.swap.clone() }
+            })
+            .collect(),
+    )
+}
+
+/// Merges the swaps on the same component with the same input and output tokens.
+fn merge_duplicate_swaps(swaps: &[Swap]) -> Vec<RouteEdge> {
+    let mut edges: Vec<RouteEdge> = vec![];
+    for (index, swap) in swaps.iter().enumerate() {
+        let duplicate = edges.iter_mut().find(|edge| {
+            edge.swap.component.id == swap.component.id &&
+                edge.swap.token_in == swap.token_in &&
+                edge.swap.token_out == swap.token_out
+        });
+        match duplicate {
+            Some(edge) => {
+                edge.split += swap.split;
+                edge.is_remainder |= swap.split == 0.0;
+            }
+            None => edges.push(RouteEdge {
+                swap: swap.clone(),
+                index,
+                split: swap.split,
+                is_remainder: swap.split == 0.0,
+                share: 0f64,
+            }),
+        }
+    }
+    edges
+}
+
+/// Computes the share of its input token swapped by each edge. Returns `None` if the splits of a
+/// token are invalid.
+fn compute_shares(edges: &mut [RouteEdge]) -> Option<()> {
+    let mut splits_by_token: HashMap<Bytes, (f64, usize)> = HashMap::new();
+    for edge in edges.iter() {
+        let (total_split, n_remainders) = splits_by_token
+            .entry(edge.swap.token_in.clone())
+            .or_insert((0f64, 0));
+        *total_split += edge.split;
+        if edge.is_remainder {
+            *n_remainders += 1;
+        }
+    }
+    if splits_by_token
+        .values()
+        .any(|(total_split, n_remainders)| *total_split >= 1.0 || *n_remainders != 1)
+    {
+        return None;
+    }
+    for edge in edges.iter_mut() {
+        edge.share = if edge.is_remainder {
+            edge.split + 1.0 - splits_by_token[&edge.swap.token_in].0
+        } else {
+            edge.split
+        };
+    }
+    Some(())
+}
+
+/// Returns the tokens which are bought by a swap but not sold by any.
+fn get_sink_tokens<'a>(swaps: impl Iterator<Item = &'a Swap> + Clone) -> HashSet<Bytes> {
+    let sold: HashSet<&Bytes> = swaps
+        .clone()
+        .map(|swap| &swap.token_in)
+        .collect();
+    swaps
+        .filter(|swap| !sold.contains(&swap.token_out))
+        .map(|swap| swap.token_out.clone())
+        .collect()
+}
+
+fn get_reachable_tokens(start_token: &Bytes, edges: &[RouteEdge]) -> HashSet<Bytes> {
+    let mut reachable = HashSet::from([start_token.clone()]);
+    loop {
+        let n_reachable = reachable.len();
+        for edge in edges.iter() {
+            if reachable.contains(&edge.swap.token_in) {
+                reachable.insert(edge.swap.token_out.clone());
+            }
+        }
+        if reachable.len() == n_reachable {
+            return reachable;
+        }
+    }
+}
+
+/// Sorts the edges so that every token is bought before it is sold and each token's remainder swap
+/// comes after the other swaps of that token. The original order is kept as much as possible,
+/// except that a swap of a groupable protocol is moved right after the swap it continues. Returns
+/// `None` if the swaps can't be sorted.
+fn sort_edges(start_token: &Bytes, edges: &[RouteEdge]) -> Option<Vec<usize>> {
+    // The start token is available from the beginning: the amounts bought back by cyclical swaps
+    // are not swapped again
+    let mut pending_buys: HashMap<&Bytes, usize> = HashMap::new();
+    let mut pending_sells: HashMap<&Bytes, usize> = HashMap::new();
+    for edge in edges.iter() {
+        if edge.swap.token_out != *start_token {
+            *pending_buys
+                .entry(&edge.swap.token_out)
+                .or_default() += 1;
+        }
+        *pending_sells
+            .entry(&edge.swap.token_in)
+            .or_default() += 1;
+    }
+
+    let mut by_original_order: Vec<usize> = (0..edges.len()).collect();
+    by_original_order.sort_by_key(|&i| edges[i].index);
+
+    let mut order: Vec<usize> = Vec::with_capacity(edges.len());
+    let mut emitted = vec![false; edges.len()];
+    while order.len() < edges.len() {
+        let is_available = |i: usize| {
+            let edge = &edges[i];
+            !emitted[i] &&
+                pending_buys
+                    .get(&edge.swap.token_in)
+                    .copied()
+                    .unwrap_or(0) ==
+                    0 &&
+                // the remainder swap waits for the other swaps of its token
+                (!edge.is_remainder || pending_sells[&edge.swap.token_in] == 1)
+        };
+        let next = order
+            .last()
+            .and_then(|&last| get_groupable_successor(last, edges))
+            .filter(|&i| is_available(i))
+            .or_else(|| {
+                by_original_order
+                    .iter()
+                    .copied()
+                    .find(|&i| is_available(i))
+            })?;
+
+        emitted[next] = true;
+        order.push(next);
+        if let Some(pending) = pending_buys.get_mut(&edges[next].swap.token_out) {
+            *pending -= 1;
+        }
+        if let Some(pending) = pending_sells.get_mut(&edges[next].swap.token_in) {
+            *pending -= 1;
+        }
+    }
+    Some(order)
+}
+
+/// Returns the swap which can be grouped with the given swap: the only swap selling its output, of
+/// the same groupable protocol, if the given swap is the only one buying that token.
+fn get_groupable_successor(i: usize, edges: &[RouteEdge]) -> Option<usize> {
+    let swap = &edges[i].swap;
+    let protocol = swap.component.protocol_system.as_str();
+    if !GROUPABLE_PROTOCOLS.contains(&protocol) {
+        return None;
+    }
+    let buyers = edges
+        .iter()
+        .filter(|edge| edge.swap.token_out == swap.token_out)
+        .count();
+    let sellers: Vec<usize> = (0..edges.len())
+        .filter(|&j| edges[j].swap.token_in == swap.token_out)
+        .collect();
+    match sellers.as_slice() {
+        [j] if buyers == 1 && edges[*j].swap.component.protocol_system == protocol => Some(*j),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use tycho_common::models::protocol::ProtocolComponent;
+
+    use super::*;
+
+    fn weth() -> Bytes {
+        Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap()
+    }
+
+    fn dai() -> Bytes {
+        Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap()
+    }
+
+    fn usdc() -> Bytes {
+        Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap()
+    }
+
+    fn wbtc() -> Bytes {
+        Bytes::from_str("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599").unwrap()
+    }
+
+    fn swap(id: &str, protocol: &str, token_in: Bytes, token_out: Bytes, split: f64) -> Swap {
+        Swap::new(
+            ProtocolComponent {
+                id: id.to_string(),
+                protocol_system: protocol.to_string(),
+                ..Default::default()
+            },
+            token_in,
+            token_out,
+            split,
+        )
+    }
+
+    fn solution(swaps: Vec<Swap>) -> Solution {
+        Solution { given_token: weth(), checked_token: usdc(), swaps, ..Default::default() }
+    }
+
+    #[test]
+    fn test_sequential_solution_unchanged() {
+        let swaps = vec![
+            swap("pool1", "uniswap_v2", weth(), dai(), 0f64),
+            swap("pool2", "uniswap_v4", dai(), usdc(), 0f64),
+        ];
+
+        let optimized = optimize_solution(&solution(swaps.clone()));
+
+        assert_eq!(optimized.swaps, swaps);
+    }
+
+    #[test]
+    fn test_groupable_swaps_made_contiguous() {
+        //         ┌──(USV4)──> DAI ──(USV4)──┐
+        // WETH ───┤                          ├──> USDC
+        //         └──(USV2)──> WBTC ─(USV2)──┘
+        let swaps = vec![
+            swap("pool1", "uniswap_v4", weth(), dai(), 0.6),
+            swap("pool2", "uniswap_v2", weth(), wbtc(), 0f64),
+            swap("pool3", "uniswap_v4", dai(), usdc(), 0f64),
+            swap("pool4", "uniswap_v2", wbtc(), usdc(), 0f64),
+        ];
+
+        let optimized = optimize_solution(&solution(swaps.clone()));
+
+        assert_eq!(
+            optimized.swaps,
+            vec![swaps[0].clone(), swaps[2].clone(), swaps[1].clone(), swaps[3].clone()]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_swaps_merged() {
+        let swaps = vec![
+            swap("pool1", "uniswap_v2", weth(), usdc(), 0.3),
+            swap("pool2", "uniswap_v3", weth(), usdc(), 0.2),
+            swap("pool1", "uniswap_v2", weth(), usdc(), 0f64),
+        ];
+
+        let optimized = optimize_solution(&solution(swaps.clone()));
+
+        // pool1 swaps 80% of the WETH and becomes the remainder
+        assert_eq!(optimized.swaps, vec![swaps[1].clone(), swaps[2].clone()]);
+    }
+
+    #[test]
+    fn test_remainder_swap_placed_last() {
+        let swaps = vec![
+            swap("pool1", "uniswap_v2", weth(), usdc(), 0f64),
+            swap("pool2", "uniswap_v3", weth(), usdc(), 0.4),
+        ];
+
+        let optimized = optimize_solution(&solution(swaps.clone()));
+
+        assert_eq!(optimized.swaps[0], swaps[1]);
+        assert_eq!(optimized.swaps[1], swaps[0]);
+    }
+
+    #[test]
+    fn test_zero_size_swaps_dropped() {
+        // The remainder of WETH is too small to be encoded, so the WETH -> WBTC -> USDC branch is
+        // dropped
+        let swaps = vec![
+            swap("pool1", "uniswap_v2", weth(), usdc(), 0.5),
+            swap("pool2", "uniswap_v3", weth(), usdc(), 0.49999999999),
+            swap("pool3", "uniswap_v2", weth(), wbtc(), 0f64),
+            swap("pool4", "uniswap_v2", wbtc(), usdc(), 0f64),
+        ];
+
+        let optimized = optimize_solution(&solution(swaps.clone()));
+
+        assert_eq!(
+            optimized.swaps,
+            vec![swaps[0].clone(), Swap { split: 0f64, ..swaps[1].clone() }]
+        );
+    }
+
+    #[test]
+    fn test_absolute_amounts_unchanged() {
+        let swaps = vec![
+            swap("pool1", "uniswap_v2", weth(), usdc(), 0f64)
+                .with_amount_in(num_bigint::BigUint::from(100u32)),
+            swap("pool2", "uniswap_v3", weth(), usdc(), 0f64),
+        ];
+
+        let optimized = optimize_solution(&solution(swaps.clone()));
+
+        assert_eq!(optimized.swaps, swaps);
+    }
+}
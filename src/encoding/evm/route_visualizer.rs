@@ -0,0 +1,454 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use tycho_common::Bytes;
+
+use crate::encoding::{
+    errors::EncodingError,
+    evm::{
+        group_swaps::group_swaps,
+        strategy_encoder::transfer_optimizations::TransferOptimization,
+        tycho_encoders::{get_swap_strategy, SwapStrategy},
+    },
+    models::{Chain, NativeAction, Solution, Swap, TransferType},
+};
+
+/// Output formats of a rendered route graph.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dot" => Ok(GraphFormat::Dot),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            _ => Err(format!("Unknown graph format: {s}. Expected one of: dot, mermaid")),
+        }
+    }
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphFormat::Dot => write!(f, "dot"),
+            GraphFormat::Mermaid => write!(f, "mermaid"),
+        }
+    }
+}
+
+/// Renders solutions as token graphs, to debug their routes.
+///
+/// Each token of the solution is a node and each swap an edge, labelled with its protocol system,
+/// component id, split and the `TransferType` the TychoRouter would use for it.
+///
+/// # Fields
+/// * `transfer_optimization`: TransferOptimization, computes the transfer types of the swaps
+/// * `native_address`: Address of the chain's native token
+/// * `wrapped_address`: Address of the chain's wrapped native token
+pub struct RouteVisualizer {
+    transfer_optimization: TransferOptimization,
+    native_address: Bytes,
+    wrapped_address: Bytes,
+}
+
+impl RouteVisualizer {
+    /// Creates a visualizer for the TychoRouter configuration used to encode the solutions:
+    /// `permit2` is whether a swapper private key signs Permit2 approvals.
+    pub fn new(
+        chain: Chain,
+        permit2: bool,
+        token_in_already_in_router: bool,
+    ) -> Result<Self, EncodingError> {
+        let native_address = chain.native_token()?;
+        let wrapped_address = chain.wrapped_token()?;
+        Ok(RouteVisualizer {
+            transfer_optimization: TransferOptimization::new(
+                native_address.clone(),
+                wrapped_address.clone(),
+                permit2,
+                token_in_already_in_router,
+                // The swap receivers are not rendered, so the router address is not needed
+                Bytes::default(),
+            ),
+            native_address,
+            wrapped_address,
+        })
+    }
+
+    /// Renders the swaps of the solution in the given format.
+    pub fn render(
+        &self,
+        solution: &Solution,
+        format: GraphFormat,
+    ) -> Result<String, EncodingError> {
+        if solution.swaps.is_empty() {
+            return Err(EncodingError::InvalidInput("No swaps found in solution".to_string()));
+        }
+        let transfer_types = self.get_transfer_types(solution)?;
+
+        // Nodes are numbered in order of appearance
+        let mut tokens: Vec<&Bytes> = vec![];
+        for swap in solution.swaps.iter() {
+            for token in [&swap.token_in, &swap.token_out] {
+                if !tokens.contains(&token) {
+                    tokens.push(token);
+                }
+            }
+        }
+        let node_ids: HashMap<&Bytes, usize> = tokens
+            .iter()
+            .enumerate()
+            .map(|(i, token)| (*token, i))
+            .collect();
+
+        let mut lines = vec![];
+        match format {
+            GraphFormat::Dot => {
+                lines.push("digraph route {".to_string());
+                lines.push("    rankdir=LR;".to_string());
+                for (i, token) in tokens.iter().enumerate() {
+                    let label = escape_dot(&self.get_token_label(token, solution));
+                    lines.push(format!("    t{i} [label=\"{label}\"];"));
+                }
+                for (swap, transfer_type) in solution
+                    .swaps
+                    .iter()
+                    .zip(&transfer_types)
+                {
+                    let label = get_swap_label(swap, transfer_type)
+                        .iter()
+                        .map(|line| escape_dot(line))
+                        .collect::<Vec<_>>()
+                        .join("\\n");
+                    lines.push(format!(
+                        "    t{} -> t{} [label=\"{label}\"];",
+                        node_ids[&swap.token_in], node_ids[&swap.token_out]
+                    ));
+                }
+                lines.push("}".to_string());
+            }
+            GraphFormat::Mermaid => {
+                lines.push("graph LR".to_string());
+                for (i, token) in tokens.iter().enumerate() {
+                    let label = escape_mermaid(&self.get_token_label(token, solution));
+                    lines.push(format!("    t{i}[\"{label}\"]"));
+                }
+                for (swap, transfer_type) in solution
+                    .swaps
+                    .iter()
+                    .zip(&transfer_types)
+                {
+                    let label = get_swap_label(swap, transfer_type)
+                        .iter()
+                        .map(|line| escape_mermaid(line))
+                        .collect::<Vec<_>>()
+                        .join("<br/>");
+                    lines.push(format!(
+                        "    t{} -->|\"{label}\"| t{}",
+                        node_ids[&swap.token_in], node_ids[&swap.token_out]
+                    ));
+                }
+            }
+        }
+        Ok(lines.join("\n") + "\n")
+    }
+
+    /// Returns the transfer type of each swap of the solution, as computed by the strategy which
+    /// would encode it. Swaps grouped with the previous swap have no transfer type.
+    fn get_transfer_types(
+        &self,
+        solution: &Solution,
+    ) -> Result<Vec<Option<TransferType>>, EncodingError> {
+        let strategy = get_swap_strategy(solution);
+        let mut transfer_optimization = self.transfer_optimization.clone();
+        if strategy == SwapStrategy::Split {
+            // The split swap strategy of the TychoRouterEncoder doesn't use Permit2
+            transfer_optimization.set_permit2(false);
+        }
+        let wrap = matches!(solution.native_action, Some(NativeAction::Wrap));
+        let input_tokens: Vec<&Bytes> = std::iter::once(&solution.given_token)
+            .chain(
+                solution
+                    .additional_inputs
+                    .iter()
+                    .map(|input| &input.token),
+            )
+            .collect();
+
+        let grouped_swaps = group_swaps(solution.swaps.clone());
+        let mut transfer_types = vec![];
+        let mut in_between_swap_optimization_allowed = true;
+        for (i, grouped_swap) in grouped_swaps.iter().enumerate() {
+            let transfer_type = match strategy {
+                SwapStrategy::Single => transfer_optimization.get_transfer_type(
+                    grouped_swap.clone(),
+                    solution.given_token.clone(),
+                    wrap,
+                    false,
+                ),
+                SwapStrategy::Sequential => {
+                    let transfer_type = transfer_optimization.get_transfer_type(
+                        grouped_swap.clone(),
+                        solution.given_token.clone(),
+                        wrap,
+                        in_between_swap_optimization_allowed,
+                    );
+                    (_, in_between_swap_optimization_allowed) = transfer_optimization
+                        .get_receiver(solution.receiver.clone(), grouped_swaps.get(i + 1))?;
+                    transfer_type
+                }
+                SwapStrategy::Split => {
+                    let swap_given_token = if input_tokens.contains(&&grouped_swap.token_in) {
+                        grouped_swap.token_in.clone()
+                    } else {
+                        solution.given_token.clone()
+                    };
+                    transfer_optimization.get_transfer_type(
+                        grouped_swap.clone(),
+                        swap_given_token,
+                        wrap,
+                        false,
+                    )
+                }
+            };
+            transfer_types.push(Some(transfer_type));
+            transfer_types.extend(
+                grouped_swap
+                    .swaps
+                    .iter()
+                    .skip(1)
+                    .map(|_| None),
+            );
+        }
+        Ok(transfer_types)
+    }
+
+    fn get_token_label(&self, token: &Bytes, solution: &Solution) -> String {
+        let address = format!("0x{}", hex::encode(token));
+        let mut roles = vec![];
+        if *token == solution.given_token {
+            roles.push("given");
+        }
+        if *token == solution.checked_token {
+            roles.push("checked");
+        }
+        if *token == self.native_address {
+            roles.push("native");
+        } else if *token == self.wrapped_address {
+            roles.push("wrapped");
+        }
+        if roles.is_empty() {
+            address
+        } else {
+            format!("{address} ({})", roles.join(", "))
+        }
+    }
+}
+
+fn get_swap_label(swap: &Swap, transfer_type: &Option<TransferType>) -> Vec<String> {
+    let amount = if let Some(amount_in) = &swap.amount_in {
+        format!("amount in: {amount_in}")
+    } else if swap.split == 0.0 {
+        "split: remainder".to_string()
+    } else {
+        format!("split: {}%", swap.split * 100.0)
+    };
+    let transfer = match transfer_type {
+        Some(transfer_type) => format!("transfer: {transfer_type:?}"),
+        None => "transfer: grouped".to_string(),
+    };
+    vec![swap.component.protocol_system.clone(), swap.component.id.clone(), amount, transfer]
+}
+
+fn escape_dot(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+}
+
+fn escape_mermaid(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use tycho_common::models::{protocol::ProtocolComponent, Chain as TychoCommonChain};
+
+    use super::*;
+
+    fn weth() -> Bytes {
+        Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap()
+    }
+
+    fn dai() -> Bytes {
+        Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap()
+    }
+
+    fn usdc() -> Bytes {
+        Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap()
+    }
+
+    fn get_visualizer() -> RouteVisualizer {
+        RouteVisualizer::new(TychoCommonChain::Ethereum.into(), false, false).unwrap()
+    }
+
+    fn swap(id: &str, protocol: &str, token_in: Bytes, token_out: Bytes, split: f64) -> Swap {
+        Swap::new(
+            ProtocolComponent {
+                id: id.to_string(),
+                protocol_system: protocol.to_string(),
+                ..Default::default()
+            },
+            token_in,
+            token_out,
+            split,
+        )
+    }
+
+    fn get_split_solution() -> Solution {
+        //         ┌──(USV2)──> DAI ───(USV2)───┐
+        // WETH ───┤                            ├──> USDC
+        //         └───────────(USV3)───────────┘
+        Solution {
+            given_token: weth(),
+            checked_token: usdc(),
+            swaps: vec![
+                swap(
+                    "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11",
+                    "uniswap_v2",
+                    weth(),
+                    dai(),
+                    0.5,
+                ),
+                swap(
+                    "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5",
+                    "uniswap_v2",
+                    dai(),
+                    usdc(),
+                    0f64,
+                ),
+                swap(
+                    "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640",
+                    "uniswap_v3",
+                    weth(),
+                    usdc(),
+                    0f64,
+                ),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let graph = get_visualizer()
+            .render(&get_split_solution(), GraphFormat::Dot)
+            .unwrap();
+
+        assert_eq!(
+            graph,
+            [
+                "digraph route {",
+                "    rankdir=LR;",
+                "    t0 [label=\"0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2 (given, wrapped)\"];",
+                "    t1 [label=\"0x6b175474e89094c44da98b954eedeac495271d0f\"];",
+                "    t2 [label=\"0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48 (checked)\"];",
+                "    t0 -> t1 [label=\"uniswap_v2\\n0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11\\nsplit: 50%\\ntransfer: TransferFromToProtocol\"];",
+                "    t1 -> t2 [label=\"uniswap_v2\\n0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5\\nsplit: remainder\\ntransfer: TransferToProtocol\"];",
+                "    t0 -> t2 [label=\"uniswap_v3\\n0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640\\nsplit: remainder\\ntransfer: TransferFromToProtocol\"];",
+                "}",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_mermaid() {
+        let graph = get_visualizer()
+            .render(&get_split_solution(), GraphFormat::Mermaid)
+            .unwrap();
+
+        assert_eq!(
+            graph,
+            [
+                "graph LR",
+                "    t0[\"0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2 (given, wrapped)\"]",
+                "    t1[\"0x6b175474e89094c44da98b954eedeac495271d0f\"]",
+                "    t2[\"0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48 (checked)\"]",
+                "    t0 -->|\"uniswap_v2<br/>0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11<br/>split: 50%<br/>transfer: TransferFromToProtocol\"| t1",
+                "    t1 -->|\"uniswap_v2<br/>0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5<br/>split: remainder<br/>transfer: TransferToProtocol\"| t2",
+                "    t0 -->|\"uniswap_v3<br/>0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640<br/>split: remainder<br/>transfer: TransferFromToProtocol\"| t2",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_sequential_transfer_types() {
+        // The second swap is optimized: the first swap sends its output directly to its pool
+        let solution = Solution {
+            given_token: weth(),
+            checked_token: usdc(),
+            swaps: vec![
+                swap(
+                    "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11",
+                    "uniswap_v2",
+                    weth(),
+                    dai(),
+                    0f64,
+                ),
+                swap(
+                    "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5",
+                    "uniswap_v2",
+                    dai(),
+                    usdc(),
+                    0f64,
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let transfer_types = get_visualizer()
+            .get_transfer_types(&solution)
+            .unwrap();
+
+        assert_eq!(
+            transfer_types,
+            vec![Some(TransferType::TransferFromToProtocol), Some(TransferType::None)]
+        );
+    }
+
+    #[test]
+    fn test_render_grouped_swaps() {
+        let solution = Solution {
+            given_token: weth(),
+            checked_token: usdc(),
+            swaps: vec![
+                swap("pool1", "uniswap_v4", weth(), dai(), 0f64),
+                swap("pool2", "uniswap_v4", dai(), usdc(), 0f64),
+            ],
+            ..Default::default()
+        };
+
+        let transfer_types = get_visualizer()
+            .get_transfer_types(&solution)
+            .unwrap();
+
+        assert_eq!(transfer_types, vec![Some(TransferType::TransferFromToProtocol), None]);
+    }
+
+    #[test]
+    fn test_graph_format_from_str() {
+        assert_eq!(GraphFormat::from_str("DOT").unwrap(), GraphFormat::Dot);
+        assert_eq!(GraphFormat::from_str("mermaid").unwrap(), GraphFormat::Mermaid);
+        assert!(GraphFormat::from_str("svg").is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use alloy::{
+    providers::{Provider, RootProvider},
+    rpc::types::{TransactionInput, TransactionRequest},
+    transports::BoxTransport,
+};
+use alloy_primitives::{Address, Bytes, TxKind};
+use alloy_sol_types::SolValue;
+use tokio::{
+    runtime::{Handle, Runtime},
+    task::block_in_place,
+};
+
+use crate::encoding::{
+    errors::EncodingError,
+    evm::utils::{encode_input, get_client, get_runtime},
+};
+
+/// Verifies on-chain that a Tycho router can execute the swaps encoded for it: that it isn't
+/// paused and that it approves the executors of the swaps.
+pub struct RouterVerifier {
+    client: Arc<RootProvider<BoxTransport>>,
+    runtime_handle: Handle,
+    // Store the runtime to prevent it from being dropped before use.
+    // This is required since tycho-execution does not have a pre-existing runtime.
+    // However, if the library is used in a context where a runtime already exists, it is not
+    // necessary to store it.
+    #[allow(dead_code)]
+    runtime: Option<Arc<Runtime>>,
+}
+
+impl RouterVerifier {
+    pub fn new() -> Result<Self, EncodingError> {
+        let (handle, runtime) = get_runtime()?;
+        let client = block_in_place(|| handle.block_on(get_client()))?;
+        Ok(Self { client, runtime_handle: handle, runtime })
+    }
+
+    /// Calls a view function of the router returning a boolean.
+    fn call_bool(
+        &self,
+        router: Address,
+        signature: &str,
+        args: Vec<u8>,
+    ) -> Result<bool, EncodingError> {
+        let tx = TransactionRequest {
+            to: Some(TxKind::from(router)),
+            input: TransactionInput {
+                input: Some(Bytes::from(encode_input(signature, args))),
+                data: None,
+            },
+            ..Default::default()
+        };
+
+        let output = block_in_place(|| {
+            self.runtime_handle
+                .block_on(async { self.client.call(&tx).await })
+        });
+        match output {
+            Ok(response) => bool::abi_decode(&response, true).map_err(|_| {
+                EncodingError::FatalError(format!(
+                    "Failed to decode response for {} of router {}",
+                    signature, router
+                ))
+            }),
+            Err(err) => Err(EncodingError::RecoverableError(format!(
+                "Call to {} of router {} failed with error: {:?}",
+                signature, router, err
+            ))),
+        }
+    }
+
+    /// Returns true if the router is paused.
+    pub fn is_paused(&self, router: Address) -> Result<bool, EncodingError> {
+        self.call_bool(router, "paused()", vec![])
+    }
+
+    /// Returns true if the router approves the given executor, i.e. if it was registered with
+    /// `setExecutors`.
+    pub fn is_executor_approved(
+        &self,
+        router: Address,
+        executor: Address,
+    ) -> Result<bool, EncodingError> {
+        self.call_bool(router, "executors(address)", executor.abi_encode())
+    }
+
+    /// Verifies that the router isn't paused and approves the executor of each protocol.
+    ///
+    /// All the executors which aren't approved are reported in a single error, since the swaps
+    /// calling them would revert with `Dispatcher__UnapprovedExecutor`.
+    pub fn verify(
+        &self,
+        router: Address,
+        executors: &HashMap<String, String>,
+    ) -> Result<(), EncodingError> {
+        if self.is_paused(router)? {
+            return Err(EncodingError::RecoverableError(format!(
+                "The Tycho router {} is paused",
+                router
+            )));
+        }
+
+        let mut protocols: Vec<&String> = executors.keys().collect();
+        protocols.sort();
+        let mut unapproved_executors = Vec::new();
+        for protocol in protocols {
+            let executor = Address::from_str(&executors[protocol]).map_err(|_| {
+                EncodingError::FatalError(format!(
+                    "Invalid executor address of {}: {}",
+                    protocol, executors[protocol]
+                ))
+            })?;
+            if !self.is_executor_approved(router, executor)? {
+                unapproved_executors.push(format!("{} ({})", executor, protocol));
+            }
+        }
+        if !unapproved_executors.is_empty() {
+            return Err(EncodingError::FatalError(format!(
+                "The Tycho router {} doesn't approve the executors {}. Register them with \
+                 setExecutors, or the swaps using them will revert with \
+                 Dispatcher__UnapprovedExecutor",
+                router,
+                unapproved_executors.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use alloy_primitives::keccak256;
+
+    use super::*;
+
+    fn router_address() -> Address {
+        Address::from_str("0xabA2fC41e2dB95E77C6799D0F580034395FF2B9E").unwrap()
+    }
+
+    /// These tests call the Tycho router deployed on Ethereum. They require an Anvil fork, so
+    /// please run `anvil --fork-url <RPC-URL>` and set up the following env var:
+    /// RPC_URL=127.0.0.1:8545
+    #[test]
+    #[cfg_attr(not(feature = "fork-tests"), ignore)]
+    fn test_is_paused() {
+        let verifier = RouterVerifier::new().unwrap();
+
+        assert!(!verifier
+            .is_paused(router_address())
+            .unwrap());
+    }
+
+    #[test]
+    #[cfg_attr(not(feature = "fork-tests"), ignore)]
+    fn test_verify_unapproved_executor() {
+        let verifier = RouterVerifier::new().unwrap();
+        // The executor address of the test deployments, which isn't approved by the router
+        let executors = HashMap::from([(
+            "uniswap_v2".to_string(),
+            "0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f".to_string(),
+        )]);
+
+        let result = verifier.verify(router_address(), &executors);
+
+        assert_eq!(
+            result.unwrap_err(),
+            EncodingError::FatalError(
+                "The Tycho router 0xabA2fC41e2dB95E77C6799D0F580034395FF2B9E doesn't approve the \
+                 executors 0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f (uniswap_v2). Register \
+                 them with setExecutors, or the swaps using them will revert with \
+                 Dispatcher__UnapprovedExecutor"
+                    .to_string()
+            )
+        );
+    }
+
+    /// First account of Anvil, which deploys the router and is unlocked to send the admin
+    /// transactions.
+    const ANVIL_ACCOUNT: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const ANVIL_PRIVATE_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    /// Deploys a Tycho router with the deployment script of the foundry directory and returns its
+    /// address.
+    fn deploy_router() -> Address {
+        let output = Command::new("npx")
+            .args(["hardhat", "run", "scripts/deploy-router.js", "--network", "anvil"])
+            .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/foundry"))
+            .env("PRIVATE_KEY", ANVIL_PRIVATE_KEY)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(output.status.success(), "Failed to deploy the router: {}", stdout);
+        let address = stdout
+            .lines()
+            .find_map(|line| line.strip_prefix("TychoRouter deployed to: "))
+            .unwrap();
+        Address::from_str(address.trim()).unwrap()
+    }
+
+    /// Sends a transaction calling the router from the Anvil account, and waits for it to succeed.
+    fn send(verifier: &RouterVerifier, router: Address, signature: &str, args: Vec<u8>) {
+        let tx = TransactionRequest {
+            from: Some(Address::from_str(ANVIL_ACCOUNT).unwrap()),
+            to: Some(TxKind::from(router)),
+            input: TransactionInput {
+                input: Some(Bytes::from(encode_input(signature, args))),
+                data: None,
+            },
+            ..Default::default()
+        };
+        let receipt = block_in_place(|| {
+            verifier.runtime_handle.block_on(async {
+                verifier
+                    .client
+                    .send_transaction(tx)
+                    .await
+                    .unwrap()
+                    .get_receipt()
+                    .await
+                    .unwrap()
+            })
+        });
+        assert!(receipt.status(), "Transaction calling {} reverted", signature);
+    }
+
+    /// This test deploys a Tycho router on a plain Anvil instance. It requires the foundry
+    /// dependencies (`npm install`), so please run `anvil` and set up the following env var:
+    /// RPC_URL=http://127.0.0.1:8545
+    #[test]
+    #[cfg_attr(not(feature = "anvil-tests"), ignore)]
+    fn test_verify_local_deployment() {
+        let verifier = RouterVerifier::new().unwrap();
+        let router = deploy_router();
+        let account = Address::from_str(ANVIL_ACCOUNT).unwrap();
+        let approved_executor =
+            Address::from_str("0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f").unwrap();
+        for role in ["EXECUTOR_SETTER_ROLE", "PAUSER_ROLE"] {
+            send(
+                &verifier,
+                router,
+                "batchGrantRole(bytes32,address[])",
+                (keccak256(role), vec![account]).abi_encode(),
+            );
+        }
+        send(&verifier, router, "setExecutors(address[])", (vec![approved_executor],).abi_encode());
+        let approved_executors = HashMap::from([(
+            "uniswap_v2".to_string(),
+            "0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f".to_string(),
+        )]);
+        let executors = HashMap::from([
+            ("uniswap_v2".to_string(), "0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f".to_string()),
+            ("uniswap_v3".to_string(), "0x2e234DAe75C793f67A35089C9d99245E1C58470b".to_string()),
+        ]);
+
+        let approved_result = verifier.verify(router, &approved_executors);
+        let unapproved_result = verifier.verify(router, &executors);
+        send(&verifier, router, "pause()", vec![]);
+        let paused_result = verifier.verify(router, &approved_executors);
+
+        assert!(approved_result.is_ok());
+        assert_eq!(
+            unapproved_result.unwrap_err(),
+            EncodingError::FatalError(format!(
+                "The Tycho router {} doesn't approve the executors \
+                 0x2e234DAe75C793f67A35089C9d99245E1C58470b (uniswap_v3). Register them with \
+                 setExecutors, or the swaps using them will revert with \
+                 Dispatcher__UnapprovedExecutor",
+                router
+            ))
+        );
+        assert_eq!(
+            paused_result.unwrap_err(),
+            EncodingError::RecoverableError(format!("The Tycho router {} is paused", router))
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use alloy_sol_types::SolValue;
+use tycho_common::Bytes;
+
+use crate::encoding::{
+    errors::EncodingError,
+    evm::utils::{
+        biguint_to_u256, bytes_to_address, encode_input, get_min_amount_for_solution, ple_decode,
+        ple_encode,
+    },
+    models::{ActionRecipient, NativeAction, Solution},
+};
+
+/// Action types of the router's `ActionDispatcher`. Mirrors the `ActionDispatcher.ActionType`
+/// enum.
+const WRAP_ACTION: u8 = 0;
+const UNWRAP_ACTION: u8 = 1;
+const VAULT_DEPOSIT_ACTION: u8 = 2;
+const DISTRIBUTE_ACTION: u8 = 3;
+const CALL_ACTION: u8 = 4;
+
+/// Returns true if the router needs to execute actions around the swaps of the solution.
+pub fn has_swap_actions(solution: &Solution) -> bool {
+    !solution.pre_swap_actions.is_empty() || !solution.post_swap_actions.is_empty()
+}
+
+/// Encodes actions as expected by the router's `ActionDispatcher`: a PLE encoded array where each
+/// action is its type (1 byte) followed by its parameters.
+pub fn encode_actions(actions: &[NativeAction]) -> Result<Vec<u8>, EncodingError> {
+    let mut encoded_actions = vec![];
+    for action in actions {
+        let encoded = match action {
+            NativeAction::Wrap => vec![WRAP_ACTION],
+            NativeAction::Unwrap => vec![UNWRAP_ACTION],
+            NativeAction::VaultDeposit { vault } => {
+                (VAULT_DEPOSIT_ACTION, bytes_to_address(vault)?).abi_encode_packed()
+            }
+            NativeAction::Distribute { recipients } => {
+                let mut encoded = vec![DISTRIBUTE_ACTION];
+                for recipient in recipients {
+                    encoded.extend(
+                        (bytes_to_address(&recipient.recipient)?, recipient.bps)
+                            .abi_encode_packed(),
+                    );
+                }
+                encoded
+            }
+            NativeAction::Call { target, calldata } => {
+                let mut encoded = (CALL_ACTION, bytes_to_address(target)?).abi_encode_packed();
+                encoded.extend(calldata.to_vec());
+                encoded
+            }
+        };
+        encoded_actions.push(encoded);
+    }
+    Ok(ple_encode(encoded_actions))
+}
+
+/// Decodes actions encoded by [`encode_actions`].
+pub fn decode_actions(encoded: &[u8]) -> Result<Vec<NativeAction>, EncodingError> {
+    let invalid_action =
+        |reason: &str| EncodingError::InvalidInput(format!("Invalid action: {reason}"));
+    let mut actions = vec![];
+    for encoded_action in ple_decode(encoded)? {
+        let (action_type, params) = encoded_action
+            .split_first()
+            .ok_or_else(|| invalid_action("empty action"))?;
+        let action = match *action_type {
+            WRAP_ACTION if params.is_empty() => NativeAction::Wrap,
+            UNWRAP_ACTION if params.is_empty() => NativeAction::Unwrap,
+            VAULT_DEPOSIT_ACTION if params.len() == 20 => {
+                NativeAction::VaultDeposit { vault: Bytes::from(params.to_vec()) }
+            }
+            DISTRIBUTE_ACTION if !params.is_empty() && params.len() % 22 == 0 => {
+                NativeAction::Distribute {
+                    recipients: params
+                        .chunks(22)
+                        .map(|entry| {
+                            ActionRecipient::new(
+                                Bytes::from(entry[..20].to_vec()),
+                                u16::from_be_bytes([entry[20], entry[21]]),
+                            )
+                        })
+                        .collect(),
+                }
+            }
+            CALL_ACTION if params.len() >= 20 => NativeAction::Call {
+                target: Bytes::from(params[..20].to_vec()),
+                calldata: Bytes::from(params[20..].to_vec()),
+            },
+            WRAP_ACTION | UNWRAP_ACTION | VAULT_DEPOSIT_ACTION | DISTRIBUTE_ACTION |
+            CALL_ACTION => return Err(invalid_action("unexpected parameters length")),
+            action_type => {
+                return Err(invalid_action(&format!("unknown action type {action_type}")))
+            }
+        };
+        actions.push(action);
+    }
+    Ok(actions)
+}
+
+/// Returns the part of a solution with swap actions that is encoded by the swap strategies: its
+/// swaps start from the output of the pre-swap actions and the router keeps their output, to
+/// execute the post-swap actions on it.
+pub fn get_swap_solution(
+    solution: &Solution,
+    router_address: &Bytes,
+    native_address: &Bytes,
+    wrapped_address: &Bytes,
+) -> Result<Solution, EncodingError> {
+    let mut given_token = solution.given_token.clone();
+    for action in solution.pre_swap_actions.iter() {
+        given_token = action
+            .output_token(&given_token, native_address, wrapped_address)?
+            .ok_or_else(|| {
+                EncodingError::InvalidInput(
+                    "Pre-swap actions must keep the funds in the router".to_string(),
+                )
+            })?;
+    }
+    Ok(Solution {
+        given_token,
+        receiver: router_address.clone(),
+        pre_swap_actions: vec![],
+        post_swap_actions: vec![],
+        ..solution.clone()
+    })
+}
+
+/// Wraps the calldata of a swap call into a `swapWithActions` call, which executes the solution's
+/// actions around it.
+///
+/// The router checks the output of the post-swap actions against the solution's
+/// `post_swap_min_amount`, or against the minimum amount out of the swaps if it isn't set.
+pub fn encode_swap_with_actions(
+    solution: &Solution,
+    swap_call: Vec<u8>,
+) -> Result<Vec<u8>, EncodingError> {
+    let min_amount_out = solution
+        .post_swap_min_amount
+        .clone()
+        .unwrap_or_else(|| get_min_amount_for_solution(solution.clone()));
+    let args = (
+        biguint_to_u256(&solution.given_amount),
+        bytes_to_address(&solution.given_token)?,
+        bytes_to_address(&solution.checked_token)?,
+        biguint_to_u256(&min_amount_out),
+        bytes_to_address(&solution.receiver)?,
+        encode_actions(&solution.pre_swap_actions)?,
+        swap_call,
+        encode_actions(&solution.post_swap_actions)?,
+    );
+    Ok(encode_input(
+        "swapWithActions(uint256,address,address,uint256,address,bytes,bytes,bytes)",
+        args.abi_encode(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use alloy::hex::encode;
+
+    use super::*;
+
+    fn eth() -> Bytes {
+        Bytes::from_str("0x0000000000000000000000000000000000000000").unwrap()
+    }
+
+    fn weth() -> Bytes {
+        Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap()
+    }
+
+    fn router() -> Bytes {
+        Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap()
+    }
+
+    #[test]
+    fn test_encode_actions() {
+        let actions = vec![
+            NativeAction::VaultDeposit {
+                vault: Bytes::from_str("0x83F20F44975D03b1b09e64809B757c47f942BEeA").unwrap(),
+            },
+            NativeAction::Distribute {
+                recipients: vec![
+                    ActionRecipient::new(
+                        Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                        6000,
+                    ),
+                    ActionRecipient::new(
+                        Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                        4000,
+                    ),
+                ],
+            },
+        ];
+
+        let encoded = encode_actions(&actions).unwrap();
+
+        assert_eq!(
+            encode(encoded),
+            String::from(concat!(
+                // length of the vault deposit action
+                "0015",
+                // vault deposit action type
+                "02",
+                // vault
+                "83f20f44975d03b1b09e64809b757c47f942beea",
+                // length of the distribute action
+                "002d",
+                // distribute action type
+                "03",
+                // first recipient
+                "1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e",
+                // first recipient's share (6000 bps)
+                "1770",
+                // second recipient
+                "cd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2",
+                // second recipient's share (4000 bps)
+                "0fa0",
+            ))
+        );
+    }
+
+    #[test]
+    fn test_decode_actions() {
+        let actions = vec![
+            NativeAction::Unwrap,
+            NativeAction::Distribute {
+                recipients: vec![
+                    ActionRecipient::new(
+                        Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                        2500,
+                    ),
+                    ActionRecipient::new(
+                        Bytes::from_str("0xcd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2").unwrap(),
+                        7500,
+                    ),
+                ],
+            },
+        ];
+
+        let decoded = decode_actions(&encode_actions(&actions).unwrap()).unwrap();
+
+        assert_eq!(decoded, actions);
+    }
+
+    #[test]
+    fn test_decode_actions_unknown_type() {
+        let result = decode_actions(&ple_encode(vec![vec![7]]));
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_encode_call_action() {
+        let actions = vec![NativeAction::Call {
+            target: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+            calldata: Bytes::from_str("0x12345678").unwrap(),
+        }];
+
+        let encoded = encode_actions(&actions).unwrap();
+
+        assert_eq!(
+            encode(encoded),
+            String::from(concat!(
+                // length of the call action
+                "0019",
+                // call action type
+                "04",
+                // target
+                "1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e",
+                // calldata
+                "12345678",
+            ))
+        );
+    }
+
+    #[test]
+    fn test_get_swap_solution() {
+        let solution = Solution {
+            given_token: eth(),
+            receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            pre_swap_actions: vec![NativeAction::Wrap],
+            post_swap_actions: vec![NativeAction::Unwrap],
+            ..Default::default()
+        };
+
+        let swap_solution = get_swap_solution(&solution, &router(), &eth(), &weth()).unwrap();
+
+        assert_eq!(swap_solution.given_token, weth());
+        assert_eq!(swap_solution.receiver, router());
+        assert!(swap_solution
+            .pre_swap_actions
+            .is_empty());
+        assert!(swap_solution
+            .post_swap_actions
+            .is_empty());
+    }
+
+    #[test]
+    fn test_get_swap_solution_terminal_pre_swap_action() {
+        let solution = Solution {
+            given_token: weth(),
+            pre_swap_actions: vec![NativeAction::Distribute { recipients: vec![] }],
+            ..Default::default()
+        };
+
+        let result = get_swap_solution(&solution, &router(), &eth(), &weth());
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+}
@@ -1,4 +1,6 @@
+pub(crate) mod actions;
+mod settlement;
 pub mod strategy_encoders;
-mod strategy_validators;
+pub(crate) mod strategy_validators;
 
-mod transfer_optimizations;
+pub(crate) mod transfer_optimizations;
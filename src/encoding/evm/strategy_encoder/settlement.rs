@@ -0,0 +1,133 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+
+use crate::encoding::{
+    errors::EncodingError,
+    evm::utils::{biguint_to_u256, bytes_to_address},
+    models::Solution,
+};
+
+sol! {
+    /// Describes how the router distributes the output of a swap. Mirrors the
+    /// `TychoRouter.OutputSettlement` struct.
+    #[derive(Debug)]
+    struct OutputSettlement {
+        uint16 feeBps;
+        address feeRecipient;
+        uint256 expectedAmountOut;
+        address surplusRecipient;
+        uint16 surplusShareBps;
+    }
+}
+
+/// Returns the output settlement of a solution, or `None` if the output can be sent directly to
+/// the receiver by the last swap.
+pub fn get_output_settlement(
+    solution: &Solution,
+) -> Result<Option<OutputSettlement>, EncodingError> {
+    if solution.fee.is_none() && solution.surplus.is_none() {
+        return Ok(None);
+    }
+
+    let (fee_bps, fee_recipient) = match &solution.fee {
+        Some(fee) => (fee.bps, bytes_to_address(&fee.recipient)?),
+        None => (0, Address::ZERO),
+    };
+    let (expected_amount_out, surplus_recipient, surplus_share_bps) = match &solution.surplus {
+        Some(surplus) => {
+            let expected_amount = solution
+                .expected_amount
+                .as_ref()
+                .ok_or_else(|| {
+                    EncodingError::InvalidInput(
+                        "Expected amount must be provided to capture surplus".to_string(),
+                    )
+                })?;
+            (
+                biguint_to_u256(expected_amount),
+                bytes_to_address(surplus.recipient())?,
+                surplus.share_bps(),
+            )
+        }
+        None => (U256::ZERO, Address::ZERO, 0),
+    };
+
+    Ok(Some(OutputSettlement {
+        feeBps: fee_bps,
+        feeRecipient: fee_recipient,
+        expectedAmountOut: expected_amount_out,
+        surplusRecipient: surplus_recipient,
+        surplusShareBps: surplus_share_bps,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::BigUint;
+    use tycho_common::Bytes;
+
+    use super::*;
+    use crate::encoding::models::{IntegratorFee, SurplusPolicy};
+
+    fn recipient() -> Bytes {
+        Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap()
+    }
+
+    #[test]
+    fn test_no_settlement() {
+        let solution = Solution::default();
+        assert!(get_output_settlement(&solution)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_fee_and_surplus_cap_settlement() {
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            fee: Some(IntegratorFee::new(25, recipient())),
+            surplus: Some(SurplusPolicy::Cap { recipient: recipient() }),
+            ..Default::default()
+        };
+        let settlement = get_output_settlement(&solution)
+            .unwrap()
+            .unwrap();
+        let recipient_address = bytes_to_address(&recipient()).unwrap();
+        assert_eq!(settlement.feeBps, 25);
+        assert_eq!(settlement.feeRecipient, recipient_address);
+        assert_eq!(settlement.expectedAmountOut, U256::from(1000));
+        assert_eq!(settlement.surplusRecipient, recipient_address);
+        assert_eq!(settlement.surplusShareBps, 10_000);
+    }
+
+    #[test]
+    fn test_surplus_share_settlement() {
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            surplus: Some(SurplusPolicy::Share { recipient: recipient(), bps: 5_000 }),
+            ..Default::default()
+        };
+        let settlement = get_output_settlement(&solution)
+            .unwrap()
+            .unwrap();
+        assert_eq!(settlement.feeBps, 0);
+        assert_eq!(settlement.feeRecipient, Address::ZERO);
+        assert_eq!(settlement.surplusShareBps, 5_000);
+    }
+
+    #[test]
+    fn test_surplus_without_expected_amount() {
+        let solution = Solution {
+            surplus: Some(SurplusPolicy::Cap { recipient: recipient() }),
+            ..Default::default()
+        };
+        assert_eq!(
+            get_output_settlement(&solution).unwrap_err(),
+            EncodingError::InvalidInput(
+                "Expected amount must be provided to capture surplus".to_string()
+            )
+        );
+    }
+}
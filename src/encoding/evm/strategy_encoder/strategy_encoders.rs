@@ -1,6 +1,6 @@
 use std::{collections::HashSet, str::FromStr};
 
-use alloy_primitives::{aliases::U24, U256, U8};
+use alloy_primitives::{aliases::U24, PrimitiveSignature as Signature, U256, U8};
 use alloy_sol_types::SolValue;
 use tycho_common::Bytes;
 
@@ -10,13 +10,14 @@ use crate::encoding::{
         approvals::permit2::Permit2,
         group_swaps::group_swaps,
         strategy_encoder::{
+            settlement::get_output_settlement,
             strategy_validators::{SequentialSwapValidator, SplitSwapValidator, SwapValidator},
             transfer_optimizations::TransferOptimization,
         },
         swap_encoder::swap_encoder_registry::SwapEncoderRegistry,
         utils::{
             biguint_to_u256, bytes_to_address, encode_input, get_min_amount_for_solution,
-            get_token_position, percentage_to_uint24, ple_encode,
+            get_token_position, get_wrap_unwrap, percentage_to_uint24, ple_encode,
         },
     },
     models::{Chain, EncodingContext, NativeAction, Solution},
@@ -24,6 +25,30 @@ use crate::encoding::{
     swap_encoder::SwapEncoder,
 };
 
+/// ABI-encodes the arguments of a swap entry point of the router: the `args` shared by all its
+/// variants, followed by the output settlement and the Permit2 approval if they're given, and the
+/// encoded swaps.
+///
+/// `args` must only hold static types, so that it's encoded in place like the arguments it's made
+/// of.
+fn encode_swap_arguments<A: SolValue, S: SolValue, P: SolValue>(
+    args: A,
+    settlement: Option<S>,
+    permit: Option<(P, Signature)>,
+    swaps: Vec<u8>,
+) -> Vec<u8> {
+    match (permit, settlement) {
+        (Some((permit, signature)), Some(settlement)) => {
+            (args, settlement, permit, signature.as_bytes().to_vec(), swaps).abi_encode()
+        }
+        (Some((permit, signature)), None) => {
+            (args, permit, signature.as_bytes().to_vec(), swaps).abi_encode()
+        }
+        (None, Some(settlement)) => (args, settlement, swaps).abi_encode(),
+        (None, None) => (args, swaps).abi_encode(),
+    }
+}
+
 /// Represents the encoder for a swap strategy which supports single swaps.
 ///
 /// # Fields
@@ -31,6 +56,8 @@ use crate::encoding::{
 /// * `permit2`: Permit2, responsible for managing permit2 operations and providing necessary
 ///   signatures and permit2 objects for calling the router
 /// * `selector`: String, the selector for the swap function in the router contract
+/// * `settlement_selector`: String, the selector for the swap function in the router contract used
+///   when the router needs to distribute the output (e.g. to charge a fee)
 /// * `router_address`: Address of the router to be used to execute swaps
 /// * `transfer_optimization`: TransferOptimization, responsible for optimizing the token transfers
 #[derive(Clone)]
@@ -38,6 +65,7 @@ pub struct SingleSwapStrategyEncoder {
     swap_encoder_registry: SwapEncoderRegistry,
     permit2: Option<Permit2>,
     selector: String,
+    settlement_selector: String,
     router_address: Bytes,
     transfer_optimization: TransferOptimization,
 }
@@ -50,18 +78,25 @@ impl SingleSwapStrategyEncoder {
         router_address: Bytes,
         token_in_already_in_router: bool,
     ) -> Result<Self, EncodingError> {
-        let (permit2, selector) = if let Some(swapper_pk) = swapper_pk {
-            (Some(Permit2::new(swapper_pk, chain.clone())?), "singleSwapPermit2(uint256,address,address,uint256,bool,bool,address,((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string())
+        let (permit2, selector, settlement_selector) = if let Some(swapper_pk) = swapper_pk {
+            (
+                Some(Permit2::new(swapper_pk, chain.clone())?),
+                "singleSwapPermit2(uint256,address,address,uint256,bool,bool,address,((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string(),
+                "singleSwapWithSettlementPermit2(uint256,address,address,uint256,bool,bool,address,(uint16,address,uint256,address,uint16),((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string(),
+            )
         } else {
             (
                 None,
                 "singleSwap(uint256,address,address,uint256,bool,bool,address,bytes)".to_string(),
+                "singleSwapWithSettlement(uint256,address,address,uint256,bool,bool,address,(uint16,address,uint256,address,uint16),bytes)"
+                    .to_string(),
             )
         };
         let permit2_is_active = permit2.is_some();
         Ok(Self {
             permit2,
             selector,
+            settlement_selector,
             swap_encoder_registry,
             router_address: router_address.clone(),
             transfer_optimization: TransferOptimization::new(
@@ -74,6 +109,28 @@ impl SingleSwapStrategyEncoder {
         })
     }
 
+    /// Makes the strategy transfer the input tokens using Permit2 allowances without signing any
+    /// approval itself. This is needed when the approvals are granted elsewhere, e.g. with a single
+    /// batch signature for several solutions.
+    pub fn with_permit2_transfers(mut self) -> Self {
+        self.transfer_optimization
+            .set_permit2(true);
+        self
+    }
+
+    /// Makes the strategy expect the input tokens to be held by the router before the first swap,
+    /// e.g. after the router executed pre-swap actions on them.
+    pub fn with_token_in_already_in_router(mut self) -> Self {
+        self.transfer_optimization
+            .set_token_in_already_in_router(true);
+        self
+    }
+
+    /// Returns how the strategy transfers the tokens into the pools.
+    pub fn transfer_optimization(&self) -> &TransferOptimization {
+        &self.transfer_optimization
+    }
+
     /// Encodes information necessary for performing a single hop against a given executor for
     /// a protocol.
     fn encode_swap_header(&self, executor_address: Bytes, protocol_data: Vec<u8>) -> Vec<u8> {
@@ -106,14 +163,9 @@ impl StrategyEncoder for SingleSwapStrategyEncoder {
         }
 
         let min_amount_out = get_min_amount_for_solution(solution.clone());
+        let settlement = get_output_settlement(&solution)?;
 
-        let (mut unwrap, mut wrap) = (false, false);
-        if let Some(action) = solution.native_action.clone() {
-            match action {
-                NativeAction::Wrap => wrap = true,
-                NativeAction::Unwrap => unwrap = true,
-            }
-        }
+        let (wrap, unwrap) = get_wrap_unwrap(&solution.native_action)?;
         let protocol = grouped_swap.protocol_system.clone();
         let swap_encoder = self
             .get_swap_encoder(&protocol)
@@ -124,8 +176,12 @@ impl StrategyEncoder for SingleSwapStrategyEncoder {
                 ))
             })?;
 
-        let swap_receiver =
-            if !unwrap { solution.receiver.clone() } else { self.router_address.clone() };
+        // The router needs to hold the output if it unwraps or distributes it
+        let swap_receiver = if !unwrap && settlement.is_none() {
+            solution.receiver.clone()
+        } else {
+            self.router_address.clone()
+        };
 
         let transfer_type = self
             .transfer_optimization
@@ -151,27 +207,18 @@ impl StrategyEncoder for SingleSwapStrategyEncoder {
             grouped_protocol_data,
         );
 
-        let method_calldata = if let Some(permit2) = self.permit2.clone() {
-            let (permit, signature) = permit2.get_permit(
+        let is_settled = settlement.is_some();
+        let permit = if let Some(permit2) = self.permit2.clone() {
+            Some(permit2.get_permit(
                 &self.router_address,
                 &solution.sender,
                 &solution.given_token,
                 &solution.given_amount,
-            )?;
-            (
-                biguint_to_u256(&solution.given_amount),
-                bytes_to_address(&solution.given_token)?,
-                bytes_to_address(&solution.checked_token)?,
-                biguint_to_u256(&min_amount_out),
-                wrap,
-                unwrap,
-                bytes_to_address(&solution.receiver)?,
-                permit,
-                signature.as_bytes().to_vec(),
-                swap_data,
-            )
-                .abi_encode()
+            )?)
         } else {
+            None
+        };
+        let method_calldata = encode_swap_arguments(
             (
                 biguint_to_u256(&solution.given_amount),
                 bytes_to_address(&solution.given_token)?,
@@ -180,12 +227,14 @@ impl StrategyEncoder for SingleSwapStrategyEncoder {
                 wrap,
                 unwrap,
                 bytes_to_address(&solution.receiver)?,
-                swap_data,
-            )
-                .abi_encode()
-        };
+            ),
+            settlement,
+            permit,
+            swap_data,
+        );
 
-        let contract_interaction = encode_input(&self.selector, method_calldata);
+        let selector = if is_settled { &self.settlement_selector } else { &self.selector };
+        let contract_interaction = encode_input(selector, method_calldata);
         Ok((contract_interaction, self.router_address.clone()))
     }
 
@@ -206,6 +255,8 @@ impl StrategyEncoder for SingleSwapStrategyEncoder {
 /// * `permit2`: Permit2, responsible for managing permit2 operations and providing necessary
 ///   signatures and permit2 objects for calling the router
 /// * `selector`: String, the selector for the swap function in the router contract
+/// * `settlement_selector`: String, the selector for the swap function in the router contract used
+///   when the router needs to distribute the output (e.g. to charge a fee)
 /// * `native_address`: Address of the chain's native token
 /// * `wrapped_address`: Address of the chain's wrapped token
 /// * `router_address`: Address of the router to be used to execute swaps
@@ -217,6 +268,7 @@ pub struct SequentialSwapStrategyEncoder {
     swap_encoder_registry: SwapEncoderRegistry,
     permit2: Option<Permit2>,
     selector: String,
+    settlement_selector: String,
     router_address: Bytes,
     native_address: Bytes,
     wrapped_address: Bytes,
@@ -232,19 +284,26 @@ impl SequentialSwapStrategyEncoder {
         router_address: Bytes,
         token_in_already_in_router: bool,
     ) -> Result<Self, EncodingError> {
-        let (permit2, selector) = if let Some(swapper_pk) = swapper_pk {
-            (Some(Permit2::new(swapper_pk, chain.clone())?), "sequentialSwapPermit2(uint256,address,address,uint256,bool,bool,address,((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string())
+        let (permit2, selector, settlement_selector) = if let Some(swapper_pk) = swapper_pk {
+            (
+                Some(Permit2::new(swapper_pk, chain.clone())?),
+                "sequentialSwapPermit2(uint256,address,address,uint256,bool,bool,address,((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string(),
+                "sequentialSwapWithSettlementPermit2(uint256,address,address,uint256,bool,bool,address,(uint16,address,uint256,address,uint16),((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string(),
+            )
         } else {
             (
                 None,
                 "sequentialSwap(uint256,address,address,uint256,bool,bool,address,bytes)"
                     .to_string(),
+                "sequentialSwapWithSettlement(uint256,address,address,uint256,bool,bool,address,(uint16,address,uint256,address,uint16),bytes)"
+                    .to_string(),
             )
         };
         let permit2_is_active = permit2.is_some();
         Ok(Self {
             permit2,
             selector,
+            settlement_selector,
             swap_encoder_registry,
             router_address: router_address.clone(),
             native_address: chain.native_token()?,
@@ -260,6 +319,28 @@ impl SequentialSwapStrategyEncoder {
         })
     }
 
+    /// Makes the strategy transfer the input tokens using Permit2 allowances without signing any
+    /// approval itself. This is needed when the approvals are granted elsewhere, e.g. with a single
+    /// batch signature for several solutions.
+    pub fn with_permit2_transfers(mut self) -> Self {
+        self.transfer_optimization
+            .set_permit2(true);
+        self
+    }
+
+    /// Makes the strategy expect the input tokens to be held by the router before the first swap,
+    /// e.g. after the router executed pre-swap actions on them.
+    pub fn with_token_in_already_in_router(mut self) -> Self {
+        self.transfer_optimization
+            .set_token_in_already_in_router(true);
+        self
+    }
+
+    /// Returns how the strategy transfers the tokens into the pools.
+    pub fn transfer_optimization(&self) -> &TransferOptimization {
+        &self.transfer_optimization
+    }
+
     /// Encodes information necessary for performing a single hop against a given executor for
     /// a protocol.
     fn encode_swap_header(&self, executor_address: Bytes, protocol_data: Vec<u8>) -> Vec<u8> {
@@ -285,15 +366,16 @@ impl StrategyEncoder for SequentialSwapStrategyEncoder {
             )?;
 
         let min_amount_out = get_min_amount_for_solution(solution.clone());
+        let settlement = get_output_settlement(&solution)?;
+        // The router needs to hold the output if it distributes it
+        let final_receiver = if settlement.is_some() {
+            self.router_address.clone()
+        } else {
+            solution.receiver.clone()
+        };
         let grouped_swaps = group_swaps(solution.swaps);
 
-        let (mut unwrap, mut wrap) = (false, false);
-        if let Some(action) = solution.native_action.clone() {
-            match action {
-                NativeAction::Wrap => wrap = true,
-                NativeAction::Unwrap => unwrap = true,
-            }
-        }
+        let (wrap, unwrap) = get_wrap_unwrap(&solution.native_action)?;
 
         let mut swaps = vec![];
         let mut next_in_between_swap_optimization_allowed = true;
@@ -312,7 +394,7 @@ impl StrategyEncoder for SequentialSwapStrategyEncoder {
             let next_swap = grouped_swaps.get(i + 1);
             let (swap_receiver, next_swap_optimization) = self
                 .transfer_optimization
-                .get_receiver(solution.receiver.clone(), next_swap)?;
+                .get_receiver(final_receiver.clone(), next_swap)?;
             next_in_between_swap_optimization_allowed = next_swap_optimization;
             let transfer_type = self
                 .transfer_optimization
@@ -348,27 +430,18 @@ impl StrategyEncoder for SequentialSwapStrategyEncoder {
         }
 
         let encoded_swaps = ple_encode(swaps);
-        let method_calldata = if let Some(permit2) = self.permit2.clone() {
-            let (permit, signature) = permit2.get_permit(
+        let is_settled = settlement.is_some();
+        let permit = if let Some(permit2) = self.permit2.clone() {
+            Some(permit2.get_permit(
                 &self.router_address,
                 &solution.sender,
                 &solution.given_token,
                 &solution.given_amount,
-            )?;
-            (
-                biguint_to_u256(&solution.given_amount),
-                bytes_to_address(&solution.given_token)?,
-                bytes_to_address(&solution.checked_token)?,
-                biguint_to_u256(&min_amount_out),
-                wrap,
-                unwrap,
-                bytes_to_address(&solution.receiver)?,
-                permit,
-                signature.as_bytes().to_vec(),
-                encoded_swaps,
-            )
-                .abi_encode()
+            )?)
         } else {
+            None
+        };
+        let method_calldata = encode_swap_arguments(
             (
                 biguint_to_u256(&solution.given_amount),
                 bytes_to_address(&solution.given_token)?,
@@ -377,12 +450,14 @@ impl StrategyEncoder for SequentialSwapStrategyEncoder {
                 wrap,
                 unwrap,
                 bytes_to_address(&solution.receiver)?,
-                encoded_swaps,
-            )
-                .abi_encode()
-        };
+            ),
+            settlement,
+            permit,
+            encoded_swaps,
+        );
 
-        let contract_interaction = encode_input(&self.selector, method_calldata);
+        let selector = if is_settled { &self.settlement_selector } else { &self.selector };
+        let contract_interaction = encode_input(selector, method_calldata);
         Ok((contract_interaction, self.router_address.clone()))
     }
 
@@ -403,6 +478,14 @@ impl StrategyEncoder for SequentialSwapStrategyEncoder {
 /// * `permit2`: Permit2, responsible for managing permit2 operations and providing necessary
 ///   signatures and permit2 objects for calling the router
 /// * `selector`: String, the selector for the swap function in the router contract
+/// * `amounts_selector`: String, the selector for the swap function in the router contract used
+///   when any swap specifies an absolute input amount
+/// * `settlement_selector`: String, the selector for the swap function in the router contract used
+///   when the router needs to distribute the output (e.g. to charge a fee)
+/// * `multi_output_selector`: String, the selector for the swap function in the router contract
+///   used when the solution has more than one output token
+/// * `multi_input_selector`: String, the selector for the swap function in the router contract used
+///   when the solution has more than one input token
 /// * `native_address`: Address of the chain's native token
 /// * `wrapped_address`: Address of the chain's wrapped token
 /// * `split_swap_validator`: SplitSwapValidator, responsible for checking validity of split swap
@@ -414,6 +497,10 @@ pub struct SplitSwapStrategyEncoder {
     swap_encoder_registry: SwapEncoderRegistry,
     permit2: Option<Permit2>,
     selector: String,
+    amounts_selector: String,
+    settlement_selector: String,
+    multi_output_selector: String,
+    multi_input_selector: String,
     native_address: Bytes,
     wrapped_address: Bytes,
     split_swap_validator: SplitSwapValidator,
@@ -429,19 +516,45 @@ impl SplitSwapStrategyEncoder {
         router_address: Bytes,
         token_in_already_in_router: bool,
     ) -> Result<Self, EncodingError> {
-        let (permit2, selector) = if let Some(swapper_pk) = swapper_pk {
-            (Some(Permit2::new(swapper_pk, chain.clone())?), "splitSwapPermit2(uint256,address,address,uint256,bool,bool,uint256,address,((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string())
+        let (
+            permit2,
+            selector,
+            amounts_selector,
+            settlement_selector,
+            multi_output_selector,
+            multi_input_selector,
+        ) = if let Some(swapper_pk) = swapper_pk {
+            (
+                Some(Permit2::new(swapper_pk, chain.clone())?),
+                "splitSwapPermit2(uint256,address,address,uint256,bool,bool,uint256,address,((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string(),
+                "splitSwapWithAmountsPermit2(uint256,address,address,uint256,bool,bool,uint256,address,((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string(),
+                "splitSwapWithSettlementPermit2(uint256,address,address,uint256,bool,bool,uint256,address,(uint16,address,uint256,address,uint16),((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string(),
+                "splitSwapMultiOutputPermit2(uint256,address,bool,uint256,(address,uint256,address)[],((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string(),
+                "splitSwapMultiInputPermit2((address,uint256)[],address,uint256,uint256,address,((address,uint160,uint48,uint48)[],address,uint256),bytes,bytes)".to_string(),
+            )
         } else {
             (
                 None,
                 "splitSwap(uint256,address,address,uint256,bool,bool,uint256,address,bytes)"
                     .to_string(),
+                "splitSwapWithAmounts(uint256,address,address,uint256,bool,bool,uint256,address,bytes)"
+                    .to_string(),
+                "splitSwapWithSettlement(uint256,address,address,uint256,bool,bool,uint256,address,(uint16,address,uint256,address,uint16),bytes)"
+                    .to_string(),
+                "splitSwapMultiOutput(uint256,address,bool,uint256,(address,uint256,address)[],bytes)"
+                    .to_string(),
+                "splitSwapMultiInput((address,uint256)[],address,uint256,uint256,address,bytes)"
+                    .to_string(),
             )
         };
         let permit2_is_active = permit2.is_some();
         Ok(Self {
             permit2,
             selector,
+            amounts_selector,
+            settlement_selector,
+            multi_output_selector,
+            multi_input_selector,
             swap_encoder_registry,
             native_address: chain.native_token()?,
             wrapped_address: chain.wrapped_token()?,
@@ -457,13 +570,39 @@ impl SplitSwapStrategyEncoder {
         })
     }
 
+    /// Makes the strategy transfer the input tokens using Permit2 allowances without signing any
+    /// approval itself. This is needed when the approvals are granted elsewhere, e.g. with a single
+    /// batch signature for several solutions.
+    pub fn with_permit2_transfers(mut self) -> Self {
+        self.transfer_optimization
+            .set_permit2(true);
+        self
+    }
+
+    /// Makes the strategy expect the input tokens to be held by the router before the first swap,
+    /// e.g. after the router executed pre-swap actions on them.
+    pub fn with_token_in_already_in_router(mut self) -> Self {
+        self.transfer_optimization
+            .set_token_in_already_in_router(true);
+        self
+    }
+
+    /// Returns how the strategy transfers the tokens into the pools.
+    pub fn transfer_optimization(&self) -> &TransferOptimization {
+        &self.transfer_optimization
+    }
+
     /// Encodes information necessary for performing a single hop against a given executor for
     /// a protocol as part of a split swap solution.
+    ///
+    /// If `amount_in` is given, it is encoded as a 32-byte value right after the split. An amount
+    /// of 0 signals to the router to use the split percentage instead.
     fn encode_swap_header(
         &self,
         token_in: U8,
         token_out: U8,
         split: U24,
+        amount_in: Option<U256>,
         executor_address: Bytes,
         protocol_data: Vec<u8>,
     ) -> Vec<u8> {
@@ -471,10 +610,25 @@ impl SplitSwapStrategyEncoder {
         encoded.push(token_in.to_be_bytes_vec()[0]);
         encoded.push(token_out.to_be_bytes_vec()[0]);
         encoded.extend_from_slice(&split.to_be_bytes_vec());
+        if let Some(amount_in) = amount_in {
+            encoded.extend_from_slice(&amount_in.to_be_bytes_vec());
+        }
         encoded.extend(executor_address.to_vec());
         encoded.extend(protocol_data);
         encoded
     }
+
+    /// Returns the tokens sold by the solution: the given token followed by the additional inputs.
+    fn get_input_tokens(&self, solution: &Solution) -> Vec<Bytes> {
+        std::iter::once(solution.given_token.clone())
+            .chain(
+                solution
+                    .additional_inputs
+                    .iter()
+                    .map(|input| input.token.clone()),
+            )
+            .collect()
+    }
 }
 
 impl StrategyEncoder for SplitSwapStrategyEncoder {
@@ -484,24 +638,107 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
         self.split_swap_validator
             .validate_split_percentages(&solution.swaps)?;
         self.split_swap_validator
-            .validate_swap_path(
+            .validate_split_amounts(
                 &solution.swaps,
                 &solution.given_token,
-                &solution.checked_token,
+                &solution.given_amount,
                 &solution.native_action,
                 &self.native_address,
                 &self.wrapped_address,
             )?;
 
+        let is_multi_input = !solution.additional_inputs.is_empty();
+        if is_multi_input {
+            if solution.native_action.is_some() ||
+                solution.fee.is_some() ||
+                solution.surplus.is_some() ||
+                !solution.additional_outputs.is_empty()
+            {
+                return Err(EncodingError::InvalidInput(
+                    "Native actions, fees, surplus capture and multiple outputs are not supported for solutions with multiple inputs"
+                        .to_string(),
+                ));
+            }
+            for input in solution.additional_inputs.iter() {
+                self.split_swap_validator
+                    .validate_split_amounts(
+                        &solution.swaps,
+                        &input.token,
+                        &input.amount,
+                        &None,
+                        &self.native_address,
+                        &self.wrapped_address,
+                    )?;
+            }
+            self.split_swap_validator
+                .validate_input_tokens(
+                    &solution.swaps,
+                    &self.get_input_tokens(&solution),
+                    &solution.checked_token,
+                )?;
+        } else {
+            self.split_swap_validator
+                .validate_swap_path(
+                    &solution.swaps,
+                    &solution.given_token,
+                    &solution.checked_token,
+                    &solution.native_action,
+                    &self.native_address,
+                    &self.wrapped_address,
+                )?;
+        }
+
         let min_amount_out = get_min_amount_for_solution(solution.clone());
+        let settlement = get_output_settlement(&solution)?;
+
+        let is_multi_output = !solution.additional_outputs.is_empty();
+        if is_multi_output {
+            if settlement.is_some() || solution.native_action == Some(NativeAction::Unwrap) {
+                return Err(EncodingError::InvalidInput(
+                    "Unwrapping, fees and surplus capture are not supported for solutions with multiple outputs"
+                        .to_string(),
+                ));
+            }
+            let output_tokens: Vec<Bytes> = std::iter::once(solution.checked_token.clone())
+                .chain(
+                    solution
+                        .additional_outputs
+                        .iter()
+                        .map(|output| output.token.clone()),
+                )
+                .collect();
+            self.split_swap_validator
+                .validate_output_tokens(&solution.swaps, &output_tokens)?;
+        }
 
         // The tokens array is composed of the given token, the checked token and all the
         // intermediary tokens in between. The contract expects the tokens to be in this order.
+        // Additional input tokens are placed after the given token and additional output tokens
+        // after the checked token, in the order of the solution's inputs and outputs.
+        let input_tokens = self.get_input_tokens(&solution);
         let solution_tokens: HashSet<Bytes> =
             vec![solution.given_token.clone(), solution.checked_token.clone()]
                 .into_iter()
+                .chain(input_tokens.iter().cloned())
+                .chain(
+                    solution
+                        .additional_outputs
+                        .iter()
+                        .map(|output| output.token.clone()),
+                )
                 .collect();
 
+        // If any swap specifies an absolute input amount, all swap headers need to carry an amount.
+        // The settlement, multi-input and multi-output entry points always expect swap headers
+        // with amounts.
+        let with_amounts = settlement.is_some() ||
+            is_multi_output ||
+            is_multi_input ||
+            solution
+                .swaps
+                .iter()
+                .any(|swap| swap.amount_in.is_some());
+
         let grouped_swaps = group_swaps(solution.swaps);
 
         let intermediary_tokens: HashSet<Bytes> = grouped_swaps
@@ -518,13 +755,7 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
         // runs)
         intermediary_tokens.sort();
 
-        let (mut unwrap, mut wrap) = (false, false);
-        if let Some(action) = solution.native_action.clone() {
-            match action {
-                NativeAction::Wrap => wrap = true,
-                NativeAction::Unwrap => unwrap = true,
-            }
-        }
+        let (wrap, unwrap) = get_wrap_unwrap(&solution.native_action)?;
 
         let mut tokens = Vec::with_capacity(2 + intermediary_tokens.len());
         if wrap {
@@ -532,6 +763,12 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
         } else {
             tokens.push(solution.given_token.clone());
         }
+        tokens.extend(
+            solution
+                .additional_inputs
+                .iter()
+                .map(|input| input.token.clone()),
+        );
         tokens.extend(intermediary_tokens);
 
         if unwrap {
@@ -539,6 +776,12 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
         } else {
             tokens.push(solution.checked_token.clone());
         }
+        tokens.extend(
+            solution
+                .additional_outputs
+                .iter()
+                .map(|output| output.token.clone()),
+        );
 
         let mut swaps = vec![];
         for grouped_swap in grouped_swaps.iter() {
@@ -552,14 +795,29 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
                     ))
                 })?;
 
-            let swap_receiver = if !unwrap && grouped_swap.token_out == solution.checked_token {
+            let additional_output = solution
+                .additional_outputs
+                .iter()
+                .find(|output| output.token == grouped_swap.token_out);
+            let swap_receiver = if let Some(output) = additional_output {
+                output.receiver.clone()
+            } else if !unwrap &&
+                settlement.is_none() &&
+                grouped_swap.token_out == solution.checked_token
+            {
                 solution.receiver.clone()
             } else {
                 self.router_address.clone()
             };
+            // Swaps spending any of the input tokens need to transfer the funds from the user
+            let swap_given_token = if input_tokens.contains(&grouped_swap.token_in) {
+                grouped_swap.token_in.clone()
+            } else {
+                solution.given_token.clone()
+            };
             let transfer_type = self
                 .transfer_optimization
-                .get_transfer_type(grouped_swap.clone(), solution.given_token.clone(), wrap, false);
+                .get_transfer_type(grouped_swap.clone(), swap_given_token, wrap, false);
             let encoding_context = EncodingContext {
                 receiver: swap_receiver.clone(),
                 exact_out: solution.exact_out,
@@ -580,6 +838,13 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
                 get_token_position(tokens.clone(), grouped_swap.token_in.clone())?,
                 get_token_position(tokens.clone(), grouped_swap.token_out.clone())?,
                 percentage_to_uint24(grouped_swap.split),
+                with_amounts.then(|| {
+                    grouped_swap
+                        .amount_in
+                        .as_ref()
+                        .map(biguint_to_u256)
+                        .unwrap_or(U256::ZERO)
+                }),
                 Bytes::from_str(swap_encoder.executor_address()).map_err(|_| {
                     EncodingError::FatalError("Invalid executor address".to_string())
                 })?,
@@ -594,28 +859,103 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
         } else {
             tokens.len()
         };
-        let method_calldata = if let Some(permit2) = self.permit2.clone() {
-            let (permit, signature) = permit2.get_permit(
+
+        if is_multi_input {
+            let mut inputs = vec![(
+                bytes_to_address(&solution.given_token)?,
+                biguint_to_u256(&solution.given_amount),
+            )];
+            let mut permit_tokens =
+                vec![(solution.given_token.clone(), solution.given_amount.clone())];
+            for input in solution.additional_inputs.iter() {
+                inputs.push((bytes_to_address(&input.token)?, biguint_to_u256(&input.amount)));
+                permit_tokens.push((input.token.clone(), input.amount.clone()));
+            }
+            let method_calldata = if let Some(permit2) = self.permit2.clone() {
+                let (permit_batch, signature) = permit2.get_permit_batch(
+                    &self.router_address,
+                    &solution.sender,
+                    &permit_tokens,
+                )?;
+                (
+                    inputs,
+                    bytes_to_address(&solution.checked_token)?,
+                    biguint_to_u256(&min_amount_out),
+                    U256::from(tokens.len()),
+                    bytes_to_address(&solution.receiver)?,
+                    permit_batch,
+                    signature.as_bytes().to_vec(),
+                    encoded_swaps,
+                )
+                    .abi_encode()
+            } else {
+                (
+                    inputs,
+                    bytes_to_address(&solution.checked_token)?,
+                    biguint_to_u256(&min_amount_out),
+                    U256::from(tokens.len()),
+                    bytes_to_address(&solution.receiver)?,
+                    encoded_swaps,
+                )
+                    .abi_encode()
+            };
+            let contract_interaction = encode_input(&self.multi_input_selector, method_calldata);
+            return Ok((contract_interaction, self.router_address.clone()));
+        }
+
+        let is_settled = settlement.is_some();
+        let permit = if let Some(permit2) = self.permit2.clone() {
+            Some(permit2.get_permit(
                 &self.router_address,
                 &solution.sender,
                 &solution.given_token,
                 &solution.given_amount,
-            )?;
-            (
-                biguint_to_u256(&solution.given_amount),
-                bytes_to_address(&solution.given_token)?,
+            )?)
+        } else {
+            None
+        };
+
+        if is_multi_output {
+            let mut outputs = vec![(
                 bytes_to_address(&solution.checked_token)?,
                 biguint_to_u256(&min_amount_out),
-                wrap,
-                unwrap,
-                U256::from(tokens_len),
                 bytes_to_address(&solution.receiver)?,
-                permit,
-                signature.as_bytes().to_vec(),
-                encoded_swaps,
-            )
-                .abi_encode()
-        } else {
+            )];
+            for output in solution.additional_outputs.iter() {
+                outputs.push((
+                    bytes_to_address(&output.token)?,
+                    biguint_to_u256(&output.min_amount),
+                    bytes_to_address(&output.receiver)?,
+                ));
+            }
+            let method_calldata = if let Some((permit, signature)) = permit {
+                (
+                    biguint_to_u256(&solution.given_amount),
+                    bytes_to_address(&solution.given_token)?,
+                    wrap,
+                    U256::from(tokens.len()),
+                    outputs,
+                    permit,
+                    signature.as_bytes().to_vec(),
+                    encoded_swaps,
+                )
+                    .abi_encode()
+            } else {
+                (
+                    biguint_to_u256(&solution.given_amount),
+                    bytes_to_address(&solution.given_token)?,
+                    wrap,
+                    U256::from(tokens.len()),
+                    outputs,
+                    encoded_swaps,
+                )
+                    .abi_encode()
+            };
+            let contract_interaction = encode_input(&self.multi_output_selector, method_calldata);
+            return Ok((contract_interaction, self.router_address.clone()));
+        }
+
+        let method_calldata = encode_swap_arguments(
             (
                 biguint_to_u256(&solution.given_amount),
                 bytes_to_address(&solution.given_token)?,
@@ -625,12 +965,20 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
                 unwrap,
                 U256::from(tokens_len),
                 bytes_to_address(&solution.receiver)?,
-                encoded_swaps,
-            )
-                .abi_encode()
-        };
+            ),
+            settlement,
+            permit,
+            encoded_swaps,
+        );
 
-        let contract_interaction = encode_input(&self.selector, method_calldata);
+        let selector = if is_settled {
+            &self.settlement_selector
+        } else if with_amounts {
+            &self.amounts_selector
+        } else {
+            &self.selector
+        };
+        let contract_interaction = encode_input(selector, method_calldata);
         Ok((contract_interaction, self.router_address.clone()))
     }
 
@@ -658,7 +1006,7 @@ mod tests {
     };
 
     use super::*;
-    use crate::encoding::models::Swap;
+    use crate::encoding::models::{IntegratorFee, SolutionInput, SolutionOutput, Swap};
 
     fn eth_chain() -> Chain {
         TychoCommonChain::Ethereum.into()
@@ -724,6 +1072,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SingleSwapStrategyEncoder::new(
@@ -808,6 +1157,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SingleSwapStrategyEncoder::new(
@@ -865,6 +1215,85 @@ mod tests {
             println!("test_single_swap_strategy_encoder_no_permit2: {}", hex_calldata);
         }
 
+        #[test]
+        fn test_single_swap_strategy_encoder_with_fee() {
+            // Performs a single swap from WETH to DAI on a USV2 pool, charging a 1% fee on the
+            // output. The output is sent to the router, which distributes it.
+
+            let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+            let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+
+            let swap = Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth.clone(),
+                dai.clone(),
+                0f64,
+            );
+            let swap_encoder_registry = get_swap_encoder_registry();
+            let encoder = SingleSwapStrategyEncoder::new(
+                eth_chain(),
+                swap_encoder_registry,
+                None,
+                Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+                false,
+            )
+            .unwrap();
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth,
+                given_amount: BigUint::from_str("1_000000000000000000").unwrap(),
+                checked_token: dai,
+                checked_amount: Some(BigUint::from_str("1_640_000000000000000000").unwrap()),
+                sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                swaps: vec![swap],
+                fee: Some(IntegratorFee::new(
+                    100,
+                    Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                )),
+                ..Default::default()
+            };
+
+            let (calldata, _) = encoder
+                .encode_strategy(solution)
+                .unwrap();
+            let expected_input = [
+                "2594a89e",                                                           // Function selector
+                "0000000000000000000000000000000000000000000000000de0b6b3a7640000",   // amount in
+                "000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",   // token in
+                "0000000000000000000000006b175474e89094c44da98b954eedeac495271d0f",   // token out
+                "000000000000000000000000000000000000000000000058e7926ee858a00000",   // min amount out
+                "0000000000000000000000000000000000000000000000000000000000000000",   // wrap
+                "0000000000000000000000000000000000000000000000000000000000000000",   // unwrap
+                "000000000000000000000000cd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2",   // receiver
+                "0000000000000000000000000000000000000000000000000000000000000064",   // fee bps
+                "0000000000000000000000001d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e",   // fee recipient
+                "0000000000000000000000000000000000000000000000000000000000000000",   // expected amount out
+                "0000000000000000000000000000000000000000000000000000000000000000",   // surplus recipient
+                "0000000000000000000000000000000000000000000000000000000000000000",   // surplus share bps
+                "00000000000000000000000000000000000000000000000000000000000001a0",   // offset of swap bytes
+                "0000000000000000000000000000000000000000000000000000000000000052",   // length of swap bytes without padding
+
+                // Swap data
+                "5615deb798bb3e4dfa0139dfa1b3d433cc23b72f", // executor address
+                "c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2", // token in
+                "a478c2975ab1ea89e8196811f51a7b7ade33eb11", // component id
+                "3ede3eca2a72b3aecc820e955b36f38437d01395", // receiver (router)
+                "00",                                       // zero2one
+                "01",                                       // transfer type
+                "0000000000000000000000000000",             // padding
+            ]
+                .join("");
+
+            let hex_calldata = encode(&calldata);
+
+            assert_eq!(hex_calldata, expected_input);
+        }
+
         #[test]
         fn test_single_swap_strategy_encoder_no_transfer_in() {
             // Performs a single swap from WETH to DAI on a USV2 pool assuming that the tokens are
@@ -887,6 +1316,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SingleSwapStrategyEncoder::new(
@@ -965,6 +1395,7 @@ mod tests {
                 token_in: weth(),
                 token_out: dai.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SingleSwapStrategyEncoder::new(
@@ -1018,6 +1449,7 @@ mod tests {
                 token_in: dai.clone(),
                 token_out: weth(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SingleSwapStrategyEncoder::new(
@@ -1080,6 +1512,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: wbtc.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_wbtc_usdc = Swap {
                 component: ProtocolComponent {
@@ -1090,6 +1523,7 @@ mod tests {
                 token_in: wbtc.clone(),
                 token_out: usdc.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SequentialSwapStrategyEncoder::new(
@@ -1140,6 +1574,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: wbtc.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_wbtc_usdc = Swap {
                 component: ProtocolComponent {
@@ -1150,6 +1585,7 @@ mod tests {
                 token_in: wbtc.clone(),
                 token_out: usdc.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SequentialSwapStrategyEncoder::new(
@@ -1247,6 +1683,7 @@ mod tests {
                 token_in: usdc.clone(),
                 token_out: weth.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             // WETH -> USDC (Pool 2)
@@ -1268,6 +1705,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: usdc.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let swap_encoder_registry = get_swap_encoder_registry();
@@ -1377,6 +1815,7 @@ mod tests {
                     token_in: weth.clone(),
                     token_out: wbtc.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
                 let swap_wbtc_usdc = Swap {
                     component: ProtocolComponent {
@@ -1387,6 +1826,7 @@ mod tests {
                     token_in: wbtc.clone(),
                     token_out: usdc.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
                 let swap_encoder_registry = get_swap_encoder_registry();
                 let encoder = SequentialSwapStrategyEncoder::new(
@@ -1451,6 +1891,7 @@ mod tests {
                     token_in: weth.clone(),
                     token_out: wbtc.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
                 let swap_wbtc_usdc = Swap {
                     component: ProtocolComponent {
@@ -1469,6 +1910,7 @@ mod tests {
                     token_in: wbtc.clone(),
                     token_out: usdc.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
                 let swap_encoder_registry = get_swap_encoder_registry();
                 let encoder = SequentialSwapStrategyEncoder::new(
@@ -1532,6 +1974,7 @@ mod tests {
                     token_in: weth.clone(),
                     token_out: wbtc.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
 
                 let swap_wbtc_usdt = Swap {
@@ -1555,6 +1998,7 @@ mod tests {
                     token_in: wbtc.clone(),
                     token_out: usdt.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
                 let swap_encoder_registry = get_swap_encoder_registry();
                 let encoder = SequentialSwapStrategyEncoder::new(
@@ -1611,6 +2055,7 @@ mod tests {
                     token_in: weth.clone(),
                     token_out: wbtc.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
 
                 let swap_wbtc_usdc = Swap {
@@ -1622,6 +2067,7 @@ mod tests {
                     token_in: wbtc.clone(),
                     token_out: usdc.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
                 let swap_encoder_registry = get_swap_encoder_registry();
                 let encoder = SequentialSwapStrategyEncoder::new(
@@ -1684,6 +2130,7 @@ mod tests {
                     token_in: dai.clone(),
                     token_out: weth.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
 
                 let balancer_swap_weth_wbtc = Swap {
@@ -1696,6 +2143,7 @@ mod tests {
                     token_in: weth.clone(),
                     token_out: wbtc.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
 
                 let curve_swap_wbtc_usdt = Swap {
@@ -1719,6 +2167,7 @@ mod tests {
                     token_in: wbtc.clone(),
                     token_out: usdt.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
 
                 // Ekubo
@@ -1740,6 +2189,7 @@ mod tests {
                     token_in: usdt.clone(),
                     token_out: usdc.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
 
                 // USV4
@@ -1763,6 +2213,7 @@ mod tests {
                     token_in: usdc.clone(),
                     token_out: eth.clone(),
                     split: 0f64,
+                    amount_in: None,
                 };
 
                 // Put all components together
@@ -1838,6 +2289,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5f64,
+                amount_in: None,
             };
             let swap_weth_wbtc = Swap {
                 component: ProtocolComponent {
@@ -1851,6 +2303,7 @@ mod tests {
                 // to 0 to signify "the remainder of the WETH value". It should
                 // still be very close to 50%
                 split: 0f64,
+                amount_in: None,
             };
             let swap_dai_usdc = Swap {
                 component: ProtocolComponent {
@@ -1861,6 +2314,7 @@ mod tests {
                 token_in: dai.clone(),
                 token_out: usdc.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_wbtc_usdc = Swap {
                 component: ProtocolComponent {
@@ -1871,6 +2325,7 @@ mod tests {
                 token_in: wbtc.clone(),
                 token_out: usdc.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SplitSwapStrategyEncoder::new(
@@ -1902,6 +2357,408 @@ mod tests {
             println!("test_split_swap_strategy_encoder: {}", _hex_calldata);
         }
 
+        #[test]
+        fn test_split_swap_strategy_encoder_with_amounts() {
+            // Performs a split swap from WETH to USDC though WBTC and DAI using USV2 pools, sending
+            // exactly 0.6 WETH to the DAI pool and the remainder to the WBTC pool
+            //
+            //         ┌──(USV2, 0.6 WETH)──> DAI  ───(USV2)──> USDC
+            //   WETH ─┤
+            //         └──(USV2)──────────> WBTC ───(USV2)──> USDC
+            //
+            let weth = weth();
+            let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+            let wbtc = Bytes::from_str("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599").unwrap();
+            let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+            let swap_weth_dai = Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth.clone(),
+                dai.clone(),
+                0f64,
+            )
+            .with_amount_in(BigUint::from_str("600000000000000000").unwrap());
+            let swap_weth_wbtc = Swap::new(
+                ProtocolComponent {
+                    id: "0xBb2b8038a1640196FbE3e38816F3e67Cba72D940".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth.clone(),
+                wbtc.clone(),
+                0f64,
+            );
+            let swap_dai_usdc = Swap::new(
+                ProtocolComponent {
+                    id: "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                dai.clone(),
+                usdc.clone(),
+                0f64,
+            );
+            let swap_wbtc_usdc = Swap::new(
+                ProtocolComponent {
+                    id: "0x004375Dff511095CC5A197A54140a24eFEF3A416".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                wbtc.clone(),
+                usdc.clone(),
+                0f64,
+            );
+            let swap_encoder_registry = get_swap_encoder_registry();
+            let encoder = SplitSwapStrategyEncoder::new(
+                eth_chain(),
+                swap_encoder_registry,
+                None,
+                Bytes::from("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395"),
+                false,
+            )
+            .unwrap();
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth,
+                given_amount: BigUint::from_str("1_000000000000000000").unwrap(),
+                checked_token: usdc,
+                expected_amount: None,
+                checked_amount: Some(BigUint::from_str("26173932").unwrap()),
+                sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                swaps: vec![swap_weth_dai, swap_weth_wbtc, swap_dai_usdc, swap_wbtc_usdc],
+                ..Default::default()
+            };
+
+            let (calldata, _) = encoder
+                .encode_strategy(solution)
+                .unwrap();
+            let hex_calldata = encode(&calldata);
+
+            // splitSwapWithAmounts selector
+            assert_eq!(&hex_calldata[..8], "656772ff");
+            let expected_weth_dai_header = String::from(concat!(
+                "00",     // token in index
+                "02",     // token out index
+                "000000", // split
+                // amount in
+                "0000000000000000000000000000000000000000000000000853a0d2313c0000",
+                "5615deb798bb3e4dfa0139dfa1b3d433cc23b72f", // executor address
+            ));
+            let expected_weth_wbtc_header = String::from(concat!(
+                "00",     // token in index
+                "01",     // token out index
+                "000000", // split
+                // amount in
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "5615deb798bb3e4dfa0139dfa1b3d433cc23b72f", // executor address
+            ));
+            assert!(hex_calldata.contains(&expected_weth_dai_header));
+            assert!(hex_calldata.contains(&expected_weth_wbtc_header));
+        }
+
+        #[test]
+        fn test_split_swap_strategy_encoder_multi_output() {
+            // Performs a split swap from WETH to DAI and WBTC using USV2 pools, sending exactly 0.5
+            // WETH to the DAI pool and the remainder to the WBTC pool. Each output token is sent to
+            // a different receiver.
+            //
+            //         ┌──(USV2, 0.5 WETH)──> DAI  (Alice)
+            //   WETH ─┤
+            //         └──(USV2)──────────> WBTC (Bob)
+            //
+            let weth = weth();
+            let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+            let wbtc = Bytes::from_str("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599").unwrap();
+            let bob = Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap();
+
+            let swap_weth_dai = Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth.clone(),
+                dai.clone(),
+                0f64,
+            )
+            .with_amount_in(BigUint::from_str("500000000000000000").unwrap());
+            let swap_weth_wbtc = Swap::new(
+                ProtocolComponent {
+                    id: "0xBb2b8038a1640196FbE3e38816F3e67Cba72D940".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth.clone(),
+                wbtc.clone(),
+                0f64,
+            );
+            let swap_encoder_registry = get_swap_encoder_registry();
+            let encoder = SplitSwapStrategyEncoder::new(
+                eth_chain(),
+                swap_encoder_registry,
+                None,
+                Bytes::from("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395"),
+                false,
+            )
+            .unwrap();
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth,
+                given_amount: BigUint::from_str("1_000000000000000000").unwrap(),
+                checked_token: dai,
+                expected_amount: None,
+                checked_amount: Some(BigUint::from_str("1000_000000000000000000").unwrap()),
+                sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                swaps: vec![swap_weth_dai, swap_weth_wbtc],
+                additional_outputs: vec![SolutionOutput::new(
+                    wbtc,
+                    BigUint::from_str("100000").unwrap(),
+                    bob,
+                )],
+                ..Default::default()
+            };
+
+            let (calldata, _) = encoder
+                .encode_strategy(solution)
+                .unwrap();
+            let hex_calldata = encode(&calldata);
+
+            // splitSwapMultiOutput selector
+            assert_eq!(&hex_calldata[..8], "23ff772d");
+            let expected_outputs = String::from(concat!(
+                // outputs length
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                // dai
+                "0000000000000000000000006b175474e89094c44da98b954eedeac495271d0f",
+                // min amount out
+                "00000000000000000000000000000000000000000000003635c9adc5dea00000",
+                // alice
+                "000000000000000000000000cd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2",
+                // wbtc
+                "0000000000000000000000002260fac5e5542a773aa44fbcfedf7c193bc2c599",
+                // min amount out
+                "00000000000000000000000000000000000000000000000000000000000186a0",
+                // bob
+                "0000000000000000000000001d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e",
+            ));
+            let expected_weth_wbtc_swap = String::from(concat!(
+                "00",     // token in index
+                "02",     // token out index
+                "000000", // split
+                // amount in
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "5615deb798bb3e4dfa0139dfa1b3d433cc23b72f", // executor address
+                "c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2", // token in
+                "bb2b8038a1640196fbe3e38816f3e67cba72d940", // component id
+                "1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e", // receiver (bob)
+            ));
+            assert!(hex_calldata.contains(&expected_outputs));
+            assert!(hex_calldata.contains(&expected_weth_wbtc_swap));
+        }
+
+        #[test]
+        fn test_split_swap_strategy_encoder_multi_input() {
+            // Performs a split swap from WETH and USDC to DAI using USV2 pools
+            //
+            //   WETH ──(USV2)──┐
+            //                  ├──> DAI
+            //   USDC ──(USV2)──┘
+            //
+            let weth = weth();
+            let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+            let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+            let swap_weth_dai = Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth.clone(),
+                dai.clone(),
+                0f64,
+            );
+            let swap_usdc_dai = Swap::new(
+                ProtocolComponent {
+                    id: "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                usdc.clone(),
+                dai.clone(),
+                0f64,
+            );
+            let swap_encoder_registry = get_swap_encoder_registry();
+            let encoder = SplitSwapStrategyEncoder::new(
+                eth_chain(),
+                swap_encoder_registry,
+                None,
+                Bytes::from("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395"),
+                false,
+            )
+            .unwrap();
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth,
+                given_amount: BigUint::from_str("1_000000000000000000").unwrap(),
+                checked_token: dai,
+                expected_amount: None,
+                checked_amount: Some(BigUint::from_str("2500_000000000000000000").unwrap()),
+                sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                swaps: vec![swap_weth_dai, swap_usdc_dai],
+                additional_inputs: vec![SolutionInput::new(
+                    usdc,
+                    BigUint::from_str("1000_000000").unwrap(),
+                )],
+                ..Default::default()
+            };
+
+            let (calldata, _) = encoder
+                .encode_strategy(solution)
+                .unwrap();
+            let hex_calldata = encode(&calldata);
+
+            // splitSwapMultiInput selector
+            assert_eq!(&hex_calldata[..8], "02a0386c");
+            let expected_inputs = String::from(concat!(
+                // inputs length
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                // weth
+                "000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                // amount in
+                "0000000000000000000000000000000000000000000000000de0b6b3a7640000",
+                // usdc
+                "000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                // amount in
+                "000000000000000000000000000000000000000000000000000000003b9aca00",
+            ));
+            let expected_usdc_dai_swap = String::from(concat!(
+                "01",     // token in index
+                "02",     // token out index
+                "000000", // split
+                // amount in
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "5615deb798bb3e4dfa0139dfa1b3d433cc23b72f", // executor address
+                "a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", // token in
+                "ae461ca67b15dc8dc81ce7615e0320da1a9ab8d5", // component id
+                "cd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2", // receiver
+                "00",                                       // zero2one
+                "01",                                       // transfer type
+            ));
+            assert!(hex_calldata.contains(&expected_inputs));
+            assert!(hex_calldata.contains(&expected_usdc_dai_swap));
+        }
+
+        #[test]
+        fn test_split_swap_strategy_encoder_multi_input_permit2() {
+            // Performs a split swap from WETH and USDC to DAI using USV2 pools, approving both
+            // input tokens with a single Permit2 batch signature
+            //
+            //   WETH ──(USV2)──┐
+            //                  ├──> DAI
+            //   USDC ──(USV2)──┘
+            //
+            let private_key =
+                "0x123456789abcdef123456789abcdef123456789abcdef123456789abcdef1234".to_string();
+
+            let weth = weth();
+            let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+            let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+            let swap_weth_dai = Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth.clone(),
+                dai.clone(),
+                0f64,
+            );
+            let swap_usdc_dai = Swap::new(
+                ProtocolComponent {
+                    id: "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                usdc.clone(),
+                dai.clone(),
+                0f64,
+            );
+            let swap_encoder_registry = get_swap_encoder_registry();
+            let encoder = SplitSwapStrategyEncoder::new(
+                eth_chain(),
+                swap_encoder_registry,
+                Some(private_key),
+                Bytes::from("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395"),
+                false,
+            )
+            .unwrap();
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth,
+                given_amount: BigUint::from_str("1_000000000000000000").unwrap(),
+                checked_token: dai,
+                expected_amount: None,
+                checked_amount: Some(BigUint::from_str("2500_000000000000000000").unwrap()),
+                sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                swaps: vec![swap_weth_dai, swap_usdc_dai],
+                additional_inputs: vec![SolutionInput::new(
+                    usdc,
+                    BigUint::from_str("1000_000000").unwrap(),
+                )],
+                ..Default::default()
+            };
+
+            let (calldata, _) = encoder
+                .encode_strategy(solution)
+                .unwrap();
+            let hex_calldata = encode(&calldata);
+
+            // splitSwapMultiInputPermit2 selector
+            assert_eq!(&hex_calldata[..8], "7223201e");
+            let weth_input = String::from(concat!(
+                "000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2", // weth
+                "0000000000000000000000000000000000000000000000000de0b6b3a7640000", // amount in
+            ));
+            let usdc_input = String::from(concat!(
+                "000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", // usdc
+                "000000000000000000000000000000000000000000000000000000003b9aca00", // amount in
+            ));
+            let expected_inputs = format!(
+                "{}{}{}",
+                // inputs length
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                weth_input,
+                usdc_input
+            );
+            assert!(hex_calldata.contains(&expected_inputs));
+            // Both input tokens are approved with their amounts in the permit batch details
+            assert_eq!(
+                hex_calldata
+                    .matches(&weth_input)
+                    .count(),
+                2
+            );
+            assert_eq!(
+                hex_calldata
+                    .matches(&usdc_input)
+                    .count(),
+                2
+            );
+            // The router is the spender of the permit batch
+            assert!(hex_calldata
+                .contains("0000000000000000000000003ede3eca2a72b3aecc820e955b36f38437d01395"));
+        }
+
         #[test]
         fn test_split_input_cyclic_swap() {
             // This test has start and end tokens that are the same
@@ -1938,6 +2795,7 @@ mod tests {
                 token_in: usdc.clone(),
                 token_out: weth.clone(),
                 split: 0.6f64, // 60% of input
+                amount_in: None,
             };
 
             // USDC -> WETH (Pool 2) - 40% of input (remaining)
@@ -1959,6 +2817,7 @@ mod tests {
                 token_in: usdc.clone(),
                 token_out: weth.clone(),
                 split: 0f64, // Remaining 40%
+                amount_in: None,
             };
 
             // WETH -> USDC (Pool 2)
@@ -1980,6 +2839,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: usdc.clone(),
                 split: 0.0f64,
+                amount_in: None,
             };
 
             let swap_encoder_registry = get_swap_encoder_registry();
@@ -2103,6 +2963,7 @@ mod tests {
                 token_in: usdc.clone(),
                 token_out: weth.clone(),
                 split: 0.0f64,
+                amount_in: None,
             };
 
             let swap_weth_usdc_v3_pool1 = Swap {
@@ -2123,6 +2984,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: usdc.clone(),
                 split: 0.6f64,
+                amount_in: None,
             };
 
             let swap_weth_usdc_v3_pool2 = Swap {
@@ -2143,6 +3005,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: usdc.clone(),
                 split: 0.0f64,
+                amount_in: None,
             };
 
             let swap_encoder_registry = get_swap_encoder_registry();
@@ -2269,6 +3132,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let swap_encoder_registry = get_swap_encoder_registry();
@@ -2336,6 +3200,7 @@ mod tests {
                 token_in: eth.clone(),
                 token_out: pepe.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SingleSwapStrategyEncoder::new(
@@ -2404,6 +3269,7 @@ mod tests {
                 token_in: usdc.clone(),
                 token_out: eth.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let swap_encoder_registry = get_swap_encoder_registry();
@@ -2480,6 +3346,7 @@ mod tests {
                 token_in: usdc.clone(),
                 token_out: eth.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let swap_eth_pepe = Swap {
@@ -2493,6 +3360,7 @@ mod tests {
                 token_in: eth.clone(),
                 token_out: pepe.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let swap_encoder_registry = get_swap_encoder_registry();
             let encoder = SequentialSwapStrategyEncoder::new(
@@ -2595,6 +3463,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let swap_encoder_registry = get_swap_encoder_registry();
@@ -2658,6 +3527,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let swap_encoder_registry = get_swap_encoder_registry();
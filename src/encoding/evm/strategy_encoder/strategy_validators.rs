@@ -1,9 +1,11 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use num_bigint::BigUint;
 use tycho_common::Bytes;
 
 use crate::encoding::{
     errors::EncodingError,
+    evm::utils::percentage_to_uint24,
     models::{NativeAction, Solution, Swap},
 };
 
@@ -130,6 +132,9 @@ impl SplitSwapValidator {
     ///   to the router to send the remainder of the token to the designated protocol
     /// * The sum of all non-remainder splits for each token is < 1 (100%)
     /// * There are no negative split amounts
+    ///
+    /// Swaps with an absolute `amount_in` are neither percentage splits nor remainders. Tokens
+    /// which are only spent by such swaps don't need a 0% split.
     pub fn validate_split_percentages(&self, swaps: &[Swap]) -> Result<(), EncodingError> {
         let mut swaps_by_token: HashMap<Bytes, Vec<&Swap>> = HashMap::new();
         for swap in swaps {
@@ -146,6 +151,14 @@ impl SplitSwapValidator {
         }
 
         for (token, token_swaps) in swaps_by_token {
+            // Absolute amount swaps don't need remainder handling
+            if token_swaps
+                .iter()
+                .all(|swap| swap.amount_in.is_some())
+            {
+                continue;
+            }
+
             // Single swaps don't need remainder handling
             if token_swaps.len() == 1 {
                 if token_swaps[0].split != 0.0 {
@@ -160,6 +173,9 @@ impl SplitSwapValidator {
             let mut found_zero_split = false;
             let mut total_percentage = 0.0;
             for (i, swap) in token_swaps.iter().enumerate() {
+                if swap.amount_in.is_some() {
+                    continue;
+                }
                 match (swap.split == 0.0, i == token_swaps.len() - 1) {
                     (true, false) => {
                         return Err(EncodingError::InvalidInput(format!(
@@ -199,6 +215,216 @@ impl SplitSwapValidator {
 
         Ok(())
     }
+
+    /// Raises an error if the absolute swap amounts are invalid.
+    ///
+    /// Absolute amounts are considered valid if all the following conditions are met:
+    /// * Each swap with an absolute amount has a 0% split and a non-zero amount
+    /// * The sum of the absolute amounts and of the percentage splits of the given token doesn't
+    ///   exceed the given amount
+    /// * If all swaps of the given token have absolute amounts, they add up to the given amount, so
+    ///   that no funds are left in the router
+    /// * Intermediate tokens spent with absolute amounts also have a 0% split swap. Their amount is
+    ///   only known on-chain, so the remainder swap spends whatever the absolute amounts leave.
+    ///
+    /// If the given token is the native token and the native action is WRAP, the swaps are
+    /// expected to spend the wrapped token.
+    pub fn validate_split_amounts(
+        &self,
+        swaps: &[Swap],
+        given_token: &Bytes,
+        given_amount: &BigUint,
+        native_action: &Option<NativeAction>,
+        native_address: &Bytes,
+        wrapped_address: &Bytes,
+    ) -> Result<(), EncodingError> {
+        let given_token = match native_action {
+            Some(NativeAction::Wrap) if given_token == native_address => wrapped_address,
+            _ => given_token,
+        };
+
+        let intermediate_tokens: HashSet<&Bytes> = swaps
+            .iter()
+            .map(|swap| &swap.token_out)
+            .filter(|token| *token != given_token)
+            .collect();
+        let mut total_amount = BigUint::ZERO;
+        let mut split_amount = BigUint::ZERO;
+        let mut all_absolute = true;
+        let mut absolute_intermediate_tokens = HashSet::new();
+        let mut remainder_intermediate_tokens = HashSet::new();
+        for swap in swaps {
+            match &swap.amount_in {
+                Some(amount_in) => {
+                    if swap.split != 0.0 {
+                        return Err(EncodingError::InvalidInput(format!(
+                            "Swap with an absolute amount must have a 0% split, got {}",
+                            swap.split
+                        )));
+                    }
+                    if *amount_in == BigUint::ZERO {
+                        return Err(EncodingError::InvalidInput(
+                            "Absolute swap amount must be greater than 0".to_string(),
+                        ));
+                    }
+                    if swap.token_in == *given_token {
+                        total_amount += amount_in;
+                    } else if intermediate_tokens.contains(&swap.token_in) {
+                        absolute_intermediate_tokens.insert(&swap.token_in);
+                    }
+                }
+                None => {
+                    if swap.token_in == *given_token {
+                        all_absolute = false;
+                        // The router computes the split amounts from the given amount
+                        split_amount += given_amount *
+                            BigUint::from(percentage_to_uint24(swap.split).to::<u32>()) /
+                            BigUint::from(0xffffffu32);
+                    } else if swap.split == 0.0 {
+                        remainder_intermediate_tokens.insert(&swap.token_in);
+                    }
+                }
+            }
+        }
+
+        if total_amount > *given_amount {
+            return Err(EncodingError::InvalidInput(format!(
+                "Total of absolute amounts for token {:?} exceeds the given amount: {} > {}",
+                given_token, total_amount, given_amount
+            )));
+        }
+        if &total_amount + &split_amount > *given_amount {
+            return Err(EncodingError::InvalidInput(format!(
+                "Absolute amounts and splits for token {:?} exceed the given amount: {} + {} > {}",
+                given_token, total_amount, split_amount, given_amount
+            )));
+        }
+        if all_absolute && total_amount != *given_amount {
+            return Err(EncodingError::InvalidInput(format!(
+                "Absolute amounts for token {:?} must add up to the given amount: {} != {}",
+                given_token, total_amount, given_amount
+            )));
+        }
+        if let Some(token) = absolute_intermediate_tokens
+            .difference(&remainder_intermediate_tokens)
+            .next()
+        {
+            return Err(EncodingError::InvalidInput(format!(
+                "Intermediate token {:?} is spent with absolute amounts, so it needs a 0% split swap \
+                 to spend the remainder",
+                token
+            )));
+        }
+        Ok(())
+    }
+
+    /// Raises an error if the swaps don't end in the given output tokens.
+    ///
+    /// Used for solutions with multiple outputs, on top of `validate_swap_path`. The swaps are
+    /// considered valid if all the following conditions are met:
+    /// * Every output token is the output of at least one swap.
+    /// * No swap spends an output token.
+    /// * Every token that is not spent by any swap is an output token.
+    pub fn validate_output_tokens(
+        &self,
+        swaps: &[Swap],
+        output_tokens: &[Bytes],
+    ) -> Result<(), EncodingError> {
+        let tokens_in: HashSet<&Bytes> = swaps
+            .iter()
+            .map(|swap| &swap.token_in)
+            .collect();
+        let tokens_out: HashSet<&Bytes> = swaps
+            .iter()
+            .map(|swap| &swap.token_out)
+            .collect();
+
+        for output_token in output_tokens {
+            if !tokens_out.contains(output_token) {
+                return Err(EncodingError::InvalidInput(format!(
+                    "Output token {:?} is not the output of any swap",
+                    output_token
+                )));
+            }
+            if tokens_in.contains(output_token) {
+                return Err(EncodingError::InvalidInput(format!(
+                    "Output token {:?} cannot be the input of a swap",
+                    output_token
+                )));
+            }
+        }
+        for token in tokens_out.difference(&tokens_in) {
+            if !output_tokens.contains(token) {
+                return Err(EncodingError::InvalidInput(format!(
+                    "Token {:?} is not spent by any swap and is not an output token",
+                    token
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Raises an error if the swaps don't connect every input token to the checked token.
+    ///
+    /// Used for solutions with multiple inputs, instead of `validate_swap_path`. The swaps are
+    /// considered valid if all the following conditions are met:
+    /// * The checked token is reachable from every input token.
+    /// * No swap outputs an input token.
+    /// * Every token of the swaps is reachable from at least one input token.
+    pub fn validate_input_tokens(
+        &self,
+        swaps: &[Swap],
+        input_tokens: &[Bytes],
+        checked_token: &Bytes,
+    ) -> Result<(), EncodingError> {
+        let mut graph: HashMap<&Bytes, HashSet<&Bytes>> = HashMap::new();
+        let mut all_tokens = HashSet::new();
+        for swap in swaps {
+            if input_tokens.contains(&swap.token_out) {
+                return Err(EncodingError::InvalidInput(format!(
+                    "Input token {:?} cannot be the output of a swap",
+                    swap.token_out
+                )));
+            }
+            graph
+                .entry(&swap.token_in)
+                .or_default()
+                .insert(&swap.token_out);
+            all_tokens.insert(&swap.token_in);
+            all_tokens.insert(&swap.token_out);
+        }
+
+        let mut visited_by_any = HashSet::new();
+        for input_token in input_tokens {
+            let mut visited = HashSet::new();
+            let mut queue = VecDeque::from([input_token]);
+            while let Some(token) = queue.pop_front() {
+                if !visited.insert(token) {
+                    continue;
+                }
+                if let Some(next_tokens) = graph.get(token) {
+                    queue.extend(next_tokens.iter().copied());
+                }
+            }
+            if !visited.contains(checked_token) {
+                return Err(EncodingError::InvalidInput(format!(
+                    "Checked token is not reachable from input token {:?}",
+                    input_token
+                )));
+            }
+            visited_by_any.extend(visited);
+        }
+
+        if all_tokens
+            .iter()
+            .any(|token| !visited_by_any.contains(*token))
+        {
+            return Err(EncodingError::InvalidInput(
+                "Some tokens are not connected to the main path".to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 /// Validates whether a sequence of sequential swaps represents a valid solution.
@@ -233,6 +459,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: dai.clone(),
             split: 0f64,
+            amount_in: None,
         }];
         let result = validator.validate_swap_path(&swaps, &weth, &dai, &None, &eth, &weth);
         assert_eq!(result, Ok(()));
@@ -255,6 +482,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5f64,
+                amount_in: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -265,6 +493,7 @@ mod tests {
                 token_in: dai.clone(),
                 token_out: usdc.clone(),
                 split: 0f64,
+                amount_in: None,
             },
         ];
         let result = validator.validate_swap_path(&swaps, &weth, &usdc, &None, &eth, &weth);
@@ -290,6 +519,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5,
+                amount_in: None,
             },
             // This swap is disconnected from the WETH->DAI path
             Swap {
@@ -301,6 +531,7 @@ mod tests {
                 token_in: wbtc.clone(),
                 token_out: usdc.clone(),
                 split: 0.0,
+                amount_in: None,
             },
         ];
         let result =
@@ -328,6 +559,7 @@ mod tests {
                 token_in: usdc.clone(),
                 token_out: weth.clone(),
                 split: 0f64,
+                amount_in: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -338,6 +570,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: usdc.clone(),
                 split: 0f64,
+                amount_in: None,
             },
         ];
 
@@ -363,6 +596,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: dai.clone(),
             split: 1.0,
+            amount_in: None,
         }];
         let result =
             validator.validate_swap_path(&unreachable_swaps, &weth, &usdc, &None, &eth, &weth);
@@ -401,6 +635,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: dai.clone(),
             split: 0f64,
+            amount_in: None,
         }];
         let result = validator.validate_split_percentages(&swaps);
         assert_eq!(result, Ok(()));
@@ -423,6 +658,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5,
+                amount_in: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -433,6 +669,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.3,
+                amount_in: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -443,6 +680,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.0, // Remainder (20%)
+                amount_in: None,
             },
         ];
         assert!(validator
@@ -466,6 +704,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.7,
+                amount_in: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -476,6 +715,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.3,
+                amount_in: None,
             },
         ];
         assert!(matches!(
@@ -500,6 +740,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.0,
+                amount_in: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -510,6 +751,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5,
+                amount_in: None,
             },
         ];
         assert!(matches!(
@@ -534,6 +776,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.6,
+                amount_in: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -544,6 +787,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5,
+                amount_in: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -554,6 +798,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.0,
+                amount_in: None,
             },
         ];
         assert!(matches!(
@@ -562,6 +807,260 @@ mod tests {
         ));
     }
 
+    fn absolute_amount_swaps(amounts: Vec<Option<u64>>) -> Vec<Swap> {
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        amounts
+            .into_iter()
+            .enumerate()
+            .map(|(i, amount_in)| Swap {
+                component: ProtocolComponent {
+                    id: format!("pool{}", i),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth.clone(),
+                token_out: dai.clone(),
+                split: 0f64,
+                amount_in: amount_in.map(BigUint::from),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_swaps_absolute_amounts() {
+        let validator = SplitSwapValidator;
+        // Only absolute amounts: no remainder needed
+        let swaps = absolute_amount_swaps(vec![Some(600), Some(400)]);
+        assert_eq!(validator.validate_split_percentages(&swaps), Ok(()));
+        // Absolute amounts followed by a remainder
+        let swaps = absolute_amount_swaps(vec![Some(600), None]);
+        assert_eq!(validator.validate_split_percentages(&swaps), Ok(()));
+        // The remainder must still be the last swap of the token
+        let swaps = absolute_amount_swaps(vec![None, Some(600)]);
+        assert!(matches!(
+            validator.validate_split_percentages(&swaps),
+            Err(EncodingError::InvalidInput(msg)) if msg.contains("must be the last swap")
+        ));
+    }
+
+    #[rstest]
+    #[case::exact_amounts(vec![Some(600), Some(400)], None)]
+    #[case::with_remainder(vec![Some(600), None], None)]
+    #[case::amounts_exceed_given_amount(vec![Some(600), Some(600)], Some("exceeds the given amount"))]
+    #[case::amounts_below_given_amount(vec![Some(600), Some(300)], Some("must add up to the given amount"))]
+    #[case::zero_amount(vec![Some(0), None], Some("must be greater than 0"))]
+    fn test_validate_split_amounts(
+        #[case] amounts: Vec<Option<u64>>,
+        #[case] expected_error: Option<&str>,
+    ) {
+        let validator = SplitSwapValidator;
+        let eth = Bytes::from_str("0x0000000000000000000000000000000000000000").unwrap();
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let swaps = absolute_amount_swaps(amounts);
+        let result = validator.validate_split_amounts(
+            &swaps,
+            &weth,
+            &BigUint::from(1000u64),
+            &None,
+            &eth,
+            &weth,
+        );
+        match expected_error {
+            None => assert_eq!(result, Ok(())),
+            Some(expected) => assert!(matches!(
+                result,
+                Err(EncodingError::InvalidInput(msg)) if msg.contains(expected)
+            )),
+        }
+    }
+
+    #[test]
+    fn test_validate_split_amounts_non_zero_split() {
+        let validator = SplitSwapValidator;
+        let eth = Bytes::from_str("0x0000000000000000000000000000000000000000").unwrap();
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let mut swaps = absolute_amount_swaps(vec![Some(600), None]);
+        swaps[0].split = 0.5;
+        assert!(matches!(
+            validator.validate_split_amounts(&swaps, &weth, &BigUint::from(1000u64), &None, &eth, &weth),
+            Err(EncodingError::InvalidInput(msg)) if msg.contains("must have a 0% split")
+        ));
+    }
+
+    #[rstest]
+    #[case::within_given_amount(Some(400), None)]
+    #[case::exceed_given_amount(Some(600), Some("exceed the given amount: 600 + 500"))]
+    fn test_validate_split_amounts_with_splits(
+        #[case] absolute_amount: Option<u64>,
+        #[case] expected_error: Option<&str>,
+    ) {
+        let validator = SplitSwapValidator;
+        let eth = Bytes::from_str("0x0000000000000000000000000000000000000000").unwrap();
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        // An absolute amount, a 50% split of the given amount and the remainder
+        let mut swaps = absolute_amount_swaps(vec![absolute_amount, None, None]);
+        swaps[1].split = 0.5;
+        let result = validator.validate_split_amounts(
+            &swaps,
+            &weth,
+            &BigUint::from(1000u64),
+            &None,
+            &eth,
+            &weth,
+        );
+        match expected_error {
+            None => assert_eq!(result, Ok(())),
+            Some(expected) => assert!(matches!(
+                result,
+                Err(EncodingError::InvalidInput(msg)) if msg.contains(expected)
+            )),
+        }
+    }
+
+    #[test]
+    fn test_validate_split_amounts_intermediate_token() {
+        let validator = SplitSwapValidator;
+        let eth = Bytes::from_str("0x0000000000000000000000000000000000000000").unwrap();
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let component = ProtocolComponent {
+            id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+            protocol_system: "uniswap_v2".to_string(),
+            ..Default::default()
+        };
+        // WETH ──> DAI ──(100 DAI)──> USDC
+        let mut swaps = vec![
+            Swap::new(component.clone(), weth.clone(), dai.clone(), 0f64),
+            Swap::new(component.clone(), dai.clone(), usdc.clone(), 0f64)
+                .with_amount_in(BigUint::from(100u64)),
+        ];
+        assert!(matches!(
+            validator.validate_split_amounts(&swaps, &weth, &BigUint::from(1000u64), &None, &eth, &weth),
+            Err(EncodingError::InvalidInput(msg)) if msg.contains("needs a 0% split swap")
+        ));
+
+        // The remainder of the DAI is swapped too
+        swaps.push(Swap::new(component, dai, usdc, 0f64));
+        assert_eq!(
+            validator.validate_split_amounts(
+                &swaps,
+                &weth,
+                &BigUint::from(1000u64),
+                &None,
+                &eth,
+                &weth
+            ),
+            Ok(())
+        );
+    }
+
+    fn multi_output_swaps() -> Vec<Swap> {
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let wbtc = Bytes::from_str("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599").unwrap();
+        let component = ProtocolComponent {
+            id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+            protocol_system: "uniswap_v2".to_string(),
+            ..Default::default()
+        };
+        // WETH ──(50%)──> DAI ──> USDC
+        //  └────────────> WBTC
+        vec![
+            Swap::new(component.clone(), weth.clone(), dai.clone(), 0.5),
+            Swap::new(component.clone(), dai, usdc, 0f64),
+            Swap::new(component, weth, wbtc, 0f64),
+        ]
+    }
+
+    #[test]
+    fn test_validate_output_tokens() {
+        let validator = SplitSwapValidator;
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let wbtc = Bytes::from_str("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599").unwrap();
+        let swaps = multi_output_swaps();
+
+        assert_eq!(validator.validate_output_tokens(&swaps, &[usdc.clone(), wbtc.clone()]), Ok(()));
+
+        // WBTC is a sink but is not an output
+        let result = validator.validate_output_tokens(&swaps, &[usdc.clone()]);
+        assert!(matches!(
+            result,
+            Err(EncodingError::InvalidInput(msg)) if msg.ends_with("is not spent by any swap and is not an output token")
+        ));
+
+        // DAI is spent by a swap
+        let result = validator.validate_output_tokens(&swaps, &[usdc.clone(), wbtc.clone(), dai]);
+        assert!(matches!(
+            result,
+            Err(EncodingError::InvalidInput(msg)) if msg.ends_with("cannot be the input of a swap")
+        ));
+
+        // USDT is never bought
+        let usdt = Bytes::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let result = validator.validate_output_tokens(&swaps, &[usdc, wbtc, usdt]);
+        assert!(matches!(
+            result,
+            Err(EncodingError::InvalidInput(msg)) if msg.ends_with("is not the output of any swap")
+        ));
+    }
+
+    #[test]
+    fn test_validate_input_tokens() {
+        let validator = SplitSwapValidator;
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let wbtc = Bytes::from_str("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599").unwrap();
+        let component = ProtocolComponent {
+            id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+            protocol_system: "uniswap_v2".to_string(),
+            ..Default::default()
+        };
+        // WETH ──> WBTC ──> USDC
+        //  DAI ───────────> USDC
+        let swaps = vec![
+            Swap::new(component.clone(), weth.clone(), wbtc.clone(), 0f64),
+            Swap::new(component.clone(), wbtc.clone(), usdc.clone(), 0f64),
+            Swap::new(component.clone(), dai.clone(), usdc.clone(), 0f64),
+        ];
+
+        assert_eq!(
+            validator.validate_input_tokens(&swaps, &[weth.clone(), dai.clone()], &usdc),
+            Ok(())
+        );
+
+        // DAI is not an input, so its swap is not connected
+        assert_eq!(
+            validator.validate_input_tokens(&swaps, &[weth.clone()], &usdc),
+            Err(EncodingError::InvalidInput(
+                "Some tokens are not connected to the main path".to_string()
+            ))
+        );
+
+        // WBTC is an input but it is also the output of a swap
+        let result =
+            validator.validate_input_tokens(&swaps, &[weth.clone(), dai.clone(), wbtc], &usdc);
+        assert!(matches!(
+            result,
+            Err(EncodingError::InvalidInput(msg)) if msg.ends_with("cannot be the output of a swap")
+        ));
+
+        // DAI doesn't reach the checked token
+        let swaps = vec![
+            Swap::new(component.clone(), weth.clone(), usdc.clone(), 0f64),
+            Swap::new(component, dai.clone(), wbtc, 0f64),
+        ];
+        let result = validator.validate_input_tokens(&swaps, &[weth, dai], &usdc);
+        assert!(matches!(
+            result,
+            Err(EncodingError::InvalidInput(msg)) if msg.starts_with("Checked token is not reachable from input token")
+        ));
+    }
+
     #[test]
     fn test_validate_path_wrap_eth_given_token() {
         let validator = SplitSwapValidator;
@@ -578,6 +1077,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            amount_in: None,
         }];
 
         let result = validator.validate_swap_path(
@@ -607,6 +1107,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: weth.clone(),
             split: 0f64,
+            amount_in: None,
         }];
 
         let result = validator.validate_swap_path(
@@ -678,6 +1179,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            amount_in: None,
         };
 
         let solution = Solution {
@@ -38,6 +38,16 @@ impl TransferOptimization {
         }
     }
 
+    /// Sets whether the input tokens are transferred using Permit2 allowances.
+    pub fn set_permit2(&mut self, permit2: bool) {
+        self.permit2 = permit2;
+    }
+
+    /// Sets whether the input tokens are already held by the router before the first swap.
+    pub fn set_token_in_already_in_router(&mut self, token_in_already_in_router: bool) {
+        self.token_in_already_in_router = token_in_already_in_router;
+    }
+
     /// Returns the transfer method that should be used for the given swap and solution.
     pub fn get_transfer_type(
         &self,
@@ -163,6 +173,7 @@ mod tests {
             token_in: weth(),
             token_out: dai(),
             split: 0f64,
+            amount_in: None,
             swaps: vec![],
         };
         let optimization = TransferOptimization::new(eth(), weth(), true, false, router_address());
@@ -178,6 +189,7 @@ mod tests {
             token_in: weth(),
             token_out: dai(),
             split: 0f64,
+            amount_in: None,
             swaps: vec![],
         };
         let optimization = TransferOptimization::new(eth(), weth(), false, false, router_address());
@@ -194,6 +206,7 @@ mod tests {
             token_in: eth(),
             token_out: dai(),
             split: 0f64,
+            amount_in: None,
             swaps: vec![],
         };
         let optimization = TransferOptimization::new(eth(), weth(), false, false, router_address());
@@ -210,6 +223,7 @@ mod tests {
             token_in: weth(),
             token_out: dai(),
             split: 0f64,
+            amount_in: None,
             swaps: vec![],
         };
         let optimization = TransferOptimization::new(eth(), weth(), false, false, router_address());
@@ -226,6 +240,7 @@ mod tests {
             token_in: usdc(),
             token_out: dai(),
             split: 0f64,
+            amount_in: None,
             swaps: vec![],
         };
         let optimization = TransferOptimization::new(eth(), weth(), false, false, router_address());
@@ -242,6 +257,7 @@ mod tests {
             token_in: usdc(),
             token_out: dai(),
             split: 0f64,
+            amount_in: None,
             swaps: vec![],
         };
         let optimization = TransferOptimization::new(eth(), weth(), false, false, router_address());
@@ -258,6 +274,7 @@ mod tests {
             token_in: usdc(),
             token_out: dai(),
             split: 0f64,
+            amount_in: None,
             swaps: vec![],
         };
         let optimization = TransferOptimization::new(eth(), weth(), false, false, router_address());
@@ -274,6 +291,7 @@ mod tests {
             token_in: usdc(),
             token_out: dai(),
             split: 0f64,
+            amount_in: None,
             swaps: vec![],
         };
         let optimization = TransferOptimization::new(eth(), weth(), false, true, router_address());
@@ -290,6 +308,7 @@ mod tests {
             token_in: usdc(),
             token_out: dai(),
             split: 0f64,
+            amount_in: None,
             swaps: vec![],
         };
         let optimization = TransferOptimization::new(eth(), weth(), false, true, router_address());
@@ -297,6 +316,22 @@ mod tests {
         assert_eq!(transfer_method, TransferType::None);
     }
 
+    #[test]
+    fn test_not_first_swap_native_wrapper_optimization() {
+        // A wrap/unwrap step in the middle of a route. The funds are already in the router
+        let swap = SwapGroup {
+            protocol_system: "native_wrapper".to_string(),
+            token_in: weth(),
+            token_out: eth(),
+            split: 0f64,
+            amount_in: None,
+            swaps: vec![],
+        };
+        let optimization = TransferOptimization::new(eth(), weth(), false, false, router_address());
+        let transfer_method = optimization.get_transfer_type(swap.clone(), usdc(), false, false);
+        assert_eq!(transfer_method, TransferType::None);
+    }
+
     fn receiver() -> Bytes {
         Bytes::from("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2")
     }
@@ -314,6 +349,8 @@ mod tests {
     #[case(Some("uniswap_v3"), router_address(), false)]
     // protocol of next swap does not support transfer in optimization
     #[case(Some("vm:curve"), router_address(), false)]
+    // next swap is a native wrap/unwrap step, which is executed on the router's funds
+    #[case(Some("native_wrapper"), router_address(), false)]
     fn test_get_receiver(
         #[case] protocol: Option<&str>,
         #[case] expected_receiver: Bytes,
@@ -329,6 +366,7 @@ mod tests {
                 token_in: usdc(),
                 token_out: dai(),
                 split: 0f64,
+                amount_in: None,
                 swaps: vec![Swap {
                     component: ProtocolComponent {
                         protocol_system: protocol.unwrap().to_string(),
@@ -338,6 +376,7 @@ mod tests {
                     token_in: usdc(),
                     token_out: dai(),
                     split: 0f64,
+                    amount_in: None,
                 }],
             })
         };
@@ -1,15 +1,38 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "rpc")]
+use crate::encoding::evm::swap_encoder::swap_encoders::{BalancerV2SwapEncoder, CurveSwapEncoder};
 use crate::encoding::{
     errors::EncodingError,
-    evm::swap_encoder::swap_encoders::{
-        BalancerV2SwapEncoder, CurveSwapEncoder, EkuboSwapEncoder, UniswapV2SwapEncoder,
-        UniswapV3SwapEncoder, UniswapV4SwapEncoder,
+    evm::{
+        constants::NATIVE_WRAPPER_PROTOCOL,
+        swap_encoder::swap_encoders::{
+            EkuboSwapEncoder, NativeWrapperSwapEncoder, UniswapV2SwapEncoder, UniswapV3SwapEncoder,
+            UniswapV4SwapEncoder,
+        },
     },
     models::Chain,
     swap_encoder::SwapEncoder,
 };
 
+/// Protocol systems with a swap encoder.
+pub const PROTOCOL_SYSTEMS: [&str; 10] = [
+    "uniswap_v2",
+    "sushiswap_v2",
+    "pancakeswap_v2",
+    "vm:balancer_v2",
+    "uniswap_v3",
+    "pancakeswap_v3",
+    "uniswap_v4",
+    "ekubo_v2",
+    "vm:curve",
+    NATIVE_WRAPPER_PROTOCOL,
+];
+
+/// Protocol systems whose swap encoders need on-chain lookups, and are therefore only available
+/// with the `rpc` feature.
+pub const RPC_PROTOCOL_SYSTEMS: [&str; 2] = ["vm:balancer_v2", "vm:curve"];
+
 /// Builds a `SwapEncoder` for the given protocol system and executor address.
 pub struct SwapEncoderBuilder {
     protocol_system: String,
@@ -50,6 +73,7 @@ impl SwapEncoderBuilder {
                 self.chain,
                 self.config,
             )?)),
+            #[cfg(feature = "rpc")]
             "vm:balancer_v2" => Ok(Box::new(BalancerV2SwapEncoder::new(
                 self.executor_address,
                 self.chain,
@@ -73,9 +97,22 @@ impl SwapEncoderBuilder {
             "ekubo_v2" => {
                 Ok(Box::new(EkuboSwapEncoder::new(self.executor_address, self.chain, self.config)?))
             }
+            #[cfg(feature = "rpc")]
             "vm:curve" => {
                 Ok(Box::new(CurveSwapEncoder::new(self.executor_address, self.chain, self.config)?))
             }
+            NATIVE_WRAPPER_PROTOCOL => Ok(Box::new(NativeWrapperSwapEncoder::new(
+                self.executor_address,
+                self.chain,
+                self.config,
+            )?)),
+            #[cfg(not(feature = "rpc"))]
+            protocol_system if RPC_PROTOCOL_SYSTEMS.contains(&protocol_system) => {
+                Err(EncodingError::FatalError(format!(
+                    "Encoding swaps on {} needs on-chain lookups, enable the `rpc` feature",
+                    protocol_system
+                )))
+            }
             _ => Err(EncodingError::FatalError(format!(
                 "Unknown protocol system: {}",
                 self.protocol_system
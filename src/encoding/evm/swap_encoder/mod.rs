@@ -1,3 +1,3 @@
-mod builder;
+pub(crate) mod builder;
 pub mod swap_encoder_registry;
 mod swap_encoders;
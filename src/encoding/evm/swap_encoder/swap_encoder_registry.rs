@@ -1,10 +1,13 @@
 use std::{collections::HashMap, fs};
 
+#[cfg(not(feature = "rpc"))]
+use crate::encoding::evm::swap_encoder::builder::RPC_PROTOCOL_SYSTEMS;
 use crate::encoding::{
     errors::EncodingError,
     evm::{
         constants::{DEFAULT_EXECUTORS_JSON, PROTOCOL_SPECIFIC_CONFIG},
         swap_encoder::builder::SwapEncoderBuilder,
+        utils::parse_configured_address,
     },
     models::Chain,
     swap_encoder::SwapEncoder,
@@ -20,21 +23,28 @@ pub struct SwapEncoderRegistry {
 impl SwapEncoderRegistry {
     /// Populates the registry with the `SwapEncoders` for the given blockchain by parsing the
     /// executors' addresses in the file at the given path.
+    ///
+    /// Malformed executor addresses and the zero address are rejected. Without the `rpc` feature,
+    /// the protocols whose encoders need on-chain lookups are skipped.
     pub fn new(executors_file_path: Option<String>, chain: Chain) -> Result<Self, EncodingError> {
-        let config_str = if let Some(ref path) = executors_file_path {
-            fs::read_to_string(path).map_err(|e| {
-                EncodingError::FatalError(format!(
-                    "Error reading executors file from {:?}: {}",
-                    executors_file_path, e
-                ))
-            })?
-        } else {
-            DEFAULT_EXECUTORS_JSON.to_string()
-        };
-        let config: HashMap<String, HashMap<String, String>> = serde_json::from_str(&config_str)?;
-        let executors = config
-            .get(&chain.name)
-            .ok_or(EncodingError::FatalError("No executors found for chain".to_string()))?;
+        Self::from_executors_file(executors_file_path, chain, true)
+    }
+
+    /// Populates the registry like `new`, but without checking the executors' addresses. This is
+    /// only meant for local setups, e.g. with placeholder addresses.
+    pub fn new_unchecked(
+        executors_file_path: Option<String>,
+        chain: Chain,
+    ) -> Result<Self, EncodingError> {
+        Self::from_executors_file(executors_file_path, chain, false)
+    }
+
+    fn from_executors_file(
+        executors_file_path: Option<String>,
+        chain: Chain,
+        check_addresses: bool,
+    ) -> Result<Self, EncodingError> {
+        let executors = get_executor_addresses(executors_file_path, &chain)?;
 
         let protocol_specific_config: HashMap<String, HashMap<String, HashMap<String, String>>> =
             serde_json::from_str(PROTOCOL_SPECIFIC_CONFIG)?;
@@ -44,7 +54,17 @@ impl SwapEncoderRegistry {
                 "No protocol specific config found for chain".to_string(),
             ))?;
         let mut encoders = HashMap::new();
-        for (protocol, executor_address) in executors {
+        for (protocol, executor_address) in executors.iter() {
+            #[cfg(not(feature = "rpc"))]
+            if RPC_PROTOCOL_SYSTEMS.contains(&protocol.as_str()) {
+                continue;
+            }
+            if check_addresses {
+                parse_configured_address(
+                    &format!("executor address of {}", protocol),
+                    executor_address,
+                )?;
+            }
             let builder = SwapEncoderBuilder::new(
                 protocol,
                 executor_address,
@@ -65,3 +85,32 @@ impl SwapEncoderRegistry {
         self.encoders.get(protocol_system)
     }
 }
+
+/// Returns the executor address of each protocol for the given chain, read from the file at the
+/// given path or from the default executors file.
+pub fn get_executor_addresses(
+    executors_file_path: Option<String>,
+    chain: &Chain,
+) -> Result<HashMap<String, String>, EncodingError> {
+    read_executors_file(executors_file_path)?
+        .remove(&chain.name)
+        .ok_or(EncodingError::FatalError("No executors found for chain".to_string()))
+}
+
+/// Returns the executor address of each protocol for each chain, by chain name, read from the file
+/// at the given path or from the default executors file.
+pub fn read_executors_file(
+    executors_file_path: Option<String>,
+) -> Result<HashMap<String, HashMap<String, String>>, EncodingError> {
+    let config_str = if let Some(ref path) = executors_file_path {
+        fs::read_to_string(path).map_err(|e| {
+            EncodingError::FatalError(format!(
+                "Error reading executors file from {:?}: {}",
+                executors_file_path, e
+            ))
+        })?
+    } else {
+        DEFAULT_EXECUTORS_JSON.to_string()
+    };
+    Ok(serde_json::from_str(&config_str)?)
+}
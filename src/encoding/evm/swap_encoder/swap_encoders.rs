@@ -1,23 +1,27 @@
 use std::{collections::HashMap, str::FromStr};
 
+#[cfg(feature = "rpc")]
 use alloy::{
     providers::Provider,
     rpc::types::{TransactionInput, TransactionRequest},
 };
-use alloy_primitives::{Address, Bytes as AlloyBytes, TxKind, U256, U8};
+use alloy_primitives::Address;
+#[cfg(feature = "rpc")]
+use alloy_primitives::{Bytes as AlloyBytes, TxKind, U256, U8};
 use alloy_sol_types::SolValue;
+#[cfg(feature = "rpc")]
 use tokio::task::block_in_place;
 use tycho_common::Bytes;
 
+#[cfg(feature = "rpc")]
+use crate::encoding::evm::{
+    approvals::protocol_approvals_manager::ProtocolApprovalsManager,
+    utils,
+    utils::{encode_input, get_runtime},
+};
 use crate::encoding::{
     errors::EncodingError,
-    evm::{
-        approvals::protocol_approvals_manager::ProtocolApprovalsManager,
-        utils,
-        utils::{
-            bytes_to_address, encode_input, get_runtime, get_static_attribute, pad_to_fixed_size,
-        },
-    },
+    evm::utils::{bytes_to_address, get_static_attribute, pad_to_fixed_size},
     models::{Chain, EncodingContext, Swap},
     swap_encoder::SwapEncoder,
 };
@@ -228,12 +232,16 @@ impl SwapEncoder for UniswapV4SwapEncoder {
 /// # Fields
 /// * `executor_address` - The address of the executor contract that will perform the swap.
 /// * `vault_address` - The address of the vault contract that will perform the swap.
+///
+/// Only available with the `rpc` feature, since the vault's token approvals are checked on-chain.
+#[cfg(feature = "rpc")]
 #[derive(Clone)]
 pub struct BalancerV2SwapEncoder {
     executor_address: String,
     vault_address: String,
 }
 
+#[cfg(feature = "rpc")]
 impl SwapEncoder for BalancerV2SwapEncoder {
     fn new(
         executor_address: String,
@@ -372,6 +380,10 @@ impl SwapEncoder for EkuboSwapEncoder {
 ///   indexes.
 /// * `native_token_curve_address` - The address used as native token in curve pools.
 /// * `native_token_address` - The address of the native token.
+///
+/// Only available with the `rpc` feature, since the coin indexes and token approvals are fetched
+/// on-chain.
+#[cfg(feature = "rpc")]
 #[derive(Clone)]
 pub struct CurveSwapEncoder {
     executor_address: String,
@@ -381,6 +393,7 @@ pub struct CurveSwapEncoder {
     wrapped_native_token_address: Bytes,
 }
 
+#[cfg(feature = "rpc")]
 impl CurveSwapEncoder {
     fn get_pool_type(&self, pool_id: &str, factory_address: &str) -> Result<U8, EncodingError> {
         match pool_id {
@@ -483,6 +496,7 @@ impl CurveSwapEncoder {
     }
 }
 
+#[cfg(feature = "rpc")]
 impl SwapEncoder for CurveSwapEncoder {
     fn new(
         executor_address: String,
@@ -592,6 +606,67 @@ impl SwapEncoder for CurveSwapEncoder {
     }
 }
 
+/// Encodes the conversion between the chain's native token and its wrapped token, as a step of a
+/// route, through the given executor address.
+///
+/// # Fields
+/// * `executor_address` - The address of the executor contract that will perform the conversion.
+/// * `native_token_address` - The address of the native token.
+/// * `wrapped_native_token_address` - The address of the wrapped native token.
+#[derive(Clone)]
+pub struct NativeWrapperSwapEncoder {
+    executor_address: String,
+    native_token_address: Bytes,
+    wrapped_native_token_address: Bytes,
+}
+
+impl SwapEncoder for NativeWrapperSwapEncoder {
+    fn new(
+        executor_address: String,
+        chain: Chain,
+        _config: Option<HashMap<String, String>>,
+    ) -> Result<Self, EncodingError> {
+        Ok(Self {
+            executor_address,
+            native_token_address: chain.native_token()?,
+            wrapped_native_token_address: chain.wrapped_token()?,
+        })
+    }
+
+    fn encode_swap(
+        &self,
+        swap: Swap,
+        encoding_context: EncodingContext,
+    ) -> Result<Vec<u8>, EncodingError> {
+        let is_wrap = swap.token_in == self.native_token_address &&
+            swap.token_out == self.wrapped_native_token_address;
+        let is_unwrap = swap.token_in == self.wrapped_native_token_address &&
+            swap.token_out == self.native_token_address;
+        if !is_wrap && !is_unwrap {
+            return Err(EncodingError::InvalidInput(format!(
+                "Native wrapper can only convert between {} and {}",
+                self.native_token_address, self.wrapped_native_token_address
+            )));
+        }
+
+        let args = (
+            bytes_to_address(&swap.token_in)?,
+            bytes_to_address(&encoding_context.receiver)?,
+            (encoding_context.transfer_type as u8).to_be_bytes(),
+        );
+
+        Ok(args.abi_encode_packed())
+    }
+
+    fn executor_address(&self) -> &str {
+        &self.executor_address
+    }
+
+    fn clone_box(&self) -> Box<dyn SwapEncoder> {
+        Box::new(self.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -622,6 +697,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let encoding_context = EncodingContext {
                 receiver: Bytes::from("0x0000000000000000000000000000000000000001"),
@@ -680,6 +756,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let encoding_context = EncodingContext {
                 receiver: Bytes::from("0x0000000000000000000000000000000000000001"),
@@ -721,6 +798,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "rpc")]
     mod balancer_v2 {
         use super::*;
 
@@ -740,6 +818,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let encoding_context = EncodingContext {
                 // The receiver was generated with `makeAddr("bob") using forge`
@@ -810,6 +889,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let encoding_context = EncodingContext {
                 // The receiver is ALICE to match the solidity tests
@@ -882,6 +962,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let encoding_context = EncodingContext {
@@ -978,6 +1059,7 @@ mod tests {
                 token_in: usde_address.clone(),
                 token_out: usdt_address.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let second_swap = Swap {
@@ -985,6 +1067,7 @@ mod tests {
                 token_in: usdt_address,
                 token_out: wbtc_address.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let encoder = UniswapV4SwapEncoder::new(
@@ -1061,6 +1144,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let encoding_context = EncodingContext {
@@ -1133,6 +1217,7 @@ mod tests {
                 token_in: group_token_in.clone(),
                 token_out: intermediary_token.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let second_swap = Swap {
@@ -1148,6 +1233,7 @@ mod tests {
                 token_in: intermediary_token.clone(),
                 token_out: group_token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let first_encoded_swap = encoder
@@ -1185,6 +1271,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "rpc")]
     mod curve {
         use rstest::rstest;
 
@@ -1303,6 +1390,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let encoding_context = EncodingContext {
                 // The receiver was generated with `makeAddr("bob") using forge`
@@ -1373,6 +1461,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let encoding_context = EncodingContext {
                 // The receiver was generated with `makeAddr("bob") using forge`
@@ -1444,6 +1533,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
             let encoding_context = EncodingContext {
                 // The receiver was generated with `makeAddr("bob") using forge`
@@ -1499,4 +1589,87 @@ mod tests {
             );
         }
     }
+
+    mod native_wrapper {
+        use super::*;
+
+        #[test]
+        fn test_encode_native_wrapper_unwrap() {
+            let token_in = Bytes::from("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+            let token_out = Bytes::from("0x0000000000000000000000000000000000000000");
+            let swap = Swap {
+                component: ProtocolComponent {
+                    id: String::from("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+                    protocol_system: String::from("native_wrapper"),
+                    ..Default::default()
+                },
+                token_in: token_in.clone(),
+                token_out: token_out.clone(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let encoding_context = EncodingContext {
+                receiver: Bytes::from("0x0000000000000000000000000000000000000001"),
+                exact_out: false,
+                router_address: Some(Bytes::zero(20)),
+                group_token_in: token_in.clone(),
+                group_token_out: token_out.clone(),
+                transfer_type: TransferType::None,
+            };
+            let encoder = NativeWrapperSwapEncoder::new(
+                String::from("0xA4AD4f68d0b91CFD19687c881e50f3A00242828c"),
+                TychoCoreChain::Ethereum.into(),
+                None,
+            )
+            .unwrap();
+            let encoded_swap = encoder
+                .encode_swap(swap, encoding_context)
+                .unwrap();
+            let hex_swap = encode(&encoded_swap);
+            assert_eq!(
+                hex_swap,
+                String::from(concat!(
+                    // in token
+                    "c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    // receiver
+                    "0000000000000000000000000000000000000001",
+                    // transfer type (none)
+                    "05",
+                ))
+            );
+        }
+
+        #[test]
+        fn test_encode_native_wrapper_invalid_tokens() {
+            let token_in = Bytes::from("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+            let token_out = Bytes::from("0x6b175474e89094c44da98b954eedeac495271d0f");
+            let swap = Swap {
+                component: ProtocolComponent {
+                    id: String::from("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+                    protocol_system: String::from("native_wrapper"),
+                    ..Default::default()
+                },
+                token_in: token_in.clone(),
+                token_out: token_out.clone(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let encoding_context = EncodingContext {
+                receiver: Bytes::from("0x0000000000000000000000000000000000000001"),
+                exact_out: false,
+                router_address: Some(Bytes::zero(20)),
+                group_token_in: token_in.clone(),
+                group_token_out: token_out.clone(),
+                transfer_type: TransferType::None,
+            };
+            let encoder = NativeWrapperSwapEncoder::new(
+                String::from("0xA4AD4f68d0b91CFD19687c881e50f3A00242828c"),
+                TychoCoreChain::Ethereum.into(),
+                None,
+            )
+            .unwrap();
+            let result = encoder.encode_swap(swap, encoding_context);
+            assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+        }
+    }
 }
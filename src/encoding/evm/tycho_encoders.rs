@@ -1,36 +1,117 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{collections::HashSet, fmt, str::FromStr};
 
+use alloy_primitives::{Bytes as AlloyBytes, U256};
+use alloy_sol_types::SolValue;
+use chrono::Utc;
 use num_bigint::BigUint;
-use tycho_common::Bytes;
+use tycho_common::{models::protocol::ProtocolComponent, Bytes};
 
 use crate::encoding::{
     errors::EncodingError,
     evm::{
+        approvals::permit2::Permit2,
+        constants::NATIVE_WRAPPER_PROTOCOL,
         group_swaps::group_swaps,
-        strategy_encoder::strategy_encoders::{
-            SequentialSwapStrategyEncoder, SingleSwapStrategyEncoder, SplitSwapStrategyEncoder,
+        route_optimizer::optimize_solution,
+        strategy_encoder::{
+            actions::{encode_swap_with_actions, get_swap_solution, has_swap_actions},
+            strategy_encoders::{
+                SequentialSwapStrategyEncoder, SingleSwapStrategyEncoder, SplitSwapStrategyEncoder,
+            },
+            strategy_validators::{SplitSwapValidator, SwapValidator},
+            transfer_optimizations::TransferOptimization,
         },
         swap_encoder::swap_encoder_registry::SwapEncoderRegistry,
+        utils::{encode_executor_swap, encode_input, get_wrap_unwrap},
+    },
+    models::{
+        Chain, EncodedSolution, EncodingContext, ExecutorCall, FundingMode, NativeAction, Solution,
+        SolutionReport, Swap, SwapGroupReport, Transaction, TransferType,
     },
-    models::{Chain, EncodingContext, NativeAction, Solution, Transaction, TransferType},
     strategy_encoder::StrategyEncoder,
     tycho_encoder::TychoEncoder,
 };
 
+/// Maximum time between encoding a solution and its deadline, set to 1 year (in seconds). Later
+/// deadlines are most likely not expressed in seconds.
+const MAX_DEADLINE_OFFSET: u64 = 365 * 24 * 60 * 60;
+
+/// The swap strategies of the TychoRouter.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum SwapStrategy {
+    Single,
+    Sequential,
+    Split,
+}
+
+impl fmt::Display for SwapStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapStrategy::Single => write!(f, "single"),
+            SwapStrategy::Sequential => write!(f, "sequential"),
+            SwapStrategy::Split => write!(f, "split"),
+        }
+    }
+}
+
+/// Returns the most appropriate swap strategy to encode the given solution.
+pub(crate) fn get_swap_strategy(solution: &Solution) -> SwapStrategy {
+    let has_absolute_amounts = solution
+        .swaps
+        .iter()
+        .any(|swap| swap.amount_in.is_some());
+    if has_absolute_amounts ||
+        !solution.additional_outputs.is_empty() ||
+        !solution.additional_inputs.is_empty()
+    {
+        // Only the split swap strategy supports absolute swap amounts and multiple inputs or
+        // outputs
+        SwapStrategy::Split
+    } else if solution.swaps.len() == 1 {
+        SwapStrategy::Single
+    } else if solution
+        .swaps
+        .iter()
+        .all(|swap| swap.split == 0.0)
+    {
+        SwapStrategy::Sequential
+    } else {
+        SwapStrategy::Split
+    }
+}
+
 /// Encodes solutions to be used by the TychoRouter.
 ///
 /// # Fields
 /// * `single_swap_strategy`: Encoder for single swaps
 /// * `sequential_swap_strategy`: Encoder for sequential swaps
 /// * `split_swap_strategy`: Encoder for split swaps
+/// * `atomic_single_swap_strategy`: Encoder for single swaps that are part of an atomic batch
+/// * `atomic_sequential_swap_strategy`: Encoder for sequential swaps that are part of an atomic
+///   batch
+/// * `atomic_split_swap_strategy`: Encoder for split swaps that are part of an atomic batch
+/// * `permit2`: Permit2, responsible for signing the approvals of an atomic batch
+/// * `router_address`: Address of the router to be used to execute swaps
 /// * `native_address`: Address of the chain's native token
 /// * `wrapped_address`: Address of the chain's wrapped native token
+/// * `token_in_already_in_router`: Whether the input tokens are already in the router
+/// * `optimize_routes`: Whether to reorder and clean up the swaps of a solution before encoding it
+/// * `native_wrapper_supported`: Whether a `native_wrapper` executor is configured, which is needed
+///   to insert native wrap/unwrap steps in a route
 pub struct TychoRouterEncoder {
     single_swap_strategy: SingleSwapStrategyEncoder,
     sequential_swap_strategy: SequentialSwapStrategyEncoder,
     split_swap_strategy: SplitSwapStrategyEncoder,
+    atomic_single_swap_strategy: SingleSwapStrategyEncoder,
+    atomic_sequential_swap_strategy: SequentialSwapStrategyEncoder,
+    atomic_split_swap_strategy: SplitSwapStrategyEncoder,
+    permit2: Option<Permit2>,
+    router_address: Bytes,
     native_address: Bytes,
     wrapped_address: Bytes,
+    token_in_already_in_router: bool,
+    optimize_routes: bool,
+    native_wrapper_supported: bool,
 }
 
 impl TychoRouterEncoder {
@@ -40,9 +121,45 @@ impl TychoRouterEncoder {
         swapper_pk: Option<String>,
         router_address: Bytes,
         token_in_already_in_router: bool,
+        optimize_routes: bool,
     ) -> Result<Self, EncodingError> {
         let native_address = chain.native_token()?;
         let wrapped_address = chain.wrapped_token()?;
+        let native_wrapper_supported = swap_encoder_registry
+            .get_encoder(NATIVE_WRAPPER_PROTOCOL)
+            .is_some();
+        // The calls of an atomic batch never sign their own approvals: a single Permit2 batch
+        // signature covers all of them.
+        let mut atomic_single_swap_strategy = SingleSwapStrategyEncoder::new(
+            chain.clone(),
+            swap_encoder_registry.clone(),
+            None,
+            router_address.clone(),
+            token_in_already_in_router,
+        )?;
+        let mut atomic_sequential_swap_strategy = SequentialSwapStrategyEncoder::new(
+            chain.clone(),
+            swap_encoder_registry.clone(),
+            None,
+            router_address.clone(),
+            token_in_already_in_router,
+        )?;
+        let mut atomic_split_swap_strategy = SplitSwapStrategyEncoder::new(
+            chain.clone(),
+            swap_encoder_registry.clone(),
+            None,
+            router_address.clone(),
+            token_in_already_in_router,
+        )?;
+        let permit2 = if let Some(swapper_pk) = swapper_pk.clone() {
+            atomic_single_swap_strategy = atomic_single_swap_strategy.with_permit2_transfers();
+            atomic_sequential_swap_strategy =
+                atomic_sequential_swap_strategy.with_permit2_transfers();
+            atomic_split_swap_strategy = atomic_split_swap_strategy.with_permit2_transfers();
+            Some(Permit2::new(swapper_pk, chain.clone())?)
+        } else {
+            None
+        };
         Ok(TychoRouterEncoder {
             single_swap_strategy: SingleSwapStrategyEncoder::new(
                 chain.clone(),
@@ -61,51 +178,508 @@ impl TychoRouterEncoder {
             split_swap_strategy: SplitSwapStrategyEncoder::new(
                 chain,
                 swap_encoder_registry,
-                None,
+                swapper_pk,
                 router_address.clone(),
                 token_in_already_in_router,
             )?,
+            atomic_single_swap_strategy,
+            atomic_sequential_swap_strategy,
+            atomic_split_swap_strategy,
+            permit2,
+            router_address,
             native_address,
             wrapped_address,
+            token_in_already_in_router,
+            optimize_routes,
+            native_wrapper_supported,
         })
     }
-}
 
-impl TychoEncoder for TychoRouterEncoder {
-    fn encode_calldata(&self, solutions: Vec<Solution>) -> Result<Vec<Transaction>, EncodingError> {
-        let mut transactions: Vec<Transaction> = Vec::new();
-        for solution in solutions.iter() {
-            self.validate_solution(solution)?;
-            let (contract_interaction, target_address) = if solution.swaps.len() == 1 {
-                self.single_swap_strategy
-                    .encode_strategy(solution.clone())?
-            } else if solution
-                .swaps
-                .iter()
-                .all(|swap| swap.split == 0.0)
+    /// Inserts a native wrap/unwrap step between consecutive swaps of a sequential solution when
+    /// one swap outputs the native token and the next one takes the wrapped token as input, or
+    /// vice versa.
+    ///
+    /// Raises an `EncodingError` if such a step is needed but no `native_wrapper` executor is
+    /// configured for the chain.
+    fn insert_native_wrapper_steps(&self, solution: &Solution) -> Result<Solution, EncodingError> {
+        let is_sequential = solution
+            .swaps
+            .iter()
+            .all(|swap| swap.split == 0.0 && swap.amount_in.is_none());
+        if !is_sequential || solution.swaps.len() < 2 {
+            return Ok(solution.clone());
+        }
+
+        let mut swaps: Vec<Swap> = Vec::with_capacity(solution.swaps.len());
+        for (i, swap) in solution.swaps.iter().enumerate() {
+            if let Some(previous) = i
+                .checked_sub(1)
+                .map(|j| &solution.swaps[j])
             {
-                self.sequential_swap_strategy
-                    .encode_strategy(solution.clone())?
-            } else {
-                self.split_swap_strategy
-                    .encode_strategy(solution.clone())?
+                let native_to_wrapped = previous.token_out == self.native_address &&
+                    swap.token_in == self.wrapped_address;
+                let wrapped_to_native = previous.token_out == self.wrapped_address &&
+                    swap.token_in == self.native_address;
+                if native_to_wrapped || wrapped_to_native {
+                    if !self.native_wrapper_supported {
+                        return Err(EncodingError::InvalidInput(format!(
+                            "Swap to {} is followed by a swap from {}, which needs a native wrap or unwrap step, but no {} executor is configured",
+                            previous.token_out, swap.token_in, NATIVE_WRAPPER_PROTOCOL
+                        )));
+                    }
+                    swaps.push(Swap {
+                        component: ProtocolComponent {
+                            id: self.wrapped_address.to_string(),
+                            protocol_system: NATIVE_WRAPPER_PROTOCOL.to_string(),
+                            ..Default::default()
+                        },
+                        token_in: previous.token_out.clone(),
+                        token_out: swap.token_in.clone(),
+                        split: 0f64,
+                        amount_in: None,
+                    });
+                }
+            }
+            swaps.push(swap.clone());
+        }
+        Ok(Solution { swaps, ..solution.clone() })
+    }
+
+    /// Checks the parameters of an action that sends funds out of the router.
+    fn validate_action_params(action: &NativeAction) -> Result<(), EncodingError> {
+        match action {
+            NativeAction::Distribute { recipients } => {
+                if recipients.is_empty() {
+                    return Err(EncodingError::FatalError(
+                        "Distribute action must have at least one recipient".to_string(),
+                    ));
+                }
+                for recipient in recipients.iter() {
+                    if recipient.recipient.len() != 20 ||
+                        recipient
+                            .recipient
+                            .iter()
+                            .all(|b| *b == 0)
+                    {
+                        return Err(EncodingError::FatalError(format!(
+                            "Invalid distribute recipient: {:?}",
+                            recipient.recipient
+                        )));
+                    }
+                }
+                let total_bps: u32 = recipients
+                    .iter()
+                    .map(|recipient| recipient.bps as u32)
+                    .sum();
+                if total_bps != 10_000 {
+                    return Err(EncodingError::FatalError(format!(
+                        "Distribute shares must add up to 10000 bps, got {}",
+                        total_bps
+                    )));
+                }
+            }
+            NativeAction::Call { target, .. } | NativeAction::VaultDeposit { vault: target } => {
+                if target.len() != 20 || target.iter().all(|b| *b == 0) {
+                    return Err(EncodingError::FatalError(format!(
+                        "Invalid action target: {:?}",
+                        target
+                    )));
+                }
+            }
+            NativeAction::Wrap | NativeAction::Unwrap => {}
+        }
+        Ok(())
+    }
+
+    /// Encodes the swaps of a solution with the most appropriate of the given strategies.
+    fn encode_swaps(
+        solution: &Solution,
+        single_swap_strategy: &SingleSwapStrategyEncoder,
+        sequential_swap_strategy: &SequentialSwapStrategyEncoder,
+        split_swap_strategy: &SplitSwapStrategyEncoder,
+    ) -> Result<(Vec<u8>, Bytes), EncodingError> {
+        match get_swap_strategy(solution) {
+            SwapStrategy::Single => single_swap_strategy.encode_strategy(solution.clone()),
+            SwapStrategy::Sequential => sequential_swap_strategy.encode_strategy(solution.clone()),
+            SwapStrategy::Split => split_swap_strategy.encode_strategy(solution.clone()),
+        }
+    }
+
+    /// Prepares a solution for encoding: optionally optimizes its route and inserts the native
+    /// wrapper steps it needs.
+    fn prepare_solution(&self, solution: &Solution) -> Result<Solution, EncodingError> {
+        let solution =
+            if self.optimize_routes { optimize_solution(solution) } else { solution.clone() };
+        self.insert_native_wrapper_steps(&solution)
+    }
+
+    /// Runs the validations of the swap strategy on the swaps of the solution, and returns all the
+    /// errors found instead of only the first one.
+    fn validate_swaps(&self, solution: &Solution, strategy: &SwapStrategy) -> Vec<EncodingError> {
+        // The path validations are shared by all strategies
+        let validator = SplitSwapValidator;
+        let mut results = vec![validator.validate_solution_min_amounts(solution)];
+        if *strategy == SwapStrategy::Split {
+            results.push(validator.validate_split_percentages(&solution.swaps));
+            results.push(validator.validate_split_amounts(
+                &solution.swaps,
+                &solution.given_token,
+                &solution.given_amount,
+                &solution.native_action,
+                &self.native_address,
+                &self.wrapped_address,
+            ));
+        }
+        if solution.additional_inputs.is_empty() {
+            results.push(validator.validate_swap_path(
+                &solution.swaps,
+                &solution.given_token,
+                &solution.checked_token,
+                &solution.native_action,
+                &self.native_address,
+                &self.wrapped_address,
+            ));
+        } else {
+            for input in solution.additional_inputs.iter() {
+                results.push(validator.validate_split_amounts(
+                    &solution.swaps,
+                    &input.token,
+                    &input.amount,
+                    &None,
+                    &self.native_address,
+                    &self.wrapped_address,
+                ));
+            }
+            let input_tokens: Vec<Bytes> = std::iter::once(solution.given_token.clone())
+                .chain(
+                    solution
+                        .additional_inputs
+                        .iter()
+                        .map(|input| input.token.clone()),
+                )
+                .collect();
+            results.push(validator.validate_input_tokens(
+                &solution.swaps,
+                &input_tokens,
+                &solution.checked_token,
+            ));
+        }
+        if !solution.additional_outputs.is_empty() {
+            let output_tokens: Vec<Bytes> = std::iter::once(solution.checked_token.clone())
+                .chain(
+                    solution
+                        .additional_outputs
+                        .iter()
+                        .map(|output| output.token.clone()),
+                )
+                .collect();
+            results.push(validator.validate_output_tokens(&solution.swaps, &output_tokens));
+        }
+        results
+            .into_iter()
+            .filter_map(Result::err)
+            .collect()
+    }
+
+    /// Returns the swap groups of the solution with the transfer type and receiver the swap
+    /// strategy would encode them with.
+    fn get_swap_group_reports(
+        &self,
+        solution: &Solution,
+        strategy: &SwapStrategy,
+        token_in_already_in_router: bool,
+    ) -> Result<Vec<SwapGroupReport>, EncodingError> {
+        // The transfers are the ones of the strategy encoding the solution
+        let mut transfer_optimization = match strategy {
+            SwapStrategy::Single => self
+                .single_swap_strategy
+                .transfer_optimization(),
+            SwapStrategy::Sequential => self
+                .sequential_swap_strategy
+                .transfer_optimization(),
+            SwapStrategy::Split => self
+                .split_swap_strategy
+                .transfer_optimization(),
+        }
+        .clone();
+        if token_in_already_in_router {
+            transfer_optimization.set_token_in_already_in_router(true);
+        }
+        let wrap = solution.native_action == Some(NativeAction::Wrap);
+        let unwrap = solution.native_action == Some(NativeAction::Unwrap);
+        // The router holds the output of settled solutions, to distribute it
+        let is_settled = solution.fee.is_some() || solution.surplus.is_some();
+        let input_tokens: Vec<&Bytes> = std::iter::once(&solution.given_token)
+            .chain(
+                solution
+                    .additional_inputs
+                    .iter()
+                    .map(|input| &input.token),
+            )
+            .collect();
+
+        let grouped_swaps = group_swaps(solution.swaps.clone());
+        let mut reports = vec![];
+        let mut in_between_swap_optimization_allowed = true;
+        for (i, grouped_swap) in grouped_swaps.iter().enumerate() {
+            let (transfer_type, receiver): (TransferType, Bytes) = match strategy {
+                SwapStrategy::Single => {
+                    let receiver = if !unwrap && !is_settled {
+                        solution.receiver.clone()
+                    } else {
+                        self.router_address.clone()
+                    };
+                    let transfer_type = transfer_optimization.get_transfer_type(
+                        grouped_swap.clone(),
+                        solution.given_token.clone(),
+                        wrap,
+                        false,
+                    );
+                    (transfer_type, receiver)
+                }
+                SwapStrategy::Sequential => {
+                    let final_receiver = if is_settled {
+                        self.router_address.clone()
+                    } else {
+                        solution.receiver.clone()
+                    };
+                    let transfer_type = transfer_optimization.get_transfer_type(
+                        grouped_swap.clone(),
+                        solution.given_token.clone(),
+                        wrap,
+                        in_between_swap_optimization_allowed,
+                    );
+                    let (receiver, next_swap_optimization) = transfer_optimization
+                        .get_receiver(final_receiver, grouped_swaps.get(i + 1))?;
+                    in_between_swap_optimization_allowed = next_swap_optimization;
+                    (transfer_type, receiver)
+                }
+                SwapStrategy::Split => {
+                    let additional_output = solution
+                        .additional_outputs
+                        .iter()
+                        .find(|output| output.token == grouped_swap.token_out);
+                    let receiver = if let Some(output) = additional_output {
+                        output.receiver.clone()
+                    } else if !unwrap &&
+                        !is_settled &&
+                        grouped_swap.token_out == solution.checked_token
+                    {
+                        solution.receiver.clone()
+                    } else {
+                        self.router_address.clone()
+                    };
+                    let swap_given_token = if input_tokens.contains(&&grouped_swap.token_in) {
+                        grouped_swap.token_in.clone()
+                    } else {
+                        solution.given_token.clone()
+                    };
+                    let transfer_type = transfer_optimization.get_transfer_type(
+                        grouped_swap.clone(),
+                        swap_given_token,
+                        wrap,
+                        false,
+                    );
+                    (transfer_type, receiver)
+                }
             };
+            reports.push(SwapGroupReport {
+                protocol_system: grouped_swap.protocol_system.clone(),
+                component_ids: grouped_swap
+                    .swaps
+                    .iter()
+                    .map(|swap| swap.component.id.clone())
+                    .collect(),
+                token_in: grouped_swap.token_in.clone(),
+                token_out: grouped_swap.token_out.clone(),
+                split: grouped_swap.split,
+                amount_in: grouped_swap.amount_in.clone(),
+                transfer_type,
+                receiver,
+            });
+        }
+        Ok(reports)
+    }
 
-            let value = if solution.given_token == self.native_address {
-                solution.given_amount.clone()
+    /// Encodes a solution with the most appropriate of the given strategies.
+    fn encode_solution(
+        &self,
+        solution: &Solution,
+        single_swap_strategy: &SingleSwapStrategyEncoder,
+        sequential_swap_strategy: &SequentialSwapStrategyEncoder,
+        split_swap_strategy: &SplitSwapStrategyEncoder,
+    ) -> Result<Transaction, EncodingError> {
+        let solution = &self.prepare_solution(solution)?;
+        self.validate_solution(solution)?;
+        let (contract_interaction, target_address) = if has_swap_actions(solution) {
+            let swap_solution = get_swap_solution(
+                solution,
+                &self.router_address,
+                &self.native_address,
+                &self.wrapped_address,
+            )?;
+            let (swap_call, target_address) = if solution.pre_swap_actions.is_empty() {
+                Self::encode_swaps(
+                    &swap_solution,
+                    single_swap_strategy,
+                    sequential_swap_strategy,
+                    split_swap_strategy,
+                )?
             } else {
-                BigUint::ZERO
+                if self.permit2.is_some() {
+                    return Err(EncodingError::InvalidInput(
+                        "Pre-swap actions are not supported with Permit2 approvals".to_string(),
+                    ));
+                }
+                // The router holds the input of the swaps after executing the pre-swap actions
+                Self::encode_swaps(
+                    &swap_solution,
+                    &single_swap_strategy
+                        .clone()
+                        .with_token_in_already_in_router(),
+                    &sequential_swap_strategy
+                        .clone()
+                        .with_token_in_already_in_router(),
+                    &split_swap_strategy
+                        .clone()
+                        .with_token_in_already_in_router(),
+                )?
             };
+            (encode_swap_with_actions(solution, swap_call)?, target_address)
+        } else {
+            Self::encode_swaps(
+                solution,
+                single_swap_strategy,
+                sequential_swap_strategy,
+                split_swap_strategy,
+            )?
+        };
 
-            transactions.push(Transaction {
-                value,
-                data: contract_interaction,
-                to: target_address,
-            });
+        let value = if solution.given_token == self.native_address {
+            solution.given_amount.clone()
+        } else {
+            BigUint::ZERO
+        };
+
+        // The router reverts the call if it is executed after the deadline
+        let data = if let Some(deadline) = solution.deadline {
+            encode_input(
+                "executeWithDeadline(uint256,bytes)",
+                (U256::from(deadline), contract_interaction).abi_encode(),
+            )
+        } else {
+            contract_interaction
+        };
+
+        Ok(Transaction { value, data, to: target_address })
+    }
+}
+
+impl TychoEncoder for TychoRouterEncoder {
+    fn encode_calldata(&self, solutions: Vec<Solution>) -> Result<Vec<Transaction>, EncodingError> {
+        let mut transactions: Vec<Transaction> = Vec::new();
+        for solution in solutions.iter() {
+            transactions.push(self.encode_solution(
+                solution,
+                &self.single_swap_strategy,
+                &self.sequential_swap_strategy,
+                &self.split_swap_strategy,
+            )?);
         }
         Ok(transactions)
     }
 
+    fn encode_solutions(
+        &self,
+        solutions: Vec<Solution>,
+    ) -> Vec<Result<EncodedSolution, EncodingError>> {
+        solutions
+            .iter()
+            .map(|solution| {
+                self.encode_solution(
+                    solution,
+                    &self.single_swap_strategy,
+                    &self.sequential_swap_strategy,
+                    &self.split_swap_strategy,
+                )
+                .map(|transaction| EncodedSolution { transaction })
+            })
+            .collect()
+    }
+
+    /// Encodes the solutions as the calls of a single router `multicall`, so that they are all
+    /// executed or all reverted together.
+    ///
+    /// All solutions must share the same sender, and at most one of them can spend the native
+    /// token. If Permit2 is used, the approvals of all the input tokens are aggregated into a
+    /// single batch signature.
+    fn encode_atomic_calldata(
+        &self,
+        solutions: Vec<Solution>,
+    ) -> Result<Transaction, EncodingError> {
+        let first_solution = solutions
+            .first()
+            .ok_or(EncodingError::FatalError("No solutions found".to_string()))?;
+        let sender = first_solution.sender.clone();
+
+        let mut calls = Vec::with_capacity(solutions.len());
+        let mut value = BigUint::ZERO;
+        let mut permit_tokens: Vec<(Bytes, BigUint)> = vec![];
+        for solution in solutions.iter() {
+            if solution.sender != sender {
+                return Err(EncodingError::FatalError(
+                    "All solutions of an atomic batch must have the same sender".to_string(),
+                ));
+            }
+            let transaction = self.encode_solution(
+                solution,
+                &self.atomic_single_swap_strategy,
+                &self.atomic_sequential_swap_strategy,
+                &self.atomic_split_swap_strategy,
+            )?;
+            if transaction.value != BigUint::ZERO {
+                if value != BigUint::ZERO {
+                    return Err(EncodingError::FatalError(
+                        "Only one solution of an atomic batch can spend the native token"
+                            .to_string(),
+                    ));
+                }
+                value = transaction.value;
+            }
+            calls.push(AlloyBytes::from(transaction.data));
+
+            let mut inputs = vec![(solution.given_token.clone(), solution.given_amount.clone())];
+            for input in solution.additional_inputs.iter() {
+                inputs.push((input.token.clone(), input.amount.clone()));
+            }
+            for (token, amount) in inputs {
+                if token == self.native_address {
+                    continue;
+                }
+                match permit_tokens
+                    .iter_mut()
+                    .find(|(permit_token, _)| *permit_token == token)
+                {
+                    Some((_, total_amount)) => *total_amount += amount,
+                    None => permit_tokens.push((token, amount)),
+                }
+            }
+        }
+
+        let data = if let Some(permit2) = &self.permit2 {
+            let (permit_batch, signature) =
+                permit2.get_permit_batch(&self.router_address, &sender, &permit_tokens)?;
+            encode_input(
+                "multicallPermit2(((address,uint160,uint48,uint48)[],address,uint256),bytes,bytes[])",
+                (permit_batch, signature.as_bytes().to_vec(), calls).abi_encode(),
+            )
+        } else {
+            encode_input("multicall(bytes[])", (calls,).abi_encode())
+        };
+
+        Ok(Transaction { value, data, to: self.router_address.clone() })
+    }
+
     /// Raises an `EncodingError` if the solution is not considered valid.
     ///
     /// A solution is considered valid if all the following conditions are met:
@@ -117,6 +691,17 @@ impl TychoEncoder for TychoRouterEncoder {
     ///   swap's output is the chain's wrapped token.
     /// * The token cannot appear more than once in the solution unless it is the first and last
     ///   token (i.e. a true cyclical swap).
+    /// * If the solution has a fee, it is less than 100% and its recipient is a non-zero address.
+    /// * If the solution has a surplus policy, the expected amount is set, the surplus share is
+    ///   between 0% and 100% and its recipient is a non-zero address.
+    /// * If the solution has additional outputs, their tokens are distinct from each other and from
+    ///   the given and checked tokens, their minimum amounts are non-zero, their receivers are
+    ///   non-zero addresses, and the solution has no unwrapping, fee nor surplus policy.
+    /// * If the solution has additional inputs, their tokens are distinct from each other and from
+    ///   the given and checked tokens, none of them is the native token, their amounts are
+    ///   non-zero, and the solution has no native action, additional outputs, fee nor surplus
+    ///   policy.
+    /// * If the solution has a deadline, it is in the future, but less than a year from now.
     fn validate_solution(&self, solution: &Solution) -> Result<(), EncodingError> {
         if solution.exact_out {
             return Err(EncodingError::FatalError(
@@ -126,6 +711,134 @@ impl TychoEncoder for TychoRouterEncoder {
         if solution.swaps.is_empty() {
             return Err(EncodingError::FatalError("No swaps found in solution".to_string()));
         }
+        if !solution.additional_outputs.is_empty() {
+            if solution.fee.is_some() || solution.surplus.is_some() {
+                return Err(EncodingError::FatalError(
+                    "Fees and surplus capture are not supported for solutions with multiple outputs"
+                        .to_string(),
+                ));
+            }
+            if solution.native_action == Some(NativeAction::Unwrap) {
+                return Err(EncodingError::FatalError(
+                    "Unwrapping is not supported for solutions with multiple outputs".to_string(),
+                ));
+            }
+            let mut output_tokens =
+                HashSet::from([solution.given_token.clone(), solution.checked_token.clone()]);
+            for output in solution.additional_outputs.iter() {
+                if !output_tokens.insert(output.token.clone()) {
+                    return Err(EncodingError::FatalError(format!(
+                        "Output token {} must be different from the other tokens of the solution",
+                        output.token
+                    )));
+                }
+                if output.min_amount == BigUint::ZERO {
+                    return Err(EncodingError::FatalError(format!(
+                        "Minimum amount of output token {} must be greater than zero",
+                        output.token
+                    )));
+                }
+                if output.receiver.len() != 20 || output.receiver.iter().all(|b| *b == 0) {
+                    return Err(EncodingError::FatalError(format!(
+                        "Invalid receiver for output token {}: {:?}",
+                        output.token, output.receiver
+                    )));
+                }
+            }
+        }
+        if !solution.additional_inputs.is_empty() {
+            if !solution.additional_outputs.is_empty() {
+                return Err(EncodingError::FatalError(
+                    "Solutions with both multiple inputs and multiple outputs are not supported"
+                        .to_string(),
+                ));
+            }
+            if solution.fee.is_some() || solution.surplus.is_some() {
+                return Err(EncodingError::FatalError(
+                    "Fees and surplus capture are not supported for solutions with multiple inputs"
+                        .to_string(),
+                ));
+            }
+            if solution.native_action.is_some() {
+                return Err(EncodingError::FatalError(
+                    "Wrapping/Unwrapping is not supported for solutions with multiple inputs"
+                        .to_string(),
+                ));
+            }
+            let mut input_tokens =
+                HashSet::from([solution.given_token.clone(), solution.checked_token.clone()]);
+            for input in solution.additional_inputs.iter() {
+                if input.token == self.native_address || solution.given_token == self.native_address
+                {
+                    return Err(EncodingError::FatalError(
+                        "Native token is not supported for solutions with multiple inputs"
+                            .to_string(),
+                    ));
+                }
+                if !input_tokens.insert(input.token.clone()) {
+                    return Err(EncodingError::FatalError(format!(
+                        "Input token {} must be different from the other tokens of the solution",
+                        input.token
+                    )));
+                }
+                if input.amount == BigUint::ZERO {
+                    return Err(EncodingError::FatalError(format!(
+                        "Amount of input token {} must be greater than zero",
+                        input.token
+                    )));
+                }
+            }
+        }
+        if let Some(deadline) = solution.deadline {
+            let now = Utc::now().timestamp() as u64;
+            if deadline <= now {
+                return Err(EncodingError::FatalError(format!(
+                    "Deadline {} is in the past",
+                    deadline
+                )));
+            }
+            if deadline > now + MAX_DEADLINE_OFFSET {
+                return Err(EncodingError::FatalError(format!(
+                    "Deadline {} is too far in the future. It must be a unix timestamp in seconds",
+                    deadline
+                )));
+            }
+        }
+        if let Some(fee) = &solution.fee {
+            if fee.bps >= 10_000 {
+                return Err(EncodingError::FatalError(format!(
+                    "Fee must be less than 10000 bps (100%), got {}",
+                    fee.bps
+                )));
+            }
+            if fee.recipient.len() != 20 || fee.recipient.iter().all(|b| *b == 0) {
+                return Err(EncodingError::FatalError(format!(
+                    "Invalid fee recipient: {:?}",
+                    fee.recipient
+                )));
+            }
+        }
+        if let Some(surplus) = &solution.surplus {
+            if solution.expected_amount.is_none() {
+                return Err(EncodingError::FatalError(
+                    "Expected amount must be provided to capture surplus".to_string(),
+                ));
+            }
+            if surplus.share_bps() == 0 || surplus.share_bps() > 10_000 {
+                return Err(EncodingError::FatalError(format!(
+                    "Surplus share must be between 1 and 10000 bps, got {}",
+                    surplus.share_bps()
+                )));
+            }
+            let recipient = surplus.recipient();
+            if recipient.len() != 20 || recipient.iter().all(|b| *b == 0) {
+                return Err(EncodingError::FatalError(format!(
+                    "Invalid surplus recipient: {:?}",
+                    recipient
+                )));
+            }
+        }
+        get_wrap_unwrap(&solution.native_action)?;
         if let Some(native_action) = solution.clone().native_action {
             if native_action == NativeAction::Wrap {
                 if solution.given_token != self.native_address {
@@ -158,11 +871,72 @@ impl TychoEncoder for TychoRouterEncoder {
             }
         }
 
+        if has_swap_actions(solution) {
+            if solution.native_action.is_some() {
+                return Err(EncodingError::FatalError(
+                    "Native actions can't be combined with swap actions. Use wrap and unwrap actions instead"
+                        .to_string(),
+                ));
+            }
+            if !solution.additional_outputs.is_empty() || !solution.additional_inputs.is_empty() {
+                return Err(EncodingError::FatalError(
+                    "Swap actions are not supported for solutions with multiple inputs or outputs"
+                        .to_string(),
+                ));
+            }
+            let mut token = solution.given_token.clone();
+            for action in solution.pre_swap_actions.iter() {
+                // The amount of the swaps is fixed, so the pre-swap actions can't change it
+                if !matches!(action, NativeAction::Wrap | NativeAction::Unwrap) {
+                    return Err(EncodingError::FatalError(
+                        "Only wrap and unwrap actions are supported before the swaps".to_string(),
+                    ));
+                }
+                token = action
+                    .output_token(&token, &self.native_address, &self.wrapped_address)?
+                    .ok_or_else(|| {
+                        EncodingError::FatalError(
+                            "Pre-swap actions must keep the funds in the router".to_string(),
+                        )
+                    })?;
+            }
+            if let Some(first_swap) = solution.swaps.first() {
+                if first_swap.token_in != token {
+                    return Err(EncodingError::FatalError(format!(
+                        "First swap's input {} must be the output of the pre-swap actions {}",
+                        first_swap.token_in, token
+                    )));
+                }
+            }
+            let mut token = Some(solution.checked_token.clone());
+            for action in solution.post_swap_actions.iter() {
+                let token_in = token.ok_or_else(|| {
+                    EncodingError::FatalError(
+                        "Distribute and call actions must be the last action".to_string(),
+                    )
+                })?;
+                Self::validate_action_params(action)?;
+                token =
+                    action.output_token(&token_in, &self.native_address, &self.wrapped_address)?;
+            }
+            // The router can't derive the minimum amount of vault shares from the swaps' minimum
+            if solution.post_swap_min_amount.is_none() &&
+                solution
+                    .post_swap_actions
+                    .iter()
+                    .any(|action| matches!(action, NativeAction::VaultDeposit { .. }))
+            {
+                return Err(EncodingError::FatalError(
+                    "Vault deposit actions require a post_swap_min_amount".to_string(),
+                ));
+            }
+        }
+
         let mut solution_tokens = vec![];
         let mut split_tokens_already_considered = HashSet::new();
         for (i, swap) in solution.swaps.iter().enumerate() {
             // so we don't count the split tokens more than once
-            if swap.split != 0.0 {
+            if swap.split != 0.0 || swap.amount_in.is_some() {
                 if !split_tokens_already_considered.contains(&swap.token_in) {
                     solution_tokens.push(swap.token_in.clone());
                     split_tokens_already_considered.insert(swap.token_in.clone());
@@ -203,113 +977,323 @@ impl TychoEncoder for TychoRouterEncoder {
         }
         Ok(())
     }
+
+    fn explain_solution(&self, solution: &Solution) -> Result<SolutionReport, EncodingError> {
+        let mut errors = vec![];
+        let solution = self
+            .prepare_solution(solution)
+            .unwrap_or_else(|error| {
+                errors.push(error);
+                solution.clone()
+            });
+        if let Err(error) = self.validate_solution(&solution) {
+            errors.push(error);
+        }
+        // The swaps of a solution with swap actions are encoded on their own, see
+        // `get_swap_solution`
+        let swap_solution = if has_swap_actions(&solution) {
+            match get_swap_solution(
+                &solution,
+                &self.router_address,
+                &self.native_address,
+                &self.wrapped_address,
+            ) {
+                Ok(swap_solution) => swap_solution,
+                Err(error) => {
+                    errors.push(error);
+                    solution.clone()
+                }
+            }
+        } else {
+            solution.clone()
+        };
+        let strategy = get_swap_strategy(&swap_solution);
+        errors.extend(self.validate_swaps(&swap_solution, &strategy));
+
+        // The router holds the input of the swaps after executing the pre-swap actions
+        let token_in_already_in_router =
+            self.token_in_already_in_router || !solution.pre_swap_actions.is_empty();
+        let groups = self
+            .get_swap_group_reports(&swap_solution, &strategy, token_in_already_in_router)
+            .unwrap_or_else(|error| {
+                errors.push(error);
+                vec![]
+            });
+
+        Ok(SolutionReport {
+            errors: errors
+                .iter()
+                .map(|error| error.to_string())
+                .collect(),
+            strategy: strategy.to_string(),
+            groups,
+        })
+    }
 }
 
-/// Represents an encoder for one swap to be executed directly against an Executor.
+/// Represents an encoder for swaps to be executed directly against Executors.
 ///
 /// This is useful when you want to bypass the Tycho Router, use your own Router contract and
-/// just need the calldata for a particular swap.
+/// just need the calldata for a particular swap. Sequential routes needing more than one executor
+/// call are encoded with `encode_executor_calls`, as one call per group of swaps.
 ///
 /// # Fields
 /// * `swap_encoder_registry`: Registry of swap encoders
 /// * `native_address`: Address of the chain's native token
+/// * `wrapped_address`: Address of the chain's wrapped native token
+/// * `full_calldata`: Whether to encode the full `IExecutor.swap(givenAmount, data)` calldata
+///   instead of only the protocol data
+/// * `caller_address`: Address of the contract calling the executors. If not set, the solution's
+///   sender is assumed to be the caller.
+/// * `funding_mode`: How the input tokens reach the caller contract
 pub struct TychoExecutorEncoder {
     swap_encoder_registry: SwapEncoderRegistry,
     native_address: Bytes,
+    wrapped_address: Bytes,
+    full_calldata: bool,
+    caller_address: Option<Bytes>,
+    funding_mode: FundingMode,
 }
 
 impl TychoExecutorEncoder {
     pub fn new(
         chain: Chain,
         swap_encoder_registry: SwapEncoderRegistry,
+        full_calldata: bool,
+        caller_address: Option<Bytes>,
+        funding_mode: FundingMode,
     ) -> Result<Self, EncodingError> {
         let native_address = chain.native_token()?;
-        Ok(TychoExecutorEncoder { swap_encoder_registry, native_address })
+        let wrapped_address = chain.wrapped_token()?;
+        Ok(TychoExecutorEncoder {
+            swap_encoder_registry,
+            native_address,
+            wrapped_address,
+            full_calldata,
+            caller_address,
+            funding_mode,
+        })
     }
 
-    fn encode_executor_calldata(
+    /// Encodes the executor calls needed to execute each of the given solutions, in order.
+    ///
+    /// Unlike `encode_calldata`, this supports sequential routes which need more than one executor
+    /// call. The calls are expected to be made by the caller contract (e.g. your own router), or by
+    /// the solution's sender if no caller address is set. The transfer types depend on the funding
+    /// mode, exactly as for the Tycho router. The output of a swap is sent directly into the pool
+    /// of the next swap whenever possible, and back to the caller otherwise.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Vec<ExecutorCall>>, EncodingError>` - The executor calls of each solution, in
+    ///   the same order as the given solutions
+    pub fn encode_executor_calls(
         &self,
-        solution: Solution,
-    ) -> Result<(Vec<u8>, Bytes), EncodingError> {
-        let grouped_swaps = group_swaps(solution.clone().swaps);
-        let number_of_groups = grouped_swaps.len();
-        if number_of_groups > 1 {
-            return Err(EncodingError::InvalidInput(format!(
-                "Tycho executor encoder only supports one swap. Found {}",
-                number_of_groups
-            )))
-        }
+        solutions: Vec<Solution>,
+    ) -> Result<Vec<Vec<ExecutorCall>>, EncodingError> {
+        solutions
+            .iter()
+            .map(|solution| {
+                self.validate_solution(solution)?;
+                self.encode_solution_calls(solution)
+            })
+            .collect()
+    }
 
-        let grouped_swap = grouped_swaps
-            .first()
-            .ok_or_else(|| EncodingError::FatalError("Swap grouping failed".to_string()))?;
+    /// Encodes the executor calls needed to execute the given solution, in order.
+    fn encode_solution_calls(
+        &self,
+        solution: &Solution,
+    ) -> Result<Vec<ExecutorCall>, EncodingError> {
+        let grouped_swaps = group_swaps(solution.swaps.clone());
+        for (group, next_group) in grouped_swaps
+            .iter()
+            .zip(grouped_swaps.iter().skip(1))
+        {
+            if group.token_out != next_group.token_in {
+                return Err(EncodingError::InvalidInput(format!(
+                    "Tycho executor encoder only supports sequential swaps. Swap to {} is followed by a swap from {}",
+                    group.token_out, next_group.token_in
+                )));
+            }
+        }
 
-        let receiver = solution.receiver;
+        let caller_address = self
+            .caller_address
+            .clone()
+            .unwrap_or_else(|| solution.sender.clone());
+        let transfer_optimization = TransferOptimization::new(
+            self.native_address.clone(),
+            self.wrapped_address.clone(),
+            self.funding_mode == FundingMode::Permit2,
+            self.funding_mode == FundingMode::InCaller,
+            caller_address,
+        );
 
-        let swap_encoder = self
-            .swap_encoder_registry
-            .get_encoder(&grouped_swap.protocol_system)
-            .ok_or_else(|| {
-                EncodingError::InvalidInput(format!(
-                    "Swap encoder not found for protocol: {}",
-                    grouped_swap.protocol_system
-                ))
-            })?;
+        let mut executor_calls = vec![];
+        let mut next_in_between_swap_optimization_allowed = true;
+        for (i, grouped_swap) in grouped_swaps.iter().enumerate() {
+            let swap_encoder = self
+                .swap_encoder_registry
+                .get_encoder(&grouped_swap.protocol_system)
+                .ok_or_else(|| {
+                    EncodingError::InvalidInput(format!(
+                        "Swap encoder not found for protocol: {}",
+                        grouped_swap.protocol_system
+                    ))
+                })?;
+
+            let in_between_swap_optimization_allowed = next_in_between_swap_optimization_allowed;
+            let (receiver, next_swap_optimization) = transfer_optimization
+                .get_receiver(solution.receiver.clone(), grouped_swaps.get(i + 1))?;
+            next_in_between_swap_optimization_allowed = next_swap_optimization;
+            let transfer_type = transfer_optimization.get_transfer_type(
+                grouped_swap.clone(),
+                solution.given_token.clone(),
+                false,
+                in_between_swap_optimization_allowed,
+            );
 
-        let mut grouped_protocol_data: Vec<u8> = vec![];
-        for swap in grouped_swap.swaps.iter() {
             let encoding_context = EncodingContext {
                 receiver: receiver.clone(),
                 exact_out: solution.exact_out,
-                router_address: None,
+                router_address: self.caller_address.clone(),
                 group_token_in: grouped_swap.token_in.clone(),
                 group_token_out: grouped_swap.token_out.clone(),
-                transfer_type: TransferType::TransferToProtocol,
+                transfer_type: transfer_type.clone(),
             };
-            let protocol_data = swap_encoder.encode_swap(swap.clone(), encoding_context.clone())?;
-            grouped_protocol_data.extend(protocol_data);
-        }
+            let mut grouped_protocol_data: Vec<u8> = vec![];
+            for swap in grouped_swap.swaps.iter() {
+                let protocol_data =
+                    swap_encoder.encode_swap(swap.clone(), encoding_context.clone())?;
+                grouped_protocol_data.extend(protocol_data);
+            }
 
-        let executor_address = Bytes::from_str(swap_encoder.executor_address())
-            .map_err(|_| EncodingError::FatalError("Invalid executor address".to_string()))?;
+            let executor_address = Bytes::from_str(swap_encoder.executor_address())
+                .map_err(|_| EncodingError::FatalError("Invalid executor address".to_string()))?;
 
-        Ok((grouped_protocol_data, executor_address))
+            executor_calls.push(ExecutorCall {
+                executor_address,
+                protocol_data: grouped_protocol_data,
+                transfer_type,
+                receiver,
+            });
+        }
+        Ok(executor_calls)
     }
-}
 
-impl TychoEncoder for TychoExecutorEncoder {
-    fn encode_calldata(&self, solutions: Vec<Solution>) -> Result<Vec<Transaction>, EncodingError> {
-        let mut transactions: Vec<Transaction> = Vec::new();
-        let solution = solutions
-            .first()
-            .ok_or(EncodingError::FatalError("No solutions found".to_string()))?;
+    /// Encodes a solution into a transaction calling its executor.
+    ///
+    /// If `full_calldata` is set, the data of the transaction is the full `IExecutor.swap` call.
+    /// Solutions needing more than one executor call can't be encoded as a single transaction,
+    /// and must be encoded with `encode_executor_calls` instead.
+    fn encode_solution_transaction(
+        &self,
+        solution: &Solution,
+    ) -> Result<Transaction, EncodingError> {
         self.validate_solution(solution)?;
 
-        let (contract_interaction, target_address) =
-            self.encode_executor_calldata(solution.clone())?;
+        let mut executor_calls = self.encode_solution_calls(solution)?;
+        if executor_calls.len() != 1 {
+            return Err(EncodingError::InvalidInput(format!(
+                "Solution needs {} executor calls, which can't be encoded as a single transaction. Use encode_executor_calls instead",
+                executor_calls.len()
+            )));
+        }
+        let executor_call = executor_calls.remove(0);
 
         let value = if solution.given_token == self.native_address {
             solution.given_amount.clone()
         } else {
             BigUint::ZERO
         };
+        let data = if self.full_calldata {
+            encode_executor_swap(&solution.given_amount, executor_call.protocol_data)
+        } else {
+            executor_call.protocol_data
+        };
+        Ok(Transaction { value, data, to: executor_call.executor_address })
+    }
+}
 
-        transactions.push(Transaction { value, data: contract_interaction, to: target_address });
-        Ok(transactions)
+impl TychoEncoder for TychoExecutorEncoder {
+    fn encode_calldata(&self, solutions: Vec<Solution>) -> Result<Vec<Transaction>, EncodingError> {
+        if solutions.is_empty() {
+            return Err(EncodingError::FatalError("No solutions found".to_string()));
+        }
+        solutions
+            .iter()
+            .map(|solution| self.encode_solution_transaction(solution))
+            .collect()
+    }
+
+    fn encode_atomic_calldata(
+        &self,
+        _solutions: Vec<Solution>,
+    ) -> Result<Transaction, EncodingError> {
+        Err(EncodingError::InvalidInput(
+            "Atomic batching is only supported when encoding for the Tycho router".to_string(),
+        ))
     }
 
     /// Raises an `EncodingError` if the solution is not considered valid.
     ///
     /// A solution is considered valid if all the following conditions are met:
     /// * The solution is not exact out.
+    /// * The solution has no fee nor surplus policy, since only the router can distribute the
+    ///   output.
+    /// * The solution has a single input token and a single output token.
+    /// * The solution has no deadline, since only the router can enforce it.
+    /// * The solution has at least one swap and no split swaps.
     fn validate_solution(&self, solution: &Solution) -> Result<(), EncodingError> {
         if solution.exact_out {
             return Err(EncodingError::FatalError(
                 "Currently only exact input solutions are supported".to_string(),
             ));
         }
+        if !solution.additional_outputs.is_empty() || !solution.additional_inputs.is_empty() {
+            return Err(EncodingError::FatalError(
+                "Multiple inputs or outputs are not supported when encoding for a direct executor call"
+                    .to_string(),
+            ));
+        }
+        if solution.fee.is_some() || solution.surplus.is_some() {
+            return Err(EncodingError::FatalError(
+                "Fees and surplus capture are not supported when encoding for a direct executor call"
+                    .to_string(),
+            ));
+        }
+        if solution.deadline.is_some() {
+            return Err(EncodingError::FatalError(
+                "Deadlines are not supported when encoding for a direct executor call".to_string(),
+            ));
+        }
+        if has_swap_actions(solution) {
+            return Err(EncodingError::FatalError(
+                "Swap actions are not supported when encoding for a direct executor call"
+                    .to_string(),
+            ));
+        }
+        if solution.swaps.is_empty() {
+            return Err(EncodingError::FatalError("No swaps found in solution".to_string()));
+        }
+        if solution
+            .swaps
+            .iter()
+            .any(|swap| swap.split != 0.0 || swap.amount_in.is_some())
+        {
+            return Err(EncodingError::FatalError(
+                "Split swaps are not supported when encoding for a direct executor call"
+                    .to_string(),
+            ));
+        }
         Ok(())
     }
+
+    fn explain_solution(&self, _solution: &Solution) -> Result<SolutionReport, EncodingError> {
+        Err(EncodingError::InvalidInput(
+            "Explaining solutions is only supported when encoding for the Tycho router".to_string(),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -319,7 +1303,9 @@ mod tests {
     use tycho_common::models::{protocol::ProtocolComponent, Chain as TychoCommonChain};
 
     use super::*;
-    use crate::encoding::models::Swap;
+    use crate::encoding::models::{
+        ActionRecipient, IntegratorFee, SolutionInput, SolutionOutput, SurplusPolicy, Swap,
+    };
 
     fn dai() -> Bytes {
         Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap()
@@ -350,6 +1336,8 @@ mod tests {
     }
 
     mod router_encoder {
+        use alloy::hex::encode;
+
         use super::*;
 
         fn get_mocked_tycho_router_encoder() -> TychoRouterEncoder {
@@ -359,6 +1347,7 @@ mod tests {
                 None,
                 Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
                 false,
+                false,
             )
             .unwrap()
         }
@@ -375,6 +1364,7 @@ mod tests {
                 token_in: weth(),
                 token_out: dai(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let solution = Solution {
@@ -401,25 +1391,47 @@ mod tests {
         }
 
         #[test]
-        fn test_validate_fails_for_exact_out() {
+        fn test_encode_router_calldata_with_deadline() {
             let encoder = get_mocked_tycho_router_encoder();
-            let solution = Solution {
-                exact_out: true, // This should cause an error
+            let swap = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: dai(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = Solution {
+                exact_out: false,
+                given_amount: BigUint::from(1000u32),
+                given_token: weth(),
+                checked_token: dai(),
+                swaps: vec![swap],
+                receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
                 ..Default::default()
             };
-            let result = encoder.validate_solution(&solution);
+            let deadline = Utc::now().timestamp() as u64 + 600;
+            let solution_with_deadline = Solution { deadline: Some(deadline), ..solution.clone() };
 
-            assert!(result.is_err());
-            assert_eq!(
-                result.err().unwrap(),
-                EncodingError::FatalError(
-                    "Currently only exact input solutions are supported".to_string()
-                )
+            let transactions = encoder
+                .encode_calldata(vec![solution, solution_with_deadline])
+                .unwrap();
+
+            // The swap calldata is wrapped into an `executeWithDeadline` call
+            let expected_data = encode_input(
+                "executeWithDeadline(uint256,bytes)",
+                (U256::from(deadline), transactions[0].data.clone()).abi_encode(),
             );
+            assert_eq!(transactions[1].data, expected_data);
+            assert_eq!(transactions[1].data[..4], [0x15, 0x70, 0x1b, 0x5f]);
         }
 
         #[test]
-        fn test_validate_passes_for_wrap() {
+        fn test_encode_router_calldata_with_actions() {
+            // ETH ──(wrap)──> WETH ──(USV2)──> DAI ──(distribute)──> 2 recipients
             let encoder = get_mocked_tycho_router_encoder();
             let swap = Swap {
                 component: ProtocolComponent {
@@ -430,26 +1442,198 @@ mod tests {
                 token_in: weth(),
                 token_out: dai(),
                 split: 0f64,
+                amount_in: None,
             };
-
+            let receiver = Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap();
             let solution = Solution {
                 exact_out: false,
+                given_amount: BigUint::from(1000u32),
                 given_token: eth(),
                 checked_token: dai(),
-                checked_amount: None,
+                checked_amount: Some(BigUint::from(1u32)),
                 swaps: vec![swap],
-                native_action: Some(NativeAction::Wrap),
+                receiver: receiver.clone(),
+                pre_swap_actions: vec![NativeAction::Wrap],
+                post_swap_actions: vec![NativeAction::Distribute {
+                    recipients: vec![
+                        ActionRecipient::new(receiver.clone(), 6000),
+                        ActionRecipient::new(
+                            Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                            4000,
+                        ),
+                    ],
+                }],
                 ..Default::default()
             };
 
-            let result = encoder.validate_solution(&solution);
+            let transactions = encoder
+                .encode_calldata(vec![solution])
+                .unwrap();
 
-            assert!(result.is_ok());
+            // The swap calldata is wrapped into a `swapWithActions` call
+            assert_eq!(transactions[0].data[..4], [0x0a, 0x63, 0x6a, 0x5f]);
+            assert_eq!(transactions[0].value, BigUint::from(1000u32));
         }
 
         #[test]
-        fn test_validate_fails_for_wrap_wrong_input() {
+        fn test_insert_native_wrapper_steps() {
+            // DAI ──(USV4)──> ETH ──(wrap)──> WETH ──(USV2)──> USDC
+            let encoder = get_mocked_tycho_router_encoder();
+            let swap_dai_eth = Swap {
+                component: ProtocolComponent {
+                    id: "0x60594a405d53811d3BC4766596EFD80fd545A270".to_string(),
+                    protocol_system: "uniswap_v4".to_string(),
+                    ..Default::default()
+                },
+                token_in: dai(),
+                token_out: eth(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let swap_weth_usdc = Swap {
+                component: ProtocolComponent {
+                    id: "0xB4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: usdc(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = Solution {
+                exact_out: false,
+                given_token: dai(),
+                checked_token: usdc(),
+                swaps: vec![swap_dai_eth.clone(), swap_weth_usdc.clone()],
+                ..Default::default()
+            };
+
+            let solution = encoder
+                .insert_native_wrapper_steps(&solution)
+                .unwrap();
+
+            assert_eq!(solution.swaps.len(), 3);
+            assert_eq!(solution.swaps[0], swap_dai_eth);
+            assert_eq!(
+                solution.swaps[1]
+                    .component
+                    .protocol_system,
+                NATIVE_WRAPPER_PROTOCOL
+            );
+            assert_eq!(solution.swaps[1].token_in, eth());
+            assert_eq!(solution.swaps[1].token_out, weth());
+            assert_eq!(solution.swaps[2], swap_weth_usdc);
+        }
+
+        #[test]
+        fn test_insert_native_wrapper_steps_split_solution_unchanged() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swap_dai_eth = Swap {
+                component: ProtocolComponent {
+                    id: "0x60594a405d53811d3BC4766596EFD80fd545A270".to_string(),
+                    protocol_system: "uniswap_v4".to_string(),
+                    ..Default::default()
+                },
+                token_in: dai(),
+                token_out: eth(),
+                split: 0.5f64,
+                amount_in: None,
+            };
+            let swap_weth_usdc = Swap {
+                component: ProtocolComponent {
+                    id: "0xB4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: usdc(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = Solution {
+                exact_out: false,
+                given_token: dai(),
+                checked_token: usdc(),
+                swaps: vec![swap_dai_eth, swap_weth_usdc],
+                ..Default::default()
+            };
+
+            let result = encoder
+                .insert_native_wrapper_steps(&solution)
+                .unwrap();
+
+            assert_eq!(result.swaps, solution.swaps);
+        }
+
+        #[test]
+        fn test_insert_native_wrapper_steps_without_native_wrapper() {
+            // The default executors file has no native_wrapper executor
+            let encoder = TychoRouterEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                SwapEncoderRegistry::new(None, TychoCommonChain::Ethereum.into()).unwrap(),
+                None,
+                Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+                false,
+                false,
+            )
+            .unwrap();
+            let swap_dai_eth = Swap {
+                component: ProtocolComponent {
+                    id: "0x60594a405d53811d3BC4766596EFD80fd545A270".to_string(),
+                    protocol_system: "uniswap_v4".to_string(),
+                    ..Default::default()
+                },
+                token_in: dai(),
+                token_out: eth(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let swap_weth_usdc = Swap {
+                component: ProtocolComponent {
+                    id: "0xB4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: usdc(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = Solution {
+                exact_out: false,
+                given_token: dai(),
+                checked_token: usdc(),
+                swaps: vec![swap_dai_eth, swap_weth_usdc],
+                ..Default::default()
+            };
+
+            let result = encoder.insert_native_wrapper_steps(&solution);
+
+            match result {
+                Err(EncodingError::InvalidInput(message)) => {
+                    assert!(message.contains("no native_wrapper executor is configured"))
+                }
+                _ => panic!("Expected a missing native_wrapper error"),
+            }
+        }
+
+        #[test]
+        fn test_encode_router_calldata_explicit_wrap_step() {
+            // ETH ──(wrap)──> WETH ──(USV2)──> DAI
             let encoder = get_mocked_tycho_router_encoder();
+            let eth_amount_in = BigUint::from(1000u32);
+            let wrap = Swap {
+                component: ProtocolComponent {
+                    id: weth().to_string(),
+                    protocol_system: NATIVE_WRAPPER_PROTOCOL.to_string(),
+                    ..Default::default()
+                },
+                token_in: eth(),
+                token_out: weth(),
+                split: 0f64,
+                amount_in: None,
+            };
             let swap = Swap {
                 component: ProtocolComponent {
                     id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
@@ -459,29 +1643,132 @@ mod tests {
                 token_in: weth(),
                 token_out: dai(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let solution = Solution {
                 exact_out: false,
+                given_amount: eth_amount_in.clone(),
+                given_token: eth(),
+                checked_token: dai(),
+                checked_amount: Some(BigUint::from(1u32)),
+                sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                swaps: vec![wrap, swap],
+                receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                ..Default::default()
+            };
+
+            let transactions = encoder.encode_calldata(vec![solution]);
+
+            assert!(transactions.is_ok());
+            let transactions = transactions.unwrap();
+            assert_eq!(transactions.len(), 1);
+            assert_eq!(transactions[0].value, eth_amount_in);
+
+            let expected_swaps = String::from(concat!(
+                // length of the swaps
+                "0000000000000000000000000000000000000000000000000000000000000093",
+                // length of the wrap step
+                "003d",
+                // Wrap step
+                "a4ad4f68d0b91cfd19687c881e50f3a00242828c", // native wrapper executor address
+                "0000000000000000000000000000000000000000", // token in (ETH)
+                "a478c2975ab1ea89e8196811f51a7b7ade33eb11", // receiver (the USV2 pool)
+                "05",                                       // transfer type (none)
+                // length of the USV2 swap
+                "0052",
+                // USV2 swap
+                "5615deb798bb3e4dfa0139dfa1b3d433cc23b72f", // executor address
+                "c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2", // token in
+                "a478c2975ab1ea89e8196811f51a7b7ade33eb11", // component id
+                "cd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2", // receiver
+                "00",                                       // zero2one
+                "05",                                       // transfer type (none)
+            ));
+            let hex_calldata = encode(&transactions[0].data);
+            assert!(hex_calldata.contains(&expected_swaps));
+        }
+
+        #[test]
+        fn test_encode_atomic_calldata() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let sender = Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap();
+            let swap = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: dai(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let wrap_solution = Solution {
+                exact_out: false,
+                given_amount: BigUint::from(1000u32),
+                given_token: eth(),
+                checked_token: dai(),
+                swaps: vec![swap.clone()],
+                sender: sender.clone(),
+                receiver: sender.clone(),
+                native_action: Some(NativeAction::Wrap),
+                ..Default::default()
+            };
+            let erc20_solution = Solution {
+                exact_out: false,
+                given_amount: BigUint::from(2000u32),
                 given_token: weth(),
+                checked_token: dai(),
                 swaps: vec![swap],
-                native_action: Some(NativeAction::Wrap),
+                sender: sender.clone(),
+                receiver: sender.clone(),
                 ..Default::default()
             };
 
-            let result = encoder.validate_solution(&solution);
+            let transaction = encoder
+                .encode_atomic_calldata(vec![wrap_solution.clone(), erc20_solution.clone()])
+                .unwrap();
+            let single_transactions = encoder
+                .encode_calldata(vec![wrap_solution.clone(), erc20_solution.clone()])
+                .unwrap();
 
-            assert!(result.is_err());
+            assert_eq!(transaction.value, BigUint::from(1000u32));
             assert_eq!(
-                result.err().unwrap(),
-                EncodingError::FatalError(
-                    "Native token must be the input token in order to wrap".to_string()
-                )
+                transaction.to,
+                Bytes::from_str("0x3ede3eca2a72b3aecc820e955b36f38437d01395").unwrap()
             );
+            let calls = vec![
+                AlloyBytes::from(single_transactions[0].data.clone()),
+                AlloyBytes::from(single_transactions[1].data.clone()),
+            ];
+            assert_eq!(transaction.data, encode_input("multicall(bytes[])", (calls,).abi_encode()));
+            assert_eq!(transaction.data[..4], [0xac, 0x96, 0x50, 0xd8]);
+
+            // Solutions of an atomic batch must share the same sender
+            let other_sender_solution = Solution {
+                sender: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                ..erc20_solution.clone()
+            };
+            assert!(matches!(
+                encoder.encode_atomic_calldata(vec![erc20_solution, other_sender_solution]),
+                Err(EncodingError::FatalError(_))
+            ));
+
+            // Only one solution can spend the native token
+            assert!(matches!(
+                encoder.encode_atomic_calldata(vec![wrap_solution.clone(), wrap_solution]),
+                Err(EncodingError::FatalError(_))
+            ));
+
+            // An empty batch can't be encoded
+            assert!(encoder
+                .encode_atomic_calldata(vec![])
+                .is_err());
         }
 
         #[test]
-        fn test_validate_fails_for_wrap_wrong_first_swap() {
+        fn test_encode_solutions_per_solution_results() {
             let encoder = get_mocked_tycho_router_encoder();
             let swap = Swap {
                 component: ProtocolComponent {
@@ -489,52 +1776,78 @@ mod tests {
                     protocol_system: "uniswap_v2".to_string(),
                     ..Default::default()
                 },
-                token_in: eth(),
+                token_in: weth(),
                 token_out: dai(),
                 split: 0f64,
+                amount_in: None,
             };
-
             let solution = Solution {
                 exact_out: false,
-                given_token: eth(),
+                given_amount: BigUint::from(1000u32),
+                given_token: weth(),
+                checked_token: dai(),
                 swaps: vec![swap],
-                native_action: Some(NativeAction::Wrap),
+                receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
                 ..Default::default()
             };
-
-            let result = encoder.validate_solution(&solution);
-
-            assert!(result.is_err());
+            let malformed_solution = Solution { exact_out: true, ..solution.clone() };
+            let other_solution =
+                Solution { given_amount: BigUint::from(2000u32), ..solution.clone() };
+
+            let results = encoder.encode_solutions(vec![
+                solution.clone(),
+                malformed_solution,
+                other_solution.clone(),
+            ]);
+
+            assert_eq!(results.len(), 3);
+            let expected_transactions = encoder
+                .encode_calldata(vec![solution, other_solution])
+                .unwrap();
             assert_eq!(
-                result.err().unwrap(),
-                EncodingError::FatalError(
-                    "Wrapped token must be the first swap's input in order to wrap".to_string()
+                results[0]
+                    .as_ref()
+                    .unwrap()
+                    .transaction
+                    .data,
+                expected_transactions[0].data
+            );
+            assert_eq!(
+                results[1].as_ref().unwrap_err(),
+                &EncodingError::FatalError(
+                    "Currently only exact input solutions are supported".to_string()
                 )
             );
+            assert_eq!(
+                results[2]
+                    .as_ref()
+                    .unwrap()
+                    .transaction
+                    .data,
+                expected_transactions[1].data
+            );
         }
 
         #[test]
-        fn test_validate_fails_no_swaps() {
+        fn test_validate_fails_for_exact_out() {
             let encoder = get_mocked_tycho_router_encoder();
             let solution = Solution {
-                exact_out: false,
-                given_token: eth(),
-                swaps: vec![],
-                native_action: Some(NativeAction::Wrap),
+                exact_out: true, // This should cause an error
                 ..Default::default()
             };
-
             let result = encoder.validate_solution(&solution);
 
             assert!(result.is_err());
             assert_eq!(
                 result.err().unwrap(),
-                EncodingError::FatalError("No swaps found in solution".to_string())
+                EncodingError::FatalError(
+                    "Currently only exact input solutions are supported".to_string()
+                )
             );
         }
 
         #[test]
-        fn test_validate_passes_for_unwrap() {
+        fn test_validate_passes_for_wrap() {
             let encoder = get_mocked_tycho_router_encoder();
             let swap = Swap {
                 component: ProtocolComponent {
@@ -542,17 +1855,19 @@ mod tests {
                     protocol_system: "uniswap_v2".to_string(),
                     ..Default::default()
                 },
-                token_in: dai(),
-                token_out: weth(),
+                token_in: weth(),
+                token_out: dai(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let solution = Solution {
                 exact_out: false,
-                checked_token: eth(),
+                given_token: eth(),
+                checked_token: dai(),
                 checked_amount: None,
                 swaps: vec![swap],
-                native_action: Some(NativeAction::Unwrap),
+                native_action: Some(NativeAction::Wrap),
                 ..Default::default()
             };
 
@@ -562,7 +1877,7 @@ mod tests {
         }
 
         #[test]
-        fn test_validate_fails_for_unwrap_wrong_output() {
+        fn test_validate_fails_for_wrap_wrong_input() {
             let encoder = get_mocked_tycho_router_encoder();
             let swap = Swap {
                 component: ProtocolComponent {
@@ -570,17 +1885,17 @@ mod tests {
                     protocol_system: "uniswap_v2".to_string(),
                     ..Default::default()
                 },
-                token_in: dai(),
-                token_out: weth(),
+                token_in: weth(),
+                token_out: dai(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let solution = Solution {
                 exact_out: false,
-                given_token: dai(),
-                checked_token: weth(),
+                given_token: weth(),
                 swaps: vec![swap],
-                native_action: Some(NativeAction::Unwrap),
+                native_action: Some(NativeAction::Wrap),
                 ..Default::default()
             };
 
@@ -590,13 +1905,13 @@ mod tests {
             assert_eq!(
                 result.err().unwrap(),
                 EncodingError::FatalError(
-                    "Native token must be the output token in order to unwrap".to_string()
+                    "Native token must be the input token in order to wrap".to_string()
                 )
             );
         }
 
         #[test]
-        fn test_validate_fails_for_unwrap_wrong_last_swap() {
+        fn test_validate_fails_for_wrap_wrong_first_swap() {
             let encoder = get_mocked_tycho_router_encoder();
             let swap = Swap {
                 component: ProtocolComponent {
@@ -604,16 +1919,495 @@ mod tests {
                     protocol_system: "uniswap_v2".to_string(),
                     ..Default::default()
                 },
-                token_in: dai(),
-                token_out: eth(),
+                token_in: eth(),
+                token_out: dai(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let solution = Solution {
                 exact_out: false,
-                checked_token: eth(),
+                given_token: eth(),
                 swaps: vec![swap],
-                native_action: Some(NativeAction::Unwrap),
+                native_action: Some(NativeAction::Wrap),
+                ..Default::default()
+            };
+
+            let result = encoder.validate_solution(&solution);
+
+            assert!(result.is_err());
+            assert_eq!(
+                result.err().unwrap(),
+                EncodingError::FatalError(
+                    "Wrapped token must be the first swap's input in order to wrap".to_string()
+                )
+            );
+        }
+
+        #[test]
+        fn test_validate_fails_no_swaps() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let solution = Solution {
+                exact_out: false,
+                given_token: eth(),
+                swaps: vec![],
+                native_action: Some(NativeAction::Wrap),
+                ..Default::default()
+            };
+
+            let result = encoder.validate_solution(&solution);
+
+            assert!(result.is_err());
+            assert_eq!(
+                result.err().unwrap(),
+                EncodingError::FatalError("No swaps found in solution".to_string())
+            );
+        }
+
+        #[test]
+        fn test_validate_fee() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swap = Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth(),
+                dai(),
+                0f64,
+            );
+            let recipient = Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap();
+            let mut solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                checked_token: dai(),
+                swaps: vec![swap],
+                fee: Some(IntegratorFee::new(25, recipient.clone())),
+                ..Default::default()
+            };
+            assert_eq!(encoder.validate_solution(&solution), Ok(()));
+
+            solution.fee = Some(IntegratorFee::new(10_000, recipient));
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(
+                    "Fee must be less than 10000 bps (100%), got 10000".to_string()
+                ))
+            );
+
+            solution.fee = Some(IntegratorFee::new(
+                25,
+                Bytes::from_str("0x0000000000000000000000000000000000000000").unwrap(),
+            ));
+            assert!(matches!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(msg)) if msg.starts_with("Invalid fee recipient")
+            ));
+        }
+
+        #[test]
+        fn test_validate_surplus() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swap = Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth(),
+                dai(),
+                0f64,
+            );
+            let recipient = Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap();
+            let mut solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                checked_token: dai(),
+                expected_amount: Some(BigUint::from(1000u64)),
+                swaps: vec![swap],
+                surplus: Some(SurplusPolicy::Share { recipient: recipient.clone(), bps: 5_000 }),
+                ..Default::default()
+            };
+            assert_eq!(encoder.validate_solution(&solution), Ok(()));
+
+            solution.surplus = Some(SurplusPolicy::Share { recipient: recipient.clone(), bps: 0 });
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(
+                    "Surplus share must be between 1 and 10000 bps, got 0".to_string()
+                ))
+            );
+
+            solution.surplus = Some(SurplusPolicy::Cap { recipient });
+            solution.expected_amount = None;
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(
+                    "Expected amount must be provided to capture surplus".to_string()
+                ))
+            );
+        }
+
+        #[test]
+        fn test_validate_deadline() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swap = Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth(),
+                dai(),
+                0f64,
+            );
+            let now = Utc::now().timestamp() as u64;
+            let mut solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                checked_token: dai(),
+                swaps: vec![swap],
+                deadline: Some(now + 600),
+                ..Default::default()
+            };
+            assert_eq!(encoder.validate_solution(&solution), Ok(()));
+
+            solution.deadline = Some(now - 1);
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(format!("Deadline {} is in the past", now - 1)))
+            );
+
+            // A deadline in milliseconds
+            solution.deadline = Some(now * 1000);
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(format!(
+                    "Deadline {} is too far in the future. It must be a unix timestamp in seconds",
+                    now * 1000
+                )))
+            );
+        }
+
+        #[test]
+        fn test_validate_swap_actions() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swap = Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth(),
+                dai(),
+                0f64,
+            );
+            let vault = Bytes::from_str("0x83F20F44975D03b1b09e64809B757c47f942BEeA").unwrap();
+            let recipient = Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap();
+            let mut solution = Solution {
+                exact_out: false,
+                given_token: eth(),
+                checked_token: dai(),
+                swaps: vec![swap],
+                pre_swap_actions: vec![NativeAction::Wrap],
+                post_swap_actions: vec![
+                    NativeAction::VaultDeposit { vault: vault.clone() },
+                    NativeAction::Distribute {
+                        recipients: vec![ActionRecipient::new(recipient.clone(), 10_000)],
+                    },
+                ],
+                post_swap_min_amount: Some(BigUint::from(1u32)),
+                ..Default::default()
+            };
+            assert_eq!(encoder.validate_solution(&solution), Ok(()));
+
+            // The minimum amount of vault shares is missing
+            solution.post_swap_min_amount = None;
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(
+                    "Vault deposit actions require a post_swap_min_amount".to_string()
+                ))
+            );
+            solution.post_swap_min_amount = Some(BigUint::from(1u32));
+
+            // The pre-swap actions don't output the first swap's input
+            solution.pre_swap_actions = vec![];
+            solution.given_token = usdc();
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(format!(
+                    "First swap's input {} must be the output of the pre-swap actions {}",
+                    weth(),
+                    usdc()
+                )))
+            );
+            solution.given_token = weth();
+
+            // Unwrapping the swap's output, which is not the wrapped native token
+            solution.post_swap_actions = vec![NativeAction::Unwrap];
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(format!(
+                    "Unwrap action expects the wrapped native token as input, got {}",
+                    dai()
+                )))
+            );
+
+            // An action after a terminal action
+            solution.post_swap_actions = vec![
+                NativeAction::Distribute {
+                    recipients: vec![ActionRecipient::new(recipient.clone(), 10_000)],
+                },
+                NativeAction::VaultDeposit { vault },
+            ];
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(
+                    "Distribute and call actions must be the last action".to_string()
+                ))
+            );
+
+            // Shares not adding up to 100%
+            solution.post_swap_actions = vec![NativeAction::Distribute {
+                recipients: vec![ActionRecipient::new(recipient, 9_000)],
+            }];
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(
+                    "Distribute shares must add up to 10000 bps, got 9000".to_string()
+                ))
+            );
+
+            // Combined with a native action
+            solution.post_swap_actions = vec![];
+            solution.pre_swap_actions = vec![NativeAction::Unwrap];
+            solution.native_action = Some(NativeAction::Unwrap);
+            assert!(encoder
+                .validate_solution(&solution)
+                .is_err());
+
+            // Only wrap and unwrap can be the native action of the solution
+            solution.pre_swap_actions = vec![];
+            let native_action = NativeAction::VaultDeposit {
+                vault: Bytes::from_str("0x83F20F44975D03b1b09e64809B757c47f942BEeA").unwrap(),
+            };
+            solution.native_action = Some(native_action.clone());
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::InvalidInput(format!(
+                    "Only wrap and unwrap are supported as the native action of a solution, got \
+                     {:?}. Use pre- or post-swap actions instead",
+                    native_action
+                )))
+            );
+        }
+
+        #[test]
+        fn test_validate_additional_outputs() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swaps = vec![
+                Swap::new(
+                    ProtocolComponent {
+                        id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                        protocol_system: "uniswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    weth(),
+                    dai(),
+                    0.5f64,
+                ),
+                Swap::new(
+                    ProtocolComponent {
+                        id: "0xBb2b8038a1640196FbE3e38816F3e67Cba72D940".to_string(),
+                        protocol_system: "uniswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    weth(),
+                    wbtc(),
+                    0f64,
+                ),
+            ];
+            let receiver = Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap();
+            let mut solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                checked_token: dai(),
+                swaps,
+                additional_outputs: vec![SolutionOutput::new(
+                    wbtc(),
+                    BigUint::from(1000u64),
+                    receiver.clone(),
+                )],
+                ..Default::default()
+            };
+            assert_eq!(encoder.validate_solution(&solution), Ok(()));
+
+            solution.additional_outputs[0].min_amount = BigUint::ZERO;
+            assert!(matches!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(msg)) if msg.starts_with("Minimum amount of output token")
+            ));
+
+            solution.additional_outputs[0] =
+                SolutionOutput::new(dai(), BigUint::from(1u64), receiver);
+            assert!(matches!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(msg)) if msg.ends_with("must be different from the other tokens of the solution")
+            ));
+
+            solution.additional_outputs[0] = SolutionOutput::new(
+                wbtc(),
+                BigUint::from(1u64),
+                Bytes::from_str("0x0000000000000000000000000000000000000000").unwrap(),
+            );
+            assert!(matches!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(msg)) if msg.starts_with("Invalid receiver for output token")
+            ));
+        }
+
+        #[test]
+        fn test_validate_additional_inputs() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swaps = vec![
+                Swap::new(
+                    ProtocolComponent {
+                        id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                        protocol_system: "uniswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    weth(),
+                    dai(),
+                    0f64,
+                ),
+                Swap::new(
+                    ProtocolComponent {
+                        id: "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5".to_string(),
+                        protocol_system: "uniswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    usdc(),
+                    dai(),
+                    0f64,
+                ),
+            ];
+            let mut solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                checked_token: dai(),
+                swaps,
+                additional_inputs: vec![SolutionInput::new(usdc(), BigUint::from(1000u64))],
+                ..Default::default()
+            };
+            assert_eq!(encoder.validate_solution(&solution), Ok(()));
+
+            solution.additional_inputs[0].amount = BigUint::ZERO;
+            assert!(matches!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(msg)) if msg.starts_with("Amount of input token")
+            ));
+
+            solution.additional_inputs[0] = SolutionInput::new(weth(), BigUint::from(1u64));
+            assert!(matches!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(msg)) if msg.ends_with("must be different from the other tokens of the solution")
+            ));
+
+            solution.additional_inputs[0] = SolutionInput::new(eth(), BigUint::from(1u64));
+            assert_eq!(
+                encoder.validate_solution(&solution),
+                Err(EncodingError::FatalError(
+                    "Native token is not supported for solutions with multiple inputs".to_string()
+                ))
+            );
+        }
+
+        #[test]
+        fn test_validate_passes_for_unwrap() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swap = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: dai(),
+                token_out: weth(),
+                split: 0f64,
+                amount_in: None,
+            };
+
+            let solution = Solution {
+                exact_out: false,
+                checked_token: eth(),
+                checked_amount: None,
+                swaps: vec![swap],
+                native_action: Some(NativeAction::Unwrap),
+                ..Default::default()
+            };
+
+            let result = encoder.validate_solution(&solution);
+
+            assert!(result.is_ok());
+        }
+
+        #[test]
+        fn test_validate_fails_for_unwrap_wrong_output() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swap = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: dai(),
+                token_out: weth(),
+                split: 0f64,
+                amount_in: None,
+            };
+
+            let solution = Solution {
+                exact_out: false,
+                given_token: dai(),
+                checked_token: weth(),
+                swaps: vec![swap],
+                native_action: Some(NativeAction::Unwrap),
+                ..Default::default()
+            };
+
+            let result = encoder.validate_solution(&solution);
+
+            assert!(result.is_err());
+            assert_eq!(
+                result.err().unwrap(),
+                EncodingError::FatalError(
+                    "Native token must be the output token in order to unwrap".to_string()
+                )
+            );
+        }
+
+        #[test]
+        fn test_validate_fails_for_unwrap_wrong_last_swap() {
+            let encoder = get_mocked_tycho_router_encoder();
+            let swap = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: dai(),
+                token_out: eth(),
+                split: 0f64,
+                amount_in: None,
+            };
+
+            let solution = Solution {
+                exact_out: false,
+                checked_token: eth(),
+                swaps: vec![swap],
+                native_action: Some(NativeAction::Unwrap),
                 ..Default::default()
             };
 
@@ -646,6 +2440,7 @@ mod tests {
                     token_in: dai(),
                     token_out: weth(),
                     split: 0.5f64,
+                    amount_in: None,
                 },
                 Swap {
                     component: ProtocolComponent {
@@ -656,6 +2451,7 @@ mod tests {
                     token_in: dai(),
                     token_out: weth(),
                     split: 0f64,
+                    amount_in: None,
                 },
                 Swap {
                     component: ProtocolComponent {
@@ -666,6 +2462,7 @@ mod tests {
                     token_in: weth(),
                     token_out: dai(),
                     split: 0f64,
+                    amount_in: None,
                 },
             ];
 
@@ -698,6 +2495,7 @@ mod tests {
                     token_in: dai(),
                     token_out: weth(),
                     split: 0f64,
+                    amount_in: None,
                 },
                 Swap {
                     component: ProtocolComponent {
@@ -708,6 +2506,7 @@ mod tests {
                     token_in: weth(),
                     token_out: usdc(),
                     split: 0f64,
+                    amount_in: None,
                 },
                 Swap {
                     component: ProtocolComponent {
@@ -718,6 +2517,7 @@ mod tests {
                     token_in: usdc(),
                     token_out: dai(),
                     split: 0f64,
+                    amount_in: None,
                 },
                 Swap {
                     component: ProtocolComponent {
@@ -728,6 +2528,7 @@ mod tests {
                     token_in: dai(),
                     token_out: wbtc(),
                     split: 0f64,
+                    amount_in: None,
                 },
             ];
 
@@ -767,6 +2568,7 @@ mod tests {
                     token_in: weth(),
                     token_out: dai(),
                     split: 0f64,
+                    amount_in: None,
                 },
                 Swap {
                     component: ProtocolComponent {
@@ -777,6 +2579,7 @@ mod tests {
                     token_in: dai(),
                     token_out: weth(),
                     split: 0.5f64,
+                    amount_in: None,
                 },
                 Swap {
                     component: ProtocolComponent {
@@ -787,6 +2590,7 @@ mod tests {
                     token_in: dai(),
                     token_out: weth(),
                     split: 0f64,
+                    amount_in: None,
                 },
             ];
 
@@ -819,6 +2623,7 @@ mod tests {
                     token_in: weth(),
                     token_out: dai(),
                     split: 0f64,
+                    amount_in: None,
                 },
                 Swap {
                     component: ProtocolComponent {
@@ -829,6 +2634,7 @@ mod tests {
                     token_in: dai(),
                     token_out: weth(),
                     split: 0f64,
+                    amount_in: None,
                 },
             ];
 
@@ -853,6 +2659,184 @@ mod tests {
                 )
             );
         }
+
+        #[test]
+        fn test_explain_solution_sequential() {
+            // DAI -> WETH -> USDC, both through uniswap_v2 pools
+            let encoder = get_mocked_tycho_router_encoder();
+            let receiver = Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap();
+            let swaps = vec![
+                Swap {
+                    component: ProtocolComponent {
+                        id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                        protocol_system: "uniswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    token_in: dai(),
+                    token_out: weth(),
+                    split: 0f64,
+                    amount_in: None,
+                },
+                Swap {
+                    component: ProtocolComponent {
+                        id: "0xB4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc".to_string(),
+                        protocol_system: "uniswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    token_in: weth(),
+                    token_out: usdc(),
+                    split: 0f64,
+                    amount_in: None,
+                },
+            ];
+            let solution = Solution {
+                exact_out: false,
+                given_token: dai(),
+                given_amount: BigUint::from(1000u32),
+                checked_token: usdc(),
+                checked_amount: Some(BigUint::from(1u32)),
+                receiver: receiver.clone(),
+                swaps,
+                ..Default::default()
+            };
+
+            let report = encoder
+                .explain_solution(&solution)
+                .unwrap();
+
+            assert!(report.is_valid());
+            assert_eq!(report.strategy, "sequential");
+            assert_eq!(report.groups.len(), 2);
+            assert_eq!(report.groups[0].transfer_type, TransferType::TransferFromToProtocol);
+            // The first swap sends its output straight to the pool of the second one
+            assert_eq!(
+                report.groups[0].receiver,
+                Bytes::from_str("0xB4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc").unwrap()
+            );
+            assert_eq!(report.groups[1].transfer_type, TransferType::None);
+            assert_eq!(report.groups[1].receiver, receiver);
+        }
+
+        #[test]
+        fn test_explain_solution_reports_all_errors() {
+            // Split DAI -> WETH whose splits add up to more than 100%, without a checked amount
+            let encoder = get_mocked_tycho_router_encoder();
+            let receiver = Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap();
+            let swaps = vec![
+                Swap {
+                    component: ProtocolComponent {
+                        id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                        protocol_system: "uniswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    token_in: dai(),
+                    token_out: weth(),
+                    split: 0.6f64,
+                    amount_in: None,
+                },
+                Swap {
+                    component: ProtocolComponent {
+                        id: "0xC3D03e4F041Fd4cD388c549Ee2A29a9E5075882f".to_string(),
+                        protocol_system: "sushiswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    token_in: dai(),
+                    token_out: weth(),
+                    split: 0.6f64,
+                    amount_in: None,
+                },
+            ];
+            let solution = Solution {
+                exact_out: false,
+                given_token: dai(),
+                given_amount: BigUint::from(1000u32),
+                checked_token: weth(),
+                receiver: receiver.clone(),
+                swaps,
+                ..Default::default()
+            };
+
+            let report = encoder
+                .explain_solution(&solution)
+                .unwrap();
+
+            assert!(!report.is_valid());
+            assert_eq!(report.errors.len(), 2);
+            assert_eq!(
+                report.errors[0],
+                "Invalid input: Checked amount or slippage with expected amount must be provided"
+            );
+            assert_eq!(report.strategy, "split");
+            assert_eq!(report.groups.len(), 2);
+            for group in report.groups.iter() {
+                assert_eq!(group.transfer_type, TransferType::TransferFromToProtocol);
+                assert_eq!(group.receiver, receiver);
+            }
+        }
+
+        #[test]
+        fn test_explain_solution_split_permit2() {
+            // Split DAI -> WETH, whose input tokens are transferred with Permit2
+            let encoder = TychoRouterEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                get_swap_encoder_registry(),
+                Some(
+                    "0x123456789abcdef123456789abcdef123456789abcdef123456789abcdef1234"
+                        .to_string(),
+                ),
+                Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+                false,
+                false,
+            )
+            .unwrap();
+            let receiver = Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap();
+            let swaps = vec![
+                Swap {
+                    component: ProtocolComponent {
+                        id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                        protocol_system: "uniswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    token_in: dai(),
+                    token_out: weth(),
+                    split: 0.5f64,
+                    amount_in: None,
+                },
+                Swap {
+                    component: ProtocolComponent {
+                        id: "0xC3D03e4F041Fd4cD388c549Ee2A29a9E5075882f".to_string(),
+                        protocol_system: "sushiswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    token_in: dai(),
+                    token_out: weth(),
+                    split: 0f64,
+                    amount_in: None,
+                },
+            ];
+            let solution = Solution {
+                exact_out: false,
+                given_token: dai(),
+                given_amount: BigUint::from(1000u32),
+                checked_token: weth(),
+                checked_amount: Some(BigUint::from(1u32)),
+                receiver: receiver.clone(),
+                swaps,
+                ..Default::default()
+            };
+
+            let report = encoder
+                .explain_solution(&solution)
+                .unwrap();
+
+            assert!(report.is_valid());
+            assert_eq!(report.strategy, "split");
+            assert_eq!(report.groups.len(), 2);
+            for group in report.groups.iter() {
+                assert_eq!(group.transfer_type, TransferType::TransferPermit2ToProtocol);
+                assert_eq!(group.receiver, receiver);
+            }
+        }
     }
 
     mod executor_encoder {
@@ -860,17 +2844,23 @@ mod tests {
 
         use alloy::hex::encode;
         use num_bigint::{BigInt, BigUint};
+        use rstest::rstest;
         use tycho_common::{models::protocol::ProtocolComponent, Bytes};
 
         use super::*;
-        use crate::encoding::models::{Solution, Swap};
+        use crate::encoding::models::{Solution, Swap, TransferType};
 
         #[test]
         fn test_executor_encoder_encode() {
             let swap_encoder_registry = get_swap_encoder_registry();
-            let encoder =
-                TychoExecutorEncoder::new(TychoCommonChain::Ethereum.into(), swap_encoder_registry)
-                    .unwrap();
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                swap_encoder_registry,
+                false,
+                None,
+                FundingMode::InCaller,
+            )
+            .unwrap();
 
             let token_in = weth();
             let token_out = dai();
@@ -884,6 +2874,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let solution = Solution {
@@ -899,6 +2890,14 @@ mod tests {
                 swaps: vec![swap],
                 slippage: None,
                 native_action: None,
+                fee: None,
+                surplus: None,
+                additional_outputs: vec![],
+                additional_inputs: vec![],
+                deadline: None,
+                pre_swap_actions: vec![],
+                post_swap_actions: vec![],
+                post_swap_min_amount: None,
             };
 
             let transactions = encoder
@@ -929,12 +2928,416 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_executor_encoder_encode_every_solution() {
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                get_swap_encoder_registry(),
+                false,
+                None,
+                FundingMode::InCaller,
+            )
+            .unwrap();
+            let swap = |token_in: Bytes, token_out: Bytes| Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in,
+                token_out,
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = |token_in: Bytes, token_out: Bytes| Solution {
+                exact_out: false,
+                given_token: token_in.clone(),
+                given_amount: BigUint::from(1000000000000000000u64),
+                checked_token: token_out.clone(),
+                checked_amount: Some(BigUint::from(1u64)),
+                receiver: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                swaps: vec![swap(token_in, token_out)],
+                ..Default::default()
+            };
+
+            let transactions = encoder
+                .encode_calldata(vec![solution(weth(), dai()), solution(dai(), weth())])
+                .unwrap();
+
+            assert_eq!(transactions.len(), 2);
+            // The token in of each solution's swap
+            assert_eq!(
+                encode(&transactions[0].data[..20]),
+                "c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"
+            );
+            assert_eq!(
+                encode(&transactions[1].data[..20]),
+                "6b175474e89094c44da98b954eedeac495271d0f"
+            );
+        }
+
+        #[test]
+        fn test_executor_encoder_atomic_calldata_not_supported() {
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                get_swap_encoder_registry(),
+                false,
+                None,
+                FundingMode::InCaller,
+            )
+            .unwrap();
+
+            let result = encoder.encode_atomic_calldata(vec![Solution::default()]);
+            assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+        }
+
+        #[test]
+        fn test_executor_encoder_encode_solutions() {
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                get_swap_encoder_registry(),
+                false,
+                None,
+                FundingMode::InCaller,
+            )
+            .unwrap();
+
+            let swap = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: dai(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                given_amount: BigUint::from(1000000000000000000u64),
+                checked_token: dai(),
+                receiver: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                swaps: vec![swap.clone()],
+                ..Default::default()
+            };
+            let unknown_protocol_solution = Solution {
+                swaps: vec![Swap {
+                    component: ProtocolComponent {
+                        protocol_system: "unknown_protocol".to_string(),
+                        ..Default::default()
+                    },
+                    ..swap
+                }],
+                ..solution.clone()
+            };
+
+            let results = encoder.encode_solutions(vec![
+                unknown_protocol_solution,
+                solution.clone(),
+                solution,
+            ]);
+
+            // The failing solution doesn't prevent the following ones from being encoded
+            assert_eq!(results.len(), 3);
+            assert!(matches!(results[0], Err(EncodingError::InvalidInput(_))));
+            for result in results[1..].iter() {
+                let encoded_solution = result.as_ref().unwrap();
+                assert_eq!(
+                    encoded_solution.transaction.to,
+                    Bytes::from_str("0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f").unwrap()
+                );
+            }
+        }
+
+        #[test]
+        fn test_executor_encoder_sequential_swaps() {
+            // Performs a sequential swap WETH -> DAI -> USDC on two USV2 pools
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                get_swap_encoder_registry(),
+                false,
+                None,
+                FundingMode::InCaller,
+            )
+            .unwrap();
+
+            let swap_weth_dai = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: dai(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let swap_dai_usdc = Swap {
+                component: ProtocolComponent {
+                    id: "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: dai(),
+                token_out: usdc(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                given_amount: BigUint::from(1000000000000000000u64),
+                checked_token: usdc(),
+                sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                receiver: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                swaps: vec![swap_weth_dai, swap_dai_usdc],
+                ..Default::default()
+            };
+
+            let executor_calls = encoder
+                .encode_executor_calls(vec![solution.clone()])
+                .unwrap()
+                .remove(0);
+            assert_eq!(executor_calls.len(), 2);
+            // The output of the first swap is sent directly into the second pool
+            assert_eq!(
+                executor_calls[0].receiver,
+                Bytes::from_str("0xae461ca67b15dc8dc81ce7615e0320da1a9ab8d5").unwrap()
+            );
+            assert_eq!(executor_calls[0].transfer_type, TransferType::TransferToProtocol);
+            assert_eq!(executor_calls[1].receiver, solution.receiver);
+            assert_eq!(executor_calls[1].transfer_type, TransferType::None);
+
+            for executor_call in executor_calls.iter() {
+                assert_eq!(
+                    executor_call.executor_address,
+                    Bytes::from_str("0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f").unwrap()
+                );
+            }
+            assert_eq!(
+                encode(&executor_calls[0].protocol_data),
+                String::from(concat!(
+                    // in token
+                    "c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    // component id
+                    "a478c2975ab1ea89e8196811f51a7b7ade33eb11",
+                    // receiver (next pool)
+                    "ae461ca67b15dc8dc81ce7615e0320da1a9ab8d5",
+                    // zero for one
+                    "00",
+                    // transfer type
+                    "00",
+                ))
+            );
+            assert_eq!(
+                encode(&executor_calls[1].protocol_data),
+                String::from(concat!(
+                    // in token
+                    "6b175474e89094c44da98b954eedeac495271d0f",
+                    // component id
+                    "ae461ca67b15dc8dc81ce7615e0320da1a9ab8d5",
+                    // receiver
+                    "1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e",
+                    // zero for one
+                    "01",
+                    // transfer type
+                    "05",
+                ))
+            );
+        }
+
+        #[test]
+        fn test_executor_encoder_sequential_swaps_single_transaction() {
+            // A sequential swap needs one executor call per swap, so it can't be encoded as a
+            // single transaction
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                get_swap_encoder_registry(),
+                false,
+                None,
+                FundingMode::InCaller,
+            )
+            .unwrap();
+
+            let swap_weth_dai = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: dai(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let swap_dai_usdc = Swap {
+                component: ProtocolComponent {
+                    id: "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: dai(),
+                token_out: usdc(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                given_amount: BigUint::from(1000000000000000000u64),
+                checked_token: usdc(),
+                receiver: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                swaps: vec![swap_weth_dai, swap_dai_usdc],
+                ..Default::default()
+            };
+
+            let result = encoder.encode_calldata(vec![solution.clone()]);
+            assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+
+            let results = encoder.encode_solutions(vec![solution]);
+            assert!(matches!(results[0], Err(EncodingError::InvalidInput(_))));
+        }
+
+        #[test]
+        fn test_executor_encoder_full_calldata() {
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                get_swap_encoder_registry(),
+                true,
+                None,
+                FundingMode::InCaller,
+            )
+            .unwrap();
+
+            let swap = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: dai(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                given_amount: BigUint::from(1000000000000000000u64),
+                checked_token: dai(),
+                receiver: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                swaps: vec![swap],
+                ..Default::default()
+            };
+
+            let transactions = encoder
+                .encode_calldata(vec![solution])
+                .unwrap();
+            assert_eq!(
+                encode(&transactions[0].data),
+                String::from(concat!(
+                    // selector of swap(uint256,bytes)
+                    "bd0625ab",
+                    // given amount
+                    "0000000000000000000000000000000000000000000000000de0b6b3a7640000",
+                    // offset of protocol data
+                    "0000000000000000000000000000000000000000000000000000000000000040",
+                    // length of protocol data
+                    "000000000000000000000000000000000000000000000000000000000000003e",
+                    // in token, component id, receiver, zero for one, transfer type
+                    "c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "a478c2975ab1ea89e8196811f51a7b7ade33eb11",
+                    "1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e",
+                    "00",
+                    "00",
+                    // padding
+                    "0000",
+                ))
+            );
+        }
+
+        #[rstest]
+        #[case::transfer_from(FundingMode::TransferFrom, TransferType::TransferFromToProtocol)]
+        #[case::permit2(FundingMode::Permit2, TransferType::TransferPermit2ToProtocol)]
+        #[case::in_caller(FundingMode::InCaller, TransferType::TransferToProtocol)]
+        fn test_executor_encoder_funding_mode(
+            #[case] funding_mode: FundingMode,
+            #[case] expected_first_transfer_type: TransferType,
+        ) {
+            // Performs a sequential swap WETH -> DAI -> USDC on a USV2 pool and a USV3 pool
+            let caller = Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap();
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                get_swap_encoder_registry(),
+                false,
+                Some(caller.clone()),
+                funding_mode,
+            )
+            .unwrap();
+
+            let swap_weth_dai = Swap {
+                component: ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth(),
+                token_out: dai(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let swap_dai_usdc = Swap {
+                component: ProtocolComponent {
+                    id: "0x5777d92f208679DB4b9778590Fa3CAB3aC9e2168".to_string(),
+                    protocol_system: "uniswap_v3".to_string(),
+                    static_attributes: HashMap::from([(
+                        "fee".to_string(),
+                        Bytes::from(BigInt::from(100).to_signed_bytes_be()),
+                    )]),
+                    ..Default::default()
+                },
+                token_in: dai(),
+                token_out: usdc(),
+                split: 0f64,
+                amount_in: None,
+            };
+            let solution = Solution {
+                exact_out: false,
+                given_token: weth(),
+                given_amount: BigUint::from(1000000000000000000u64),
+                checked_token: usdc(),
+                sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                receiver: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+                swaps: vec![swap_weth_dai, swap_dai_usdc],
+                ..Default::default()
+            };
+
+            let executor_calls = encoder
+                .encode_executor_calls(vec![solution.clone()])
+                .unwrap()
+                .remove(0);
+            assert_eq!(executor_calls.len(), 2);
+            assert_eq!(executor_calls[0].transfer_type, expected_first_transfer_type);
+            // USV3 pools can't receive the tokens of the previous swap directly, so they are sent
+            // back to the caller
+            assert_eq!(executor_calls[0].receiver, caller);
+            assert_eq!(executor_calls[1].transfer_type, TransferType::TransferToProtocol);
+            assert_eq!(executor_calls[1].receiver, solution.receiver);
+        }
+
         #[test]
         fn test_executor_encoder_too_many_swaps() {
             let swap_encoder_registry = get_swap_encoder_registry();
-            let encoder =
-                TychoExecutorEncoder::new(TychoCommonChain::Ethereum.into(), swap_encoder_registry)
-                    .unwrap();
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                swap_encoder_registry,
+                false,
+                None,
+                FundingMode::InCaller,
+            )
+            .unwrap();
 
             let token_in = weth();
             let token_out = dai();
@@ -947,6 +3350,7 @@ mod tests {
                 token_in: token_in.clone(),
                 token_out: token_out.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let solution = Solution {
@@ -961,6 +3365,14 @@ mod tests {
                 swaps: vec![swap.clone(), swap],
                 slippage: None,
                 native_action: None,
+                fee: None,
+                surplus: None,
+                additional_outputs: vec![],
+                additional_inputs: vec![],
+                deadline: None,
+                pre_swap_actions: vec![],
+                post_swap_actions: vec![],
+                post_swap_min_amount: None,
             };
 
             let result = encoder.encode_calldata(vec![solution]);
@@ -970,9 +3382,14 @@ mod tests {
         #[test]
         fn test_executor_encoder_grouped_swaps() {
             let swap_encoder_registry = get_swap_encoder_registry();
-            let encoder =
-                TychoExecutorEncoder::new(TychoCommonChain::Ethereum.into(), swap_encoder_registry)
-                    .unwrap();
+            let encoder = TychoExecutorEncoder::new(
+                TychoCommonChain::Ethereum.into(),
+                swap_encoder_registry,
+                false,
+                None,
+                FundingMode::InCaller,
+            )
+            .unwrap();
 
             let eth = eth();
             let usdc = usdc();
@@ -1004,6 +3421,7 @@ mod tests {
                 token_in: usdc.clone(),
                 token_out: eth.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let swap_eth_pepe = Swap {
@@ -1017,6 +3435,7 @@ mod tests {
                 token_in: eth.clone(),
                 token_out: pepe.clone(),
                 split: 0f64,
+                amount_in: None,
             };
 
             let solution = Solution {
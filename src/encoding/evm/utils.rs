@@ -1,5 +1,8 @@
-use std::{cmp::max, env, sync::Arc};
+use std::{cmp::max, str::FromStr};
+#[cfg(feature = "rpc")]
+use std::{env, sync::Arc};
 
+#[cfg(feature = "rpc")]
 use alloy::{
     providers::{ProviderBuilder, RootProvider},
     transports::BoxTransport,
@@ -7,12 +10,13 @@ use alloy::{
 use alloy_primitives::{aliases::U24, keccak256, Address, FixedBytes, Keccak256, U256, U8};
 use alloy_sol_types::SolValue;
 use num_bigint::BigUint;
+#[cfg(feature = "rpc")]
 use tokio::runtime::{Handle, Runtime};
 use tycho_common::Bytes;
 
 use crate::encoding::{
     errors::EncodingError,
-    models::{Solution, Swap},
+    models::{NativeAction, Solution, Swap},
 };
 
 /// Safely converts a `Bytes` object to an `Address` object.
@@ -27,6 +31,23 @@ pub fn bytes_to_address(address: &Bytes) -> Result<Address, EncodingError> {
     }
 }
 
+/// Parses an executor or router address read from the configuration.
+///
+/// Malformed addresses and the zero address are rejected, since the encoded calldata would target
+/// them.
+pub fn parse_configured_address(
+    description: &str,
+    address: &str,
+) -> Result<Address, EncodingError> {
+    let parsed = Address::from_str(address).map_err(|_| {
+        EncodingError::FatalError(format!("Malformed {}: {}", description, address))
+    })?;
+    if parsed.is_zero() {
+        return Err(EncodingError::FatalError(format!("Invalid {}: the zero address", description)));
+    }
+    Ok(parsed)
+}
+
 /// Converts a general `BigUint` to an EVM-specific `U256` value.
 pub fn biguint_to_u256(value: &BigUint) -> U256 {
     let bytes = value.to_bytes_be();
@@ -64,6 +85,23 @@ pub fn percentage_to_uint24(decimal: f64) -> U24 {
     U24::from(scaled.round())
 }
 
+/// Returns whether the native token is wrapped before the first swap and unwrapped after the last
+/// one, according to the native action of a solution.
+pub fn get_wrap_unwrap(
+    native_action: &Option<NativeAction>,
+) -> Result<(bool, bool), EncodingError> {
+    match native_action {
+        None => Ok((false, false)),
+        Some(NativeAction::Wrap) => Ok((true, false)),
+        Some(NativeAction::Unwrap) => Ok((false, true)),
+        Some(action) => Err(EncodingError::InvalidInput(format!(
+            "Only wrap and unwrap are supported as the native action of a solution, got {:?}. \
+             Use pre- or post-swap actions instead",
+            action
+        ))),
+    }
+}
+
 /// Gets the minimum amount out for a solution to pass when executed on-chain.
 ///
 /// The minimum amount is calculated based on the expected amount and the slippage percentage, if
@@ -127,6 +165,7 @@ pub fn get_static_attribute(swap: &Swap, attribute_name: &str) -> Result<Vec<u8>
         .to_vec())
 }
 
+#[cfg(feature = "rpc")]
 pub fn get_runtime() -> Result<(Handle, Option<Arc<Runtime>>), EncodingError> {
     match Handle::try_current() {
         Ok(h) => Ok((h, None)),
@@ -140,6 +179,7 @@ pub fn get_runtime() -> Result<(Handle, Option<Arc<Runtime>>), EncodingError> {
 }
 
 /// Gets the client used for interacting with the EVM-compatible network.
+#[cfg(feature = "rpc")]
 pub async fn get_client() -> Result<Arc<RootProvider<BoxTransport>>, EncodingError> {
     dotenv::dotenv().ok();
     let eth_rpc_url = env::var("RPC_URL")
@@ -166,6 +206,42 @@ pub fn ple_encode(action_data_array: Vec<Vec<u8>>) -> Vec<u8> {
     encoded_action_data
 }
 
+/// Splits prefix-length encoded data back into its elements. The reverse of [`ple_encode`].
+pub fn ple_decode(encoded: &[u8]) -> Result<Vec<&[u8]>, EncodingError> {
+    let mut elements = vec![];
+    let mut remaining = encoded;
+    while !remaining.is_empty() {
+        if remaining.len() < 2 {
+            return Err(EncodingError::InvalidInput(
+                "Invalid prefix-length encoding: missing length".to_string(),
+            ));
+        }
+        let length = u16::from_be_bytes([remaining[0], remaining[1]]) as usize;
+        if remaining.len() < 2 + length {
+            return Err(EncodingError::InvalidInput(
+                "Invalid prefix-length encoding: element shorter than its length".to_string(),
+            ));
+        }
+        elements.push(&remaining[2..2 + length]);
+        remaining = &remaining[2 + length..];
+    }
+    Ok(elements)
+}
+
+/// Encodes the calldata of an `IExecutor.swap(uint256,bytes)` call, to call or delegatecall an
+/// executor directly.
+pub fn encode_executor_swap(given_amount: &BigUint, protocol_data: Vec<u8>) -> Vec<u8> {
+    encode_input("swap(uint256,bytes)", (biguint_to_u256(given_amount), protocol_data).abi_encode())
+}
+
+/// Encodes the calldata of an `ICallback.handleCallback(bytes)` call.
+///
+/// `callback_data` is the full calldata of the callback received from the protocol (e.g. a
+/// `uniswapV3SwapCallback` call), which the caller forwards to the executor that started the swap.
+pub fn encode_handle_callback(callback_data: Vec<u8>) -> Vec<u8> {
+    encode_input("handleCallback(bytes)", (callback_data,).abi_encode())
+}
+
 #[cfg(test)]
 mod tests {
     use num_bigint::BigUint;
@@ -189,4 +265,89 @@ mod tests {
         let min_amount_out = get_min_amount_for_solution(solution);
         assert_eq!(min_amount_out, BigUint::from(999000000000000000u64));
     }
+
+    #[test]
+    fn test_parse_configured_address() {
+        let address = parse_configured_address(
+            "executor address of uniswap_v2",
+            "0xf6c5be66FFf9DC69962d73da0A617a827c382329",
+        )
+        .unwrap();
+        assert_eq!(
+            address,
+            Address::from_str("0xf6c5be66FFf9DC69962d73da0A617a827c382329").unwrap()
+        );
+
+        let result = parse_configured_address(
+            "executor address of uniswap_v3",
+            "0x0000000000000000000000000000000000000000",
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            EncodingError::FatalError(
+                "Invalid executor address of uniswap_v3: the zero address".to_string()
+            )
+        );
+
+        let result = parse_configured_address("router address", "0x1234");
+        assert_eq!(
+            result.unwrap_err(),
+            EncodingError::FatalError("Malformed router address: 0x1234".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ple_decode() {
+        let elements = vec![vec![0xab, 0xcd], vec![], vec![0x01, 0x02, 0x03]];
+
+        let encoded = ple_encode(elements.clone());
+        let decoded = ple_decode(&encoded).unwrap();
+
+        assert_eq!(decoded, elements);
+    }
+
+    #[test]
+    fn test_ple_decode_truncated() {
+        let result = ple_decode(&[0x00, 0x03, 0xab, 0xcd]);
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_encode_executor_swap() {
+        let calldata = encode_executor_swap(&BigUint::from(1000u32), vec![0xab, 0xcd]);
+        assert_eq!(
+            alloy_primitives::hex::encode(calldata),
+            String::from(concat!(
+                // selector
+                "bd0625ab",
+                // given amount
+                "00000000000000000000000000000000000000000000000000000000000003e8",
+                // offset of data
+                "0000000000000000000000000000000000000000000000000000000000000040",
+                // length of data
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                // data
+                "abcd000000000000000000000000000000000000000000000000000000000000",
+            ))
+        );
+    }
+
+    #[test]
+    fn test_encode_handle_callback() {
+        let calldata = encode_handle_callback(vec![0xab, 0xcd]);
+        assert_eq!(
+            alloy_primitives::hex::encode(calldata),
+            String::from(concat!(
+                // selector
+                "7003fdf4",
+                // offset of data
+                "0000000000000000000000000000000000000000000000000000000000000020",
+                // length of data
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                // data
+                "abcd000000000000000000000000000000000000000000000000000000000000",
+            ))
+        );
+    }
 }
@@ -8,7 +8,7 @@ use tycho_common::{
 
 use crate::encoding::{
     errors::EncodingError,
-    serde_primitives::{biguint_string, biguint_string_option},
+    serde_primitives::{biguint_hex, biguint_string, biguint_string_option, hex_string},
 };
 
 /// Represents a solution containing details describing an order, and  instructions for filling
@@ -43,22 +43,220 @@ pub struct Solution {
     pub swaps: Vec<Swap>,
     /// If set, the corresponding native action will be executed.
     pub native_action: Option<NativeAction>,
+    /// If set, this fee is deducted from the output token by the router before checking the
+    /// minimum amount out.
+    #[serde(default)]
+    pub fee: Option<IntegratorFee>,
+    /// If set, the router applies this policy to the output exceeding `expected_amount`.
+    #[serde(default)]
+    pub surplus: Option<SurplusPolicy>,
+    /// Output tokens bought in addition to `checked_token`, each one with its own minimum amount
+    /// and receiver. Only supported by split swap solutions.
+    #[serde(default)]
+    pub additional_outputs: Vec<SolutionOutput>,
+    /// Input tokens sold in addition to `given_token`, each one with its own amount. Only
+    /// supported by split swap solutions.
+    #[serde(default)]
+    pub additional_inputs: Vec<SolutionInput>,
+    /// If set, the unix timestamp (in seconds) after which the router reverts the transaction.
+    #[serde(default)]
+    pub deadline: Option<u64>,
+    /// Actions executed by the router on the given token before the first swap.
+    #[serde(default)]
+    pub pre_swap_actions: Vec<NativeAction>,
+    /// Actions executed by the router on the checked token after the last swap. Whatever the last
+    /// action outputs is sent to the receiver.
+    #[serde(default)]
+    pub post_swap_actions: Vec<NativeAction>,
+    /// Minimum amount output by the post-swap actions, or sent out by the last one for distribute
+    /// and call actions. Required with vault deposit actions. Otherwise, it defaults to the
+    /// minimum amount out of the swaps, since the other actions don't change the amount.
+    #[serde(default, with = "biguint_string_option")]
+    pub post_swap_min_amount: Option<BigUint>,
+}
+
+/// Represents an additional input token of a solution.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct SolutionInput {
+    /// The token being sold.
+    pub token: Bytes,
+    /// Amount of the token to be sold.
+    #[serde(with = "biguint_string")]
+    pub amount: BigUint,
+}
+
+impl SolutionInput {
+    pub fn new(token: Bytes, amount: BigUint) -> Self {
+        Self { token, amount }
+    }
+}
+
+/// Represents an additional output token of a solution.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct SolutionOutput {
+    /// The token being bought.
+    pub token: Bytes,
+    /// Minimum amount of the token to be received.
+    #[serde(with = "biguint_string")]
+    pub min_amount: BigUint,
+    /// Address receiving the token.
+    pub receiver: Bytes,
+}
+
+impl SolutionOutput {
+    pub fn new(token: Bytes, min_amount: BigUint, receiver: Bytes) -> Self {
+        Self { token, min_amount, receiver }
+    }
+}
+
+/// Represents a fee charged by an integrator on the output token of a solution.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct IntegratorFee {
+    /// Fee in basis points of the output amount (for example, 25 means 0.25%).
+    pub bps: u16,
+    /// Address receiving the fee.
+    pub recipient: Bytes,
+}
+
+impl IntegratorFee {
+    pub fn new(bps: u16, recipient: Bytes) -> Self {
+        Self { bps, recipient }
+    }
+}
+
+/// Represents how the output exceeding a solution's `expected_amount` (positive slippage) is
+/// distributed.
+///
+/// `Cap` means that the receiver gets at most `expected_amount` and all the excess is sent to the
+/// `recipient`. `Share` means that `bps` of the excess is sent to the `recipient` and the rest to
+/// the receiver. Surplus is captured before any integrator fee is charged.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SurplusPolicy {
+    Cap { recipient: Bytes },
+    Share { recipient: Bytes, bps: u16 },
+}
+
+impl SurplusPolicy {
+    /// Returns the recipient of the surplus.
+    pub fn recipient(&self) -> &Bytes {
+        match self {
+            SurplusPolicy::Cap { recipient } | SurplusPolicy::Share { recipient, .. } => recipient,
+        }
+    }
+
+    /// Returns the share of the surplus sent to the recipient, in basis points.
+    pub fn share_bps(&self) -> u16 {
+        match self {
+            SurplusPolicy::Cap { .. } => 10_000,
+            SurplusPolicy::Share { bps, .. } => *bps,
+        }
+    }
 }
 
-/// Represents an action to be performed on the native token either before or after the swap.
+/// Represents an action executed by the router on the funds it holds, before the first swap or
+/// after the last one.
+///
+/// As the `native_action` of a solution, only `Wrap` and `Unwrap` are supported: `Wrap` means that
+/// the native token will be wrapped before the first swap, and `Unwrap` means that the native token
+/// will be unwrapped after the last swap, before being sent to the receiver.
 ///
-/// `Wrap` means that the native token will be wrapped before the first swap, and `Unwrap`
-/// means that the native token will be unwrapped after the last swap, before being sent to the
-/// receiver.
+/// As pre- and post-swap actions, all the actions are supported. They are executed in order, each
+/// one on the output of the previous one. `Distribute` and `Call` send out all the funds they
+/// receive, so they can only be the last action of a solution.
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum NativeAction {
+    /// Wraps the native token.
     Wrap,
+    /// Unwraps the wrapped native token.
     Unwrap,
+    /// Deposits the funds into an ERC-4626 vault. The vault shares are the output.
+    VaultDeposit { vault: Bytes },
+    /// Sends the funds to several recipients. The shares must add up to 10000 bps.
+    Distribute { recipients: Vec<ActionRecipient> },
+    /// Approves the funds to a contract whitelisted in the router and calls it with `calldata`.
+    /// The contract is expected to pull all the funds.
+    Call { target: Bytes, calldata: Bytes },
+}
+
+impl NativeAction {
+    /// Returns the token held by the router after executing the action on `token_in`, or `None`
+    /// if the action sends out all the funds it receives.
+    pub fn output_token(
+        &self,
+        token_in: &Bytes,
+        native_address: &Bytes,
+        wrapped_address: &Bytes,
+    ) -> Result<Option<Bytes>, EncodingError> {
+        match self {
+            NativeAction::Wrap => {
+                if token_in != native_address {
+                    return Err(EncodingError::FatalError(format!(
+                        "Wrap action expects the native token as input, got {}",
+                        token_in
+                    )));
+                }
+                Ok(Some(wrapped_address.clone()))
+            }
+            NativeAction::Unwrap => {
+                if token_in != wrapped_address {
+                    return Err(EncodingError::FatalError(format!(
+                        "Unwrap action expects the wrapped native token as input, got {}",
+                        token_in
+                    )));
+                }
+                Ok(Some(native_address.clone()))
+            }
+            NativeAction::VaultDeposit { vault } => {
+                if token_in == native_address {
+                    return Err(EncodingError::FatalError(
+                        "Vault deposit action does not support the native token".to_string(),
+                    ));
+                }
+                Ok(Some(vault.clone()))
+            }
+            NativeAction::Distribute { .. } | NativeAction::Call { .. } => Ok(None),
+        }
+    }
+}
+
+/// Represents a recipient of a `Distribute` action.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ActionRecipient {
+    /// Address receiving the funds.
+    pub recipient: Bytes,
+    /// Share of the funds in basis points (for example, 2500 means 25%).
+    pub bps: u16,
+}
+
+impl ActionRecipient {
+    pub fn new(recipient: Bytes, bps: u16) -> Self {
+        Self { recipient, bps }
+    }
+}
+
+/// Represents how the input tokens reach the contract calling the executors directly.
+///
+/// * `InCaller`: The input tokens are already held by the calling contract.
+/// * `TransferFrom`: The input tokens are transferred from the swapper with a `transferFrom`, using
+///   an allowance given to the calling contract.
+/// * `Permit2`: The input tokens are transferred from the swapper using a Permit2 allowance given
+///   to the calling contract.
+#[derive(Clone, Copy, Default, PartialEq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FundingMode {
+    #[default]
+    InCaller,
+    TransferFrom,
+    Permit2,
 }
 
 /// Represents a swap operation to be performed on a pool.
+///
+/// Build it with `Swap::new`, and `with_amount_in` to swap an absolute amount.
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct Swap {
     /// Protocol component from tycho indexer
     pub component: ProtocolComponent,
@@ -69,6 +267,10 @@ pub struct Swap {
     /// Decimal of the amount to be swapped in this operation (for example, 0.5 means 50%)
     #[serde(default)]
     pub split: f64,
+    /// Absolute amount of `token_in` to be swapped in this operation. If set, it takes precedence
+    /// over `split`, which must then be 0. Only supported by split swap solutions.
+    #[serde(default, with = "biguint_string_option")]
+    pub amount_in: Option<BigUint>,
 }
 
 impl Swap {
@@ -78,7 +280,13 @@ impl Swap {
         token_out: Bytes,
         split: f64,
     ) -> Self {
-        Self { component: component.into(), token_in, token_out, split }
+        Self { component: component.into(), token_in, token_out, split, amount_in: None }
+    }
+
+    /// Sets an absolute input amount for this swap, to be used instead of a split percentage.
+    pub fn with_amount_in(mut self, amount_in: BigUint) -> Self {
+        self.amount_in = Some(amount_in);
+        self
     }
 }
 
@@ -89,13 +297,87 @@ impl Swap {
 /// * `value`: Native token value to be sent with the transaction.
 /// * `data`: Encoded calldata for the transaction.
 /// * `selector`: Only relevant for direct executions. The selector of the function to be called.
-#[derive(Clone, Debug)]
+///
+/// Serializes to `{"to", "value", "data"}`, as `0x`-prefixed hex strings.
+#[derive(Clone, Debug, Serialize)]
 pub struct Transaction {
+    #[serde(serialize_with = "hex_string::serialize")]
     pub to: Bytes,
+    #[serde(serialize_with = "biguint_hex::serialize")]
     pub value: BigUint,
+    #[serde(serialize_with = "hex_string::serialize")]
     pub data: Vec<u8>,
 }
 
+/// Represents a solution that was successfully encoded as part of a batch.
+///
+/// # Fields
+/// * `transaction`: Transaction executing the solution.
+#[derive(Clone, Debug)]
+pub struct EncodedSolution {
+    pub transaction: Transaction,
+}
+
+/// Represents a call to an executor, as part of a solution executed without the Tycho router.
+///
+/// # Fields
+/// * `executor_address`: Address of the executor to call.
+/// * `protocol_data`: Encoded protocol data to pass to the executor.
+/// * `transfer_type`: How the input tokens are transferred into the pool.
+/// * `receiver`: Address receiving the output tokens of the call. This is either the next pool, the
+///   caller or the solution's receiver.
+#[derive(Clone, Debug)]
+pub struct ExecutorCall {
+    pub executor_address: Bytes,
+    pub protocol_data: Vec<u8>,
+    pub transfer_type: TransferType,
+    pub receiver: Bytes,
+}
+
+/// Represents the outcome of validating a solution, explaining how it would be encoded.
+///
+/// # Fields
+/// * `errors`: All the problems found in the solution. The solution can be encoded if there are
+///   none.
+/// * `strategy`: Name of the swap strategy which would encode the solution.
+/// * `groups`: The swap groups which would be encoded, in execution order.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SolutionReport {
+    pub errors: Vec<String>,
+    pub strategy: String,
+    pub groups: Vec<SwapGroupReport>,
+}
+
+impl SolutionReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Represents a group of swaps executed in a single executor call, as part of a `SolutionReport`.
+///
+/// # Fields
+/// * `protocol_system`: Protocol system of the swaps of the group.
+/// * `component_ids`: Ids of the components swapped through, in order.
+/// * `token_in`: Input token of the first swap of the group.
+/// * `token_out`: Output token of the last swap of the group.
+/// * `split`: Split of the first swap of the group.
+/// * `amount_in`: Absolute input amount of the first swap of the group, if any.
+/// * `transfer_type`: How the input tokens are transferred into the pool.
+/// * `receiver`: Address receiving the output tokens of the group.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SwapGroupReport {
+    pub protocol_system: String,
+    pub component_ids: Vec<String>,
+    pub token_in: Bytes,
+    pub token_out: Bytes,
+    pub split: f64,
+    #[serde(with = "biguint_string_option")]
+    pub amount_in: Option<BigUint>,
+    pub transfer_type: TransferType,
+    pub receiver: Bytes,
+}
+
 /// Represents the type of transfer to be performed into the pool.
 ///
 /// # Fields
@@ -107,7 +389,7 @@ pub struct Transaction {
 /// * `TransferPermit2ToRouter`: Transfer the token from the sender to the router using Permit2.
 /// * `None`: No transfer is needed. Tokens are already in the pool.
 #[repr(u8)]
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum TransferType {
     TransferToProtocol = 0,
     TransferFromToProtocol = 1,
@@ -233,4 +515,21 @@ mod tests {
         assert_eq!(swap.component.protocol_system, "uniswap_v2");
         assert_eq!(swap.component.id, "i-am-an-id");
     }
+
+    #[test]
+    fn test_transaction_serialize() {
+        let transaction = Transaction {
+            to: Bytes::from("0x3ede3eca2a72b3aecc820e955b36f38437d01395"),
+            value: BigUint::from(1000u32),
+            data: vec![0x12, 0x34],
+        };
+        assert_eq!(
+            serde_json::to_value(&transaction).unwrap(),
+            serde_json::json!({
+                "to": "0x3ede3eca2a72b3aecc820e955b36f38437d01395",
+                "value": "0x03e8",
+                "data": "0x1234",
+            })
+        );
+    }
 }
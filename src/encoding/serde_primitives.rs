@@ -58,3 +58,28 @@ pub mod biguint_string_option {
             .transpose()
     }
 }
+
+/// Serializes a `BigUint` as a `0x`-prefixed big-endian hex string, e.g. a transaction value.
+pub mod biguint_hex {
+    use super::*;
+
+    pub fn serialize<S>(value: &BigUint, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(value.to_bytes_be())))
+    }
+}
+
+/// Serializes bytes as a `0x`-prefixed hex string.
+pub mod hex_string {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]>,
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(value)))
+    }
+}
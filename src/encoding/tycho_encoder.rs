@@ -1,6 +1,6 @@
 use crate::encoding::{
     errors::EncodingError,
-    models::{Solution, Transaction},
+    models::{EncodedSolution, Solution, SolutionReport, Transaction},
 };
 
 /// A high-level encoder that converts solutions into executable transactions. Allows for modularity
@@ -16,5 +16,68 @@ pub trait TychoEncoder {
     /// * `Result<Vec<Transaction>, EncodingError>` - Vector of executable transactions
     fn encode_calldata(&self, solutions: Vec<Solution>) -> Result<Vec<Transaction>, EncodingError>;
 
+    /// Encodes each solution independently, so that a solution that fails to encode doesn't
+    /// prevent the others from being encoded.
+    ///
+    /// # Arguments
+    /// * `solutions` - Vector of solutions to encode
+    ///
+    /// # Returns
+    /// * `Vec<Result<EncodedSolution, EncodingError>>` - The result of encoding each solution, in
+    ///   the same order as the given solutions
+    fn encode_solutions(
+        &self,
+        solutions: Vec<Solution>,
+    ) -> Vec<Result<EncodedSolution, EncodingError>> {
+        solutions
+            .into_iter()
+            .map(|solution| {
+                let mut transactions = self.encode_calldata(vec![solution])?;
+                if transactions.len() != 1 {
+                    return Err(EncodingError::FatalError(format!(
+                        "Expected a single transaction for the solution, got {}",
+                        transactions.len()
+                    )));
+                }
+                Ok(EncodedSolution { transaction: transactions.remove(0) })
+            })
+            .collect()
+    }
+
+    /// Encodes solutions into a single transaction that executes all of them atomically: if any
+    /// of them fails, the whole transaction reverts.
+    ///
+    /// # Arguments
+    /// * `solutions` - Vector of solutions to encode. They must all have the same sender.
+    ///
+    /// # Returns
+    /// * `Result<Transaction, EncodingError>` - A single executable transaction
+    fn encode_atomic_calldata(
+        &self,
+        _solutions: Vec<Solution>,
+    ) -> Result<Transaction, EncodingError> {
+        Err(EncodingError::FatalError(
+            "Atomic batching is not supported by this encoder".to_string(),
+        ))
+    }
+
     fn validate_solution(&self, solution: &Solution) -> Result<(), EncodingError>;
+
+    /// Validates a solution without encoding it, and explains how it would be encoded.
+    ///
+    /// Unlike `validate_solution`, all the problems found in the solution are reported, not only
+    /// the first one.
+    ///
+    /// # Arguments
+    /// * `solution` - Solution to validate
+    ///
+    /// # Returns
+    /// * `Result<SolutionReport, EncodingError>` - The problems found in the solution, the swap
+    ///   strategy which would encode it, and the transfer type and receiver of each of its swap
+    ///   groups
+    fn explain_solution(&self, _solution: &Solution) -> Result<SolutionReport, EncodingError> {
+        Err(EncodingError::FatalError(
+            "Explaining solutions is not supported by this encoder".to_string(),
+        ))
+    }
 }
@@ -1 +1,5 @@
 pub mod encoding;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "wasm")]
+mod wasm;
@@ -0,0 +1,383 @@
+//! Python bindings of the encoders, built with maturin (see `pyproject.toml`).
+//!
+//! Solutions and swaps are built from dicts with the same format as the JSON read by
+//! `tycho-encode`: addresses are hex strings and amounts are decimal strings.
+
+use pyo3::{
+    create_exception,
+    exceptions::PyException,
+    prelude::*,
+    types::{PyDict, PyList},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tycho_common::{models::Chain, Bytes};
+
+use crate::encoding::{
+    errors,
+    evm::encoder_builders::{TychoExecutorEncoderBuilder, TychoRouterEncoderBuilder},
+    models::{FundingMode, Solution, Swap},
+    tycho_encoder::TychoEncoder,
+};
+
+create_exception!(
+    tycho_execution,
+    EncodingError,
+    PyException,
+    "Base class of the encoding errors."
+);
+create_exception!(
+    tycho_execution,
+    InvalidInputError,
+    EncodingError,
+    "The encoding failed due to bad input parameters."
+);
+create_exception!(
+    tycho_execution,
+    FatalError,
+    EncodingError,
+    "The encoding failed due to a problem with the encoder setup."
+);
+create_exception!(
+    tycho_execution,
+    RecoverableError,
+    EncodingError,
+    "The encoding failed due to a temporary issue. Retrying later may succeed."
+);
+
+impl From<errors::EncodingError> for PyErr {
+    fn from(err: errors::EncodingError) -> Self {
+        match err {
+            errors::EncodingError::InvalidInput(message) => InvalidInputError::new_err(message),
+            errors::EncodingError::FatalError(message) => FatalError::new_err(message),
+            errors::EncodingError::RecoverableError(message) => RecoverableError::new_err(message),
+        }
+    }
+}
+
+/// Deserializes a Python object through its JSON representation.
+fn from_py<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json: String = value
+        .py()
+        .import_bound("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    Ok(serde_json::from_str(&json)
+        .map_err(|e| errors::EncodingError::InvalidInput(format!("Invalid value: {}", e)))?)
+}
+
+/// Serializes a value into a Python object through its JSON representation.
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| {
+        errors::EncodingError::FatalError(format!("Failed to serialize value: {}", e))
+    })?;
+    Ok(py
+        .import_bound("json")?
+        .call_method1("loads", (json,))?
+        .unbind())
+}
+
+/// Deserializes a value from its JSON string representation, e.g. a chain or an address.
+fn from_py_str<T: DeserializeOwned>(value: &str) -> PyResult<T> {
+    Ok(serde_json::from_value(serde_json::Value::String(value.to_string())).map_err(|e| {
+        errors::EncodingError::InvalidInput(format!("Invalid value {}: {}", value, e))
+    })?)
+}
+
+/// A swap of a solution.
+#[pyclass(name = "Swap", module = "tycho_execution")]
+#[derive(Clone)]
+struct PySwap {
+    swap: Swap,
+}
+
+#[pymethods]
+impl PySwap {
+    #[staticmethod]
+    fn from_dict(value: &Bound<'_, PyDict>) -> PyResult<Self> {
+        Ok(PySwap { swap: from_py(value.as_any())? })
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.swap)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Swap({} {} -> {})",
+            self.swap.component.protocol_system, self.swap.token_in, self.swap.token_out
+        )
+    }
+}
+
+/// A solution to encode. Its `swaps` can be given as dicts or as `Swap` objects.
+#[pyclass(name = "Solution", module = "tycho_execution")]
+#[derive(Clone)]
+struct PySolution {
+    solution: Solution,
+}
+
+#[pymethods]
+impl PySolution {
+    #[staticmethod]
+    fn from_dict(value: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let value = value.copy()?;
+        if let Some(swaps) = value.get_item("swaps")? {
+            let swaps = swaps.downcast::<PyList>()?;
+            let swap_dicts = PyList::empty_bound(value.py());
+            for swap in swaps.iter() {
+                match swap.downcast::<PySwap>() {
+                    Ok(swap) => swap_dicts.append(swap.borrow().to_dict(value.py())?)?,
+                    Err(_) => swap_dicts.append(swap)?,
+                }
+            }
+            value.set_item("swaps", swap_dicts)?;
+        }
+        Ok(PySolution { solution: from_py(value.as_any())? })
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.solution)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Solution({} {} -> {}, {} swaps)",
+            self.solution.given_amount,
+            self.solution.given_token,
+            self.solution.checked_token,
+            self.solution.swaps.len()
+        )
+    }
+}
+
+/// An encoder built by one of the builders.
+#[pyclass(name = "TychoEncoder", module = "tycho_execution")]
+struct PyTychoEncoder {
+    encoder: Box<dyn TychoEncoder + Send + Sync>,
+}
+
+#[pymethods]
+impl PyTychoEncoder {
+    /// Encodes the solutions into transactions, returned as dicts with the `to`, `value` and
+    /// `data` hex strings.
+    fn encode_calldata(
+        &self,
+        py: Python<'_>,
+        solutions: Vec<PyRef<'_, PySolution>>,
+    ) -> PyResult<Vec<PyObject>> {
+        let solutions: Vec<Solution> = solutions
+            .iter()
+            .map(|solution| solution.solution.clone())
+            .collect();
+        let transactions = py.allow_threads(|| self.encoder.encode_calldata(solutions))?;
+        transactions
+            .iter()
+            .map(|transaction| to_py(py, transaction))
+            .collect()
+    }
+
+    /// Raises an `EncodingError` if the solution is not valid.
+    fn validate_solution(&self, solution: PyRef<'_, PySolution>) -> PyResult<()> {
+        Ok(self
+            .encoder
+            .validate_solution(&solution.solution)?)
+    }
+}
+
+/// Builds an encoder for the Tycho router.
+#[pyclass(name = "TychoRouterEncoderBuilder", module = "tycho_execution")]
+#[derive(Default)]
+struct PyTychoRouterEncoderBuilder {
+    chain: Option<Chain>,
+    executors_file_path: Option<String>,
+    router_address: Option<Bytes>,
+    swapper_pk: Option<String>,
+    token_in_already_in_router: Option<bool>,
+    optimize_routes: Option<bool>,
+    allow_unchecked_addresses: Option<bool>,
+    verify_router: Option<bool>,
+}
+
+#[pymethods]
+impl PyTychoRouterEncoderBuilder {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn chain<'py>(mut slf: PyRefMut<'py, Self>, chain: &str) -> PyResult<PyRefMut<'py, Self>> {
+        slf.chain = Some(from_py_str(chain)?);
+        Ok(slf)
+    }
+
+    fn executors_file_path(mut slf: PyRefMut<'_, Self>, path: String) -> PyRefMut<'_, Self> {
+        slf.executors_file_path = Some(path);
+        slf
+    }
+
+    fn router_address<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        address: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.router_address = Some(from_py_str(address)?);
+        Ok(slf)
+    }
+
+    fn swapper_pk(mut slf: PyRefMut<'_, Self>, swapper_pk: String) -> PyRefMut<'_, Self> {
+        slf.swapper_pk = Some(swapper_pk);
+        slf
+    }
+
+    fn token_in_already_in_router(
+        mut slf: PyRefMut<'_, Self>,
+        token_in_already_in_router: bool,
+    ) -> PyRefMut<'_, Self> {
+        slf.token_in_already_in_router = Some(token_in_already_in_router);
+        slf
+    }
+
+    fn optimize_routes(mut slf: PyRefMut<'_, Self>, optimize_routes: bool) -> PyRefMut<'_, Self> {
+        slf.optimize_routes = Some(optimize_routes);
+        slf
+    }
+
+    fn allow_unchecked_addresses(
+        mut slf: PyRefMut<'_, Self>,
+        allow_unchecked_addresses: bool,
+    ) -> PyRefMut<'_, Self> {
+        slf.allow_unchecked_addresses = Some(allow_unchecked_addresses);
+        slf
+    }
+
+    fn verify_router(mut slf: PyRefMut<'_, Self>, verify_router: bool) -> PyRefMut<'_, Self> {
+        slf.verify_router = Some(verify_router);
+        slf
+    }
+
+    fn build(&self) -> PyResult<PyTychoEncoder> {
+        let mut builder = TychoRouterEncoderBuilder::new();
+        if let Some(chain) = self.chain {
+            builder = builder.chain(chain);
+        }
+        if let Some(path) = self.executors_file_path.clone() {
+            builder = builder.executors_file_path(path);
+        }
+        if let Some(router_address) = self.router_address.clone() {
+            builder = builder.router_address(router_address);
+        }
+        if let Some(swapper_pk) = self.swapper_pk.clone() {
+            builder = builder.swapper_pk(swapper_pk);
+        }
+        if let Some(token_in_already_in_router) = self.token_in_already_in_router {
+            builder = builder.token_in_already_in_router(token_in_already_in_router);
+        }
+        if let Some(optimize_routes) = self.optimize_routes {
+            builder = builder.optimize_routes(optimize_routes);
+        }
+        if let Some(allow_unchecked_addresses) = self.allow_unchecked_addresses {
+            builder = builder.allow_unchecked_addresses(allow_unchecked_addresses);
+        }
+        if let Some(verify_router) = self.verify_router {
+            builder = builder.verify_router(verify_router);
+        }
+        Ok(PyTychoEncoder { encoder: builder.build()? })
+    }
+}
+
+/// Builds an encoder for direct executor calls.
+#[pyclass(name = "TychoExecutorEncoderBuilder", module = "tycho_execution")]
+#[derive(Default)]
+struct PyTychoExecutorEncoderBuilder {
+    chain: Option<Chain>,
+    executors_file_path: Option<String>,
+    full_calldata: Option<bool>,
+    caller_address: Option<Bytes>,
+    funding_mode: Option<FundingMode>,
+    allow_unchecked_addresses: Option<bool>,
+}
+
+#[pymethods]
+impl PyTychoExecutorEncoderBuilder {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn chain<'py>(mut slf: PyRefMut<'py, Self>, chain: &str) -> PyResult<PyRefMut<'py, Self>> {
+        slf.chain = Some(from_py_str(chain)?);
+        Ok(slf)
+    }
+
+    fn executors_file_path(mut slf: PyRefMut<'_, Self>, path: String) -> PyRefMut<'_, Self> {
+        slf.executors_file_path = Some(path);
+        slf
+    }
+
+    fn full_calldata(mut slf: PyRefMut<'_, Self>, full_calldata: bool) -> PyRefMut<'_, Self> {
+        slf.full_calldata = Some(full_calldata);
+        slf
+    }
+
+    fn caller_address<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        address: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.caller_address = Some(from_py_str(address)?);
+        Ok(slf)
+    }
+
+    /// Sets the funding mode by its name, as serialized in JSON.
+    fn funding_mode<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        funding_mode: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.funding_mode = Some(from_py_str(funding_mode)?);
+        Ok(slf)
+    }
+
+    fn allow_unchecked_addresses(
+        mut slf: PyRefMut<'_, Self>,
+        allow_unchecked_addresses: bool,
+    ) -> PyRefMut<'_, Self> {
+        slf.allow_unchecked_addresses = Some(allow_unchecked_addresses);
+        slf
+    }
+
+    fn build(&self) -> PyResult<PyTychoEncoder> {
+        let mut builder = TychoExecutorEncoderBuilder::new();
+        if let Some(chain) = self.chain {
+            builder = builder.chain(chain);
+        }
+        if let Some(path) = self.executors_file_path.clone() {
+            builder = builder.executors_file_path(path);
+        }
+        if let Some(full_calldata) = self.full_calldata {
+            builder = builder.full_calldata(full_calldata);
+        }
+        if let Some(caller_address) = self.caller_address.clone() {
+            builder = builder.caller_address(caller_address);
+        }
+        if let Some(funding_mode) = self.funding_mode {
+            builder = builder.funding_mode(funding_mode);
+        }
+        if let Some(allow_unchecked_addresses) = self.allow_unchecked_addresses {
+            builder = builder.allow_unchecked_addresses(allow_unchecked_addresses);
+        }
+        Ok(PyTychoEncoder { encoder: builder.build()? })
+    }
+}
+
+#[pymodule]
+fn tycho_execution(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("EncodingError", py.get_type_bound::<EncodingError>())?;
+    m.add("InvalidInputError", py.get_type_bound::<InvalidInputError>())?;
+    m.add("FatalError", py.get_type_bound::<FatalError>())?;
+    m.add("RecoverableError", py.get_type_bound::<RecoverableError>())?;
+    m.add_class::<PySwap>()?;
+    m.add_class::<PySolution>()?;
+    m.add_class::<PyTychoEncoder>()?;
+    m.add_class::<PyTychoRouterEncoderBuilder>()?;
+    m.add_class::<PyTychoExecutorEncoderBuilder>()?;
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! WebAssembly bindings of the encoders, built as a `cdylib` with `wasm-bindgen` (see the README).
+//!
+//! Only the encoding paths that need no on-chain lookups are available: the crate is built without
+//! the `rpc` feature, so Permit2 approvals and the protocols whose encoders query the chain (see
+//! `RPC_PROTOCOL_SYSTEMS`) are not supported.
+
+use tycho_common::{models::Chain, Bytes};
+use wasm_bindgen::prelude::*;
+
+use crate::encoding::{
+    errors::EncodingError, evm::encoder_builders::TychoRouterEncoderBuilder, models::Solution,
+};
+
+/// Deserializes a value from its JSON string representation, e.g. a chain or an address.
+fn from_js_str<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, EncodingError> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|e| EncodingError::InvalidInput(format!("Invalid value {}: {}", value, e)))
+}
+
+/// Encodes solutions for the Tycho router of the given chain.
+///
+/// `solutions` is a JSON array of solutions, in the same format as read by `tycho-encode`. The
+/// router address defaults to the router deployed by Tycho on the chain, and the executors to the
+/// default executors. Returns a JSON array of `{"to", "value", "data"}` transactions, as hex
+/// strings.
+#[wasm_bindgen(js_name = encodeCalldata)]
+pub fn encode_calldata(
+    chain: &str,
+    solutions: &str,
+    router_address: Option<String>,
+) -> Result<String, JsError> {
+    let mut builder = TychoRouterEncoderBuilder::new().chain(from_js_str::<Chain>(chain)?);
+    if let Some(router_address) = router_address {
+        builder = builder.router_address(from_js_str::<Bytes>(&router_address)?);
+    }
+    let encoder = builder.build()?;
+
+    let solutions: Vec<Solution> = serde_json::from_str(solutions)
+        .map_err(|e| EncodingError::InvalidInput(format!("Invalid solutions: {}", e)))?;
+    let transactions = encoder.encode_calldata(solutions)?;
+    Ok(serde_json::to_string(&transactions)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::BigUint;
+    use tycho_common::models::protocol::ProtocolComponent;
+
+    use super::*;
+    use crate::encoding::models::Swap;
+
+    #[test]
+    fn test_encode_calldata() {
+        // Sells 1 WETH for DAI on a Uniswap V2 pool
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let receiver = Bytes::from_str("0xcd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2").unwrap();
+        let solution = Solution {
+            sender: receiver.clone(),
+            receiver,
+            given_token: weth.clone(),
+            given_amount: BigUint::from(1_000_000_000_000_000_000u64),
+            checked_token: dai.clone(),
+            checked_amount: Some(BigUint::from(1u32)),
+            swaps: vec![Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth,
+                dai,
+                0f64,
+            )],
+            ..Default::default()
+        };
+        let router_address = "0x3ede3eca2a72b3aecc820e955b36f38437d01395";
+
+        let transactions = encode_calldata(
+            "ethereum",
+            &serde_json::to_string(&vec![solution]).unwrap(),
+            Some(router_address.to_string()),
+        )
+        .unwrap_or_else(|_| panic!("Failed to encode calldata"));
+
+        let transactions: serde_json::Value = serde_json::from_str(&transactions).unwrap();
+        assert_eq!(transactions.as_array().unwrap().len(), 1);
+        assert_eq!(transactions[0]["to"], router_address);
+        assert_eq!(transactions[0]["value"], "0x00");
+        // singleSwap selector
+        assert!(transactions[0]["data"]
+            .as_str()
+            .unwrap()
+            .starts_with("0x20144a07"));
+    }
+}
@@ -0,0 +1,124 @@
+"""Tests of the Python bindings, runnable offline.
+
+Build the bindings with `maturin develop` and run `pytest tests/python`.
+"""
+
+from pathlib import Path
+
+import pytest
+
+from tycho_execution import (
+    EncodingError,
+    InvalidInputError,
+    Solution,
+    Swap,
+    TychoExecutorEncoderBuilder,
+    TychoRouterEncoderBuilder,
+)
+
+EXECUTORS_FILE = str(Path(__file__).parents[2] / "config" / "test_executor_addresses.json")
+ROUTER = "0x3ede3eca2a72b3aecc820e955b36f38437d01395"
+RECEIVER = "0xcd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2"
+WETH = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"
+DAI = "0x6b175474e89094c44da98b954eedeac495271d0f"
+
+
+def swap_dict():
+    return {
+        "component": {
+            "id": "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11",
+            "protocol_system": "uniswap_v2",
+            "protocol_type_name": "uniswap_v2_pool",
+            "chain": "ethereum",
+            "tokens": [DAI, WETH],
+            "contract_ids": [],
+            "static_attributes": {},
+        },
+        "token_in": WETH,
+        "token_out": DAI,
+        "split": 0.0,
+    }
+
+
+def solution_dict(swaps):
+    return {
+        "sender": RECEIVER,
+        "receiver": RECEIVER,
+        "given_token": WETH,
+        "given_amount": "1000000000000000000",
+        "checked_token": DAI,
+        "exact_out": False,
+        "slippage": None,
+        "expected_amount": None,
+        "checked_amount": "1",
+        "swaps": swaps,
+    }
+
+
+def router_encoder():
+    return (
+        TychoRouterEncoderBuilder()
+        .chain("ethereum")
+        .executors_file_path(EXECUTORS_FILE)
+        .router_address(ROUTER)
+        .build()
+    )
+
+
+def test_encode_router_calldata():
+    solution = Solution.from_dict(solution_dict([swap_dict()]))
+
+    transactions = router_encoder().encode_calldata([solution])
+
+    assert len(transactions) == 1
+    assert transactions[0]["to"] == ROUTER
+    assert transactions[0]["value"] == "0x00"
+    assert transactions[0]["data"].startswith("0x")
+
+
+def test_solution_from_swap_objects():
+    swap = Swap.from_dict(swap_dict())
+    solution = Solution.from_dict(solution_dict([swap]))
+
+    assert solution.to_dict()["swaps"][0]["token_in"] == WETH
+    router_encoder().validate_solution(solution)
+
+
+def test_encode_executor_calldata():
+    encoder = (
+        TychoExecutorEncoderBuilder()
+        .chain("ethereum")
+        .executors_file_path(EXECUTORS_FILE)
+        .build()
+    )
+    solution = Solution.from_dict(solution_dict([swap_dict()]))
+
+    transactions = encoder.encode_calldata([solution])
+
+    assert transactions[0]["to"] == "0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f"
+
+
+def test_validate_solution_raises_encoding_error():
+    solution = Solution.from_dict(solution_dict([]))
+
+    with pytest.raises(EncodingError, match="No swaps found in solution"):
+        router_encoder().validate_solution(solution)
+
+
+def test_invalid_dict_raises_invalid_input_error():
+    with pytest.raises(InvalidInputError):
+        Solution.from_dict({"sender": RECEIVER})
+
+
+def test_allow_unchecked_addresses():
+    zero_address = "0x0000000000000000000000000000000000000000"
+    builder = (
+        TychoRouterEncoderBuilder()
+        .chain("ethereum")
+        .executors_file_path(EXECUTORS_FILE)
+        .router_address(zero_address)
+    )
+
+    with pytest.raises(EncodingError, match="Invalid router address: the zero address"):
+        builder.build()
+    builder.allow_unchecked_addresses(True).build()